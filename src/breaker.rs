@@ -0,0 +1,291 @@
+//! A builder configuring everything needed to follow a generator, for
+//! embedders which would rather not wire up predictors and trackers.
+
+use crate::{
+    scale_output, BitLeak, CancellationToken, Candidates, Derivation, Inconsistency, LookupTable,
+    Observer, Pcg32State, Prediction, SolverConfig, SolverRegistry, Tracker,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+
+/// Number of candidates from which they are enumerated by default, as with
+/// pcg-breaker's `--threshold`.
+const DEFAULT_THRESHOLD: usize = 1000;
+
+/// How the target generator's outputs are observed once the four outputs
+/// initializing the predictor have been given as they are.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ObservationModel {
+    /// Outputs are observed as they are.
+    Outputs,
+    /// Observations are derived from each output and the one before it.
+    Derived(Derivation),
+    /// A single bit leaked from one or more outputs is observed, as 0 or 1.
+    Bits(BitLeak),
+    /// Outputs are observed reduced to `0..range` by float scaling (see
+    /// `scale_output`).
+    Scaled(u32),
+    /// Only the lowest bits of each output are observed, as many as given
+    /// (all of them from 32).
+    LowBits(u32),
+    /// Outputs are observed reduced to `0..modulus` by a (nonzero) modulo.
+    Modulo(u32),
+}
+
+impl ObservationModel {
+    /// Returns how many outputs the generator consumes for an observation.
+    pub fn outputs(self) -> usize {
+        match self {
+            Self::Bits(leak) => leak.outputs(),
+            _ => 1,
+        }
+    }
+
+    /// Returns the observation of the last output consumed, given the one
+    /// before it for derived observations, which is what `Breaker::submit`
+    /// expects.
+    pub fn observe(self, previous: u32, output: u32) -> u32 {
+        match self {
+            Self::Outputs => output,
+            Self::Derived(derivation) => derivation.derive(previous, output),
+            Self::Bits(leak) => leak.observe(output) as u32,
+            Self::Scaled(range) => scale_output(output, range),
+            Self::LowBits(bits) => output & low_mask(bits),
+            Self::Modulo(modulus) => output % modulus,
+        }
+    }
+}
+
+/// Returns the mask keeping the lowest `bits` bits of an output.
+fn low_mask(bits: u32) -> u32 {
+    u32::MAX.checked_shr(32 - bits.min(32)).unwrap_or(0)
+}
+
+/// Configures a `Breaker`: the table is the only thing which must be given,
+/// and every other setting has a default.
+pub struct BreakerBuilder<O: Observer = ()> {
+    registry: SolverRegistry,
+    solver: &'static str,
+    config: SolverConfig,
+    increments: Option<HashSet<u64>>,
+    model: ObservationModel,
+    observer: O,
+}
+
+impl BreakerBuilder {
+    /// Starts configuring a breaker using a table, which can be given by
+    /// value or shared as an `Arc<LookupTable>`. The table decides how many
+    /// outputs separate the observations (see `TableOptions::stride`).
+    pub fn new<T: Into<Arc<LookupTable>>>(table: T) -> Self {
+        Self {
+            registry: SolverRegistry::with_builtins(),
+            solver: "table",
+            config: SolverConfig {
+                table: Some(table.into()),
+                threshold: DEFAULT_THRESHOLD,
+                ..SolverConfig::default()
+            },
+            increments: None,
+            model: ObservationModel::Outputs,
+            observer: (),
+        }
+    }
+}
+
+impl<O: Observer> BreakerBuilder<O> {
+    /// Sets the registry the solver is selected from, the built-in solvers
+    /// by default (see `SolverRegistry::with_builtins`).
+    pub fn registry(mut self, registry: SolverRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Selects the solver initializing the breaker by its name in the
+    /// registry, `table` by default. It must track candidate states (see
+    /// `SolverBackend::into_candidates`), which the breaker goes on tracking.
+    pub fn solver(mut self, name: &'static str) -> Self {
+        self.solver = name;
+        self
+    }
+
+    /// Only keeps the candidates with this increment (with the highest bits
+    /// clear for tables made for an even stride), which is also given to
+    /// solvers needing it.
+    pub fn increment(mut self, inc: u64) -> Self {
+        self.config.inc = Some(inc | 1);
+        self.increments(Some(inc))
+    }
+
+    /// Only keeps the candidates with any of these increments.
+    pub fn increments<I: IntoIterator<Item = u64>>(mut self, increments: I) -> Self {
+        self.increments = Some(increments.into_iter().map(|inc| inc | 1).collect());
+        self
+    }
+
+    /// Sets how outputs are observed after the initial ones, as they are by
+    /// default.
+    pub fn observation_model(mut self, model: ObservationModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Sets how few candidates must remain for them to be enumerated, which
+    /// releases the table (1000 by default, never if zero).
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.config.threshold = threshold;
+        self
+    }
+
+    /// Sets how many threads enumerate the candidates, one per core if zero
+    /// (the default).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads;
+        self
+    }
+
+    /// Sets the token cancelling the searches of the breaker, from the one
+    /// initializing it in `build` to those narrowing down its candidates.
+    pub fn cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.config.cancellation = cancellation;
+        self
+    }
+
+    /// Sets the observer notified of the breaker's progress.
+    pub fn observer<P: Observer>(self, observer: P) -> BreakerBuilder<P> {
+        BreakerBuilder {
+            registry: self.registry,
+            solver: self.solver,
+            config: self.config,
+            increments: self.increments,
+            model: self.model,
+            observer,
+        }
+    }
+
+    /// Initializes the breaker with at least as many consecutive outputs as
+    /// the solver needs (four for `table`), given as they are, submitting
+    /// those past them.
+    pub fn build(self, outputs: &[u32]) -> std::result::Result<Breaker<O>, Inconsistency> {
+        let entry = (self.registry.get(self.solver)).ok_or(Inconsistency::Solver(self.solver))?;
+
+        if outputs.len() < entry.outputs {
+            return Err(Inconsistency::TooFewOutputs(outputs.len()));
+        }
+
+        let (initial, rest) = outputs.split_at(entry.outputs);
+
+        let mut backend =
+            (self.registry.start(entry.name, &self.config, initial)).map_err(|err| {
+                match err.kind() {
+                    ErrorKind::InvalidData => Inconsistency::Initialization,
+                    ErrorKind::Interrupted => Inconsistency::Cancelled,
+                    _ => Inconsistency::Solver(entry.name),
+                }
+            })?;
+
+        for (index, &output) in rest.iter().enumerate() {
+            backend
+                .submit(output)
+                .map_err(|_| Inconsistency::Output(entry.outputs + index))?;
+        }
+
+        let candidates = (backend.into_candidates()).ok_or(Inconsistency::Solver(entry.name))?;
+
+        let candidates = match &self.increments {
+            Some(increments) => {
+                let accepted = |state: &Pcg32State| increments.contains(&state.inc.0);
+
+                let states = match &candidates {
+                    Candidates::Implicit(predictor) => {
+                        predictor.remaining_candidates_where(accepted)
+                    }
+                    candidates => candidates.to_vec().into_iter().filter(accepted).collect(),
+                };
+
+                if states.is_empty() {
+                    return Err(Inconsistency::Increment);
+                }
+
+                Candidates::Explicit(states)
+            }
+            None => candidates,
+        };
+
+        let tracker = Tracker::with_threads(
+            candidates,
+            self.config.threshold,
+            self.config.threads,
+            self.observer,
+        );
+
+        Ok(Breaker {
+            tracker,
+            model: self.model,
+        })
+    }
+}
+
+/// Follows a generator through observations of its outputs, as configured by
+/// a `BreakerBuilder`. A breaker is `Send` and `Sync` as long as its observer
+/// is, so one engine can be handed between the threads of a server or an
+/// async runtime, behind a `Mutex` when several of them submit observations.
+pub struct Breaker<O: Observer = ()> {
+    tracker: Tracker<O>,
+    model: ObservationModel,
+}
+
+impl<O: Observer> Breaker<O> {
+    /// Submits the next observation, as described by the observation model,
+    /// failing with `ErrorKind::Interrupted` if the search it takes is
+    /// cancelled.
+    pub fn submit(&mut self, observation: u32) -> Result<()> {
+        match self.model {
+            ObservationModel::Outputs => self.tracker.submit_next_output(observation),
+            ObservationModel::Derived(derivation) => {
+                self.tracker.submit_next_derived(observation, derivation)
+            }
+            ObservationModel::Bits(leak) => match observation {
+                0 | 1 => self.tracker.submit_next_bit(observation == 1, leak),
+                _ => Err(Error::other("leaked bit must be 0 or 1")),
+            },
+            ObservationModel::Scaled(range) => self.tracker.submit_next_scaled(observation, range),
+            ObservationModel::LowBits(bits) => self
+                .tracker
+                .submit_next_where(1, |_, next| next & low_mask(bits) == observation),
+            ObservationModel::Modulo(modulus) => self
+                .tracker
+                .submit_next_where(1, |_, next| next % modulus == observation),
+        }
+    }
+
+    /// Predicts the next output.
+    pub fn predict_future_output(&self) -> Prediction {
+        self.tracker.candidates().predict_future_output()
+    }
+
+    /// Returns the generator state if it has been uniquely determined.
+    pub fn recovered_state(&self) -> Option<Pcg32State> {
+        self.tracker.candidates().recovered_state()
+    }
+
+    pub fn model(&self) -> ObservationModel {
+        self.model
+    }
+
+    pub fn tracker(&self) -> &Tracker<O> {
+        &self.tracker
+    }
+
+    pub fn tracker_mut(&mut self) -> &mut Tracker<O> {
+        &mut self.tracker
+    }
+
+    /// Drops the configuration, handing back the tracker.
+    pub fn into_tracker(self) -> Tracker<O> {
+        self.tracker
+    }
+}
@@ -1,23 +1,278 @@
 use bytemuck::cast_slice;
-use std::fs::write;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{
+    create_dir_all, metadata, read_to_string, remove_dir, remove_file, rename, write, File,
+};
+use std::io::{BufReader, BufWriter, Read, Result, Write};
 use std::num::Wrapping as W;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
 
 /// The multiplicative constant from PCG-XSH-RR
 const A: W<u64> = W(6_364_136_223_846_793_005);
 
-fn main() {
-    let mut table: Vec<u64> = Vec::with_capacity(0x800_0000);
+/// Number of entries in the table.
+const ENTRIES: u64 = 0x800_0000;
 
-    for zeta in 0..0x800_0000 {
-        let product = A * (-W(zeta as u64));
+/// Name of the file recording which runs have been sorted so far.
+const MANIFEST: &str = "manifest.txt";
 
-        let negative_n = product >> 27;
-        let beta = product & W(0x7ff_ffff);
+/// Precomputes the lookup table used by pcg-breaker. The entries are sorted
+/// in runs small enough to fit in memory, each written to a temporary file,
+/// which are then merged into the table; an interrupted generation resumes
+/// from the last run sorted when run again with the same options.
+#[derive(StructOpt)]
+#[structopt(name = "gen-table")]
+struct Opt {
+    /// Number of entries (of 8 bytes each) sorted in memory at once
+    #[structopt(long = "run-entries", default_value = "16777216")]
+    run_entries: u64,
 
-        table.push((((-negative_n) << 27) | beta).0);
+    /// Sort runs as large as fit in this much memory (e.g. 512M or 2G)
+    /// instead of giving --run-entries
+    #[structopt(
+        long = "memory-budget",
+        conflicts_with = "run-entries",
+        parse(try_from_str = parse_memory_size)
+    )]
+    memory_budget: Option<u64>,
+
+    /// Directory holding the sorted runs and the manifest until the table is
+    /// written
+    #[structopt(long = "work-dir", default_value = "table.tmp", parse(from_os_str))]
+    work_dir: PathBuf,
+
+    /// Path to write the table to
+    #[structopt(long = "output", default_value = "table.bin", parse(from_os_str))]
+    output: PathBuf,
+
+    /// Make the table for observing only every k-th output (see
+    /// pcg-breaker --stride), relating observations by A^k instead of A
+    #[structopt(long = "stride", default_value = "1")]
+    stride: u64,
+}
+
+/// Memory set aside by --memory-budget for everything besides the run being
+/// sorted: the program itself and the buffers merging the runs.
+const MEMORY_RESERVE: u64 = 16 << 20;
+
+/// Parses a size in bytes, optionally followed by `K`, `M`, `G` or `T` for
+/// binary multiples, e.g. `512M`.
+fn parse_memory_size(text: &str) -> std::result::Result<u64, String> {
+    let invalid = || format!("invalid memory size `{}`, e.g. 512M or 2G", text);
+
+    let text = text.trim();
+    let (digits, shift) = match text.char_indices().last().ok_or_else(invalid)? {
+        (index, 'k' | 'K') => (&text[..index], 10),
+        (index, 'm' | 'M') => (&text[..index], 20),
+        (index, 'g' | 'G') => (&text[..index], 30),
+        (index, 't' | 'T') => (&text[..index], 40),
+        _ => (text, 0),
+    };
+
+    let size = digits.parse::<u64>().map_err(|_| invalid())?;
+
+    match size.checked_shl(shift) {
+        Some(bytes) if bytes >> shift == size && bytes > MEMORY_RESERVE => Ok(bytes),
+        Some(_) => Err(format!(
+            "a memory budget of {} leaves no room for sorting past the {} MiB reserved",
+            text,
+            MEMORY_RESERVE >> 20
+        )),
+        None => Err(invalid()),
+    }
+}
+
+fn entry(zeta: u64, mult: W<u64>) -> u64 {
+    let product = mult * (-W(zeta));
+
+    let negative_n = product >> 27;
+    let beta = product & W(0x7ff_ffff);
+
+    (((-negative_n) << 27) | beta).0
+}
+
+fn run_path(work_dir: &Path, run: usize) -> PathBuf {
+    work_dir.join(format!("run-{}.bin", run))
+}
+
+/// Returns the range of zeta values sorted in a run.
+fn run_range(run: usize, run_entries: u64) -> (u64, u64) {
+    let start = run as u64 * run_entries;
+    (start, (start + run_entries).min(ENTRIES))
+}
+
+/// Returns the first line of the manifest, which records the options the
+/// runs were sorted with.
+fn manifest_header(run_entries: u64, stride: u64) -> String {
+    match stride {
+        1 => format!("run-entries {}", run_entries),
+        _ => format!("run-entries {} stride {}", run_entries, stride),
+    }
+}
+
+/// Reads the runs already sorted from the manifest, as long as they were
+/// sorted with the same options and their files are intact.
+fn read_manifest(work_dir: &Path, run_entries: u64, stride: u64) -> Vec<usize> {
+    let text = read_to_string(work_dir.join(MANIFEST)).unwrap_or_default();
+    let mut lines = text.lines();
+
+    if lines.next() != Some(&manifest_header(run_entries, stride)) {
+        return vec![];
+    }
+
+    lines
+        .filter_map(|line| line.strip_prefix("run ")?.parse().ok())
+        .filter(|&run| {
+            let (start, end) = run_range(run, run_entries);
+
+            metadata(run_path(work_dir, run)).is_ok_and(|file| file.len() == (end - start) * 8)
+        })
+        .collect()
+}
+
+fn write_manifest(
+    work_dir: &Path,
+    run_entries: u64,
+    stride: u64,
+    completed: &[usize],
+) -> Result<()> {
+    let mut text = manifest_header(run_entries, stride) + "\n";
+
+    for run in completed {
+        text += &format!("run {}\n", run);
+    }
+
+    write(work_dir.join(MANIFEST), text)
+}
+
+/// Merges the sorted runs into the table, reporting progress as it goes and
+/// returning how many entries share their key with the entry before them.
+fn merge_runs(work_dir: &Path, runs: usize, output: &Path) -> Result<u64> {
+    let mut readers = (0..runs)
+        .map(|run| {
+            Ok(BufReader::with_capacity(
+                1 << 20,
+                File::open(run_path(work_dir, run))?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let next_entry = |reader: &mut BufReader<File>| -> Option<u64> {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes).ok()?;
+        Some(u64::from_ne_bytes(bytes))
+    };
+
+    let mut heap = BinaryHeap::with_capacity(runs);
+
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(entry) = next_entry(reader) {
+            heap.push(Reverse((entry, run)));
+        }
+    }
+
+    // write to a separate file first so as not to leave a truncated table
+    let partial = output.with_extension("partial");
+    let mut writer = BufWriter::with_capacity(1 << 23, File::create(&partial)?);
+    let (mut written, mut shared, mut last_key) = (0, 0, None);
+
+    while let Some(Reverse((entry, run))) = heap.pop() {
+        writer.write_all(&entry.to_ne_bytes())?;
+        written += 1;
+
+        if last_key == Some(entry >> 27) {
+            shared += 1;
+        }
+
+        last_key = Some(entry >> 27);
+
+        if written % (ENTRIES / 10) == 0 {
+            eprintln!("[-] Merging runs: {}% done.", written * 100 / ENTRIES);
+        }
+
+        if let Some(entry) = next_entry(&mut readers[run]) {
+            heap.push(Reverse((entry, run)));
+        }
     }
 
-    table.sort_unstable();
+    writer.flush()?;
+    drop(writer);
+
+    rename(partial, output)?;
+    Ok(shared)
+}
+
+fn main() -> Result<()> {
+    let args = Opt::from_args();
+
+    let run_entries = match args.memory_budget {
+        Some(budget) => (budget - MEMORY_RESERVE) / 8,
+        None => args.run_entries,
+    }
+    .clamp(1, ENTRIES);
+
+    if args.memory_budget.is_some() {
+        eprintln!(
+            "[-] Sorting runs of {} entries to fit the memory budget.",
+            run_entries
+        );
+    }
+
+    let runs = ENTRIES.div_ceil(run_entries) as usize;
+    let stride = args.stride.max(1);
+    let mult = (0..stride).fold(W(1), |mult, _| mult * A);
+
+    create_dir_all(&args.work_dir)?;
+
+    let mut completed = read_manifest(&args.work_dir, run_entries, stride);
+
+    if !completed.is_empty() {
+        eprintln!(
+            "[-] Resuming with {} of {} runs already sorted.",
+            completed.len(),
+            runs
+        );
+    }
+
+    for run in 0..runs {
+        if completed.contains(&run) {
+            continue;
+        }
+
+        let (start, end) = run_range(run, run_entries);
+
+        let mut table: Vec<u64> = (start..end).map(|zeta| entry(zeta, mult)).collect();
+        table.sort_unstable();
+
+        write(run_path(&args.work_dir, run), cast_slice(&table))?;
+
+        completed.push(run);
+        write_manifest(&args.work_dir, run_entries, stride, &completed)?;
+
+        eprintln!("[-] Sorted run {} of {}.", run + 1, runs);
+    }
+
+    let shared = merge_runs(&args.work_dir, runs, &args.output)?;
+
+    for run in 0..runs {
+        remove_file(run_path(&args.work_dir, run))?;
+    }
+
+    remove_file(args.work_dir.join(MANIFEST))?;
+    let _ = remove_dir(&args.work_dir);
+
+    eprintln!("[+] Wrote the table to {}.", args.output.display());
+
+    // keys are unique for A, but not necessarily for every power of it
+    if shared > 0 {
+        eprintln!(
+            "[!] {} entries share their key with another, so some states may be \
+             missed with this stride.",
+            shared
+        );
+    }
 
-    write("table.bin", cast_slice(&table)).unwrap();
+    Ok(())
 }
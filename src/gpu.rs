@@ -0,0 +1,470 @@
+//! Pruning explicitly enumerated candidates on a GPU through OpenCL, which
+//! is loaded at run time so that building with the `gpu` feature needs
+//! nothing but libc, and the same binary runs on machines without OpenCL.
+
+use crate::{Pcg32State, A};
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::io::{Error, Result};
+use std::ptr::{null, null_mut};
+use std::sync::Mutex;
+
+/// Number of candidates from which they are pruned on the GPU rather than
+/// on the CPU, below which copying them over costs more than it saves.
+pub const GPU_MIN_CANDIDATES: usize = 1 << 20;
+
+/// The kernel flagging the candidates producing every output given, writing
+/// the index of each of them to `matches`, in no particular order.
+const KERNEL_SOURCE: &str = "
+__kernel void prune(__global const ulong *words, ulong count,
+                    __global const uint *outputs, uint output_count,
+                    __global uint *matches, volatile __global uint *found) {
+    ulong index = get_global_id(0);
+
+    if (index >= count) {
+        return;
+    }
+
+    ulong state = words[2 * index], inc = words[2 * index + 1];
+
+    for (uint k = 0; k < output_count; k++) {
+        state = state * MULTIPLIER + inc;
+
+        uint xorshifted = (uint)(((state >> 18) ^ state) >> 27);
+        uint rotation = (uint)(state >> 59);
+
+        if (((xorshifted >> rotation) | (xorshifted << ((32 - rotation) & 31))) != outputs[k]) {
+            return;
+        }
+    }
+
+    matches[atomic_inc(found)] = (uint)index;
+}
+";
+
+const CL_SUCCESS: i32 = 0;
+const CL_DEVICE_TYPE_GPU: u64 = 1 << 2;
+const CL_DEVICE_TYPE_ALL: u64 = 0xFFFF_FFFF;
+const CL_DEVICE_NAME: u32 = 0x102B;
+const CL_MEM_READ_WRITE: u64 = 1 << 0;
+const CL_MEM_READ_ONLY: u64 = 1 << 2;
+const CL_MEM_COPY_HOST_PTR: u64 = 1 << 5;
+const CL_TRUE: u32 = 1;
+
+type Handle = *mut c_void;
+
+/// The OpenCL entry points used, resolved from the library at run time.
+struct OpenCl {
+    library: Handle,
+    get_platform_ids: unsafe extern "C" fn(u32, *mut Handle, *mut u32) -> i32,
+    get_device_ids: unsafe extern "C" fn(Handle, u64, u32, *mut Handle, *mut u32) -> i32,
+    get_device_info: unsafe extern "C" fn(Handle, u32, usize, *mut c_void, *mut usize) -> i32,
+    create_context: unsafe extern "C" fn(
+        *const isize,
+        u32,
+        *const Handle,
+        *const c_void,
+        *mut c_void,
+        *mut i32,
+    ) -> Handle,
+    create_command_queue: unsafe extern "C" fn(Handle, Handle, u64, *mut i32) -> Handle,
+    create_program_with_source:
+        unsafe extern "C" fn(Handle, u32, *const *const c_char, *const usize, *mut i32) -> Handle,
+    build_program: unsafe extern "C" fn(
+        Handle,
+        u32,
+        *const Handle,
+        *const c_char,
+        *const c_void,
+        *mut c_void,
+    ) -> i32,
+    create_kernel: unsafe extern "C" fn(Handle, *const c_char, *mut i32) -> Handle,
+    create_buffer: unsafe extern "C" fn(Handle, u64, usize, *mut c_void, *mut i32) -> Handle,
+    set_kernel_arg: unsafe extern "C" fn(Handle, u32, usize, *const c_void) -> i32,
+    enqueue_nd_range_kernel: unsafe extern "C" fn(
+        Handle,
+        Handle,
+        u32,
+        *const usize,
+        *const usize,
+        *const usize,
+        u32,
+        *const Handle,
+        *mut Handle,
+    ) -> i32,
+    enqueue_read_buffer: unsafe extern "C" fn(
+        Handle,
+        Handle,
+        u32,
+        usize,
+        usize,
+        *mut c_void,
+        u32,
+        *const Handle,
+        *mut Handle,
+    ) -> i32,
+    release: [unsafe extern "C" fn(Handle) -> i32; 5],
+}
+
+/// Indices into `OpenCl::release` of the function releasing each object.
+const RELEASE_MEM_OBJECT: usize = 0;
+const RELEASE_KERNEL: usize = 1;
+const RELEASE_PROGRAM: usize = 2;
+const RELEASE_COMMAND_QUEUE: usize = 3;
+const RELEASE_CONTEXT: usize = 4;
+
+impl OpenCl {
+    fn load() -> Result<Self> {
+        let library = ["libOpenCL.so.1", "libOpenCL.so"]
+            .iter()
+            .map(|name| {
+                let name = CString::new(*name).unwrap();
+                unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) }
+            })
+            .find(|library| !library.is_null())
+            .ok_or_else(|| Error::other("no OpenCL library found"))?;
+
+        macro_rules! resolve {
+            ($name:literal) => {{
+                let name = CString::new($name).unwrap();
+                let symbol = unsafe { libc::dlsym(library, name.as_ptr()) };
+
+                if symbol.is_null() {
+                    return Err(Error::other(concat!("OpenCL library lacks ", $name)));
+                }
+
+                #[allow(clippy::missing_transmute_annotations)]
+                unsafe {
+                    std::mem::transmute(symbol)
+                }
+            }};
+        }
+
+        Ok(Self {
+            library,
+            get_platform_ids: resolve!("clGetPlatformIDs"),
+            get_device_ids: resolve!("clGetDeviceIDs"),
+            get_device_info: resolve!("clGetDeviceInfo"),
+            create_context: resolve!("clCreateContext"),
+            create_command_queue: resolve!("clCreateCommandQueue"),
+            create_program_with_source: resolve!("clCreateProgramWithSource"),
+            build_program: resolve!("clBuildProgram"),
+            create_kernel: resolve!("clCreateKernel"),
+            create_buffer: resolve!("clCreateBuffer"),
+            set_kernel_arg: resolve!("clSetKernelArg"),
+            enqueue_nd_range_kernel: resolve!("clEnqueueNDRangeKernel"),
+            enqueue_read_buffer: resolve!("clEnqueueReadBuffer"),
+            release: [
+                resolve!("clReleaseMemObject"),
+                resolve!("clReleaseKernel"),
+                resolve!("clReleaseProgram"),
+                resolve!("clReleaseCommandQueue"),
+                resolve!("clReleaseContext"),
+            ],
+        })
+    }
+}
+
+/// Fails with the name of the call unless an OpenCL status is a success.
+fn check(status: i32, call: &str) -> Result<()> {
+    match status {
+        CL_SUCCESS => Ok(()),
+        _ => Err(Error::other(format!(
+            "{} failed with error {}",
+            call, status
+        ))),
+    }
+}
+
+/// A buffer on the device, released when dropped.
+struct Buffer<'a> {
+    cl: &'a OpenCl,
+    memory: Handle,
+}
+
+impl Drop for Buffer<'_> {
+    fn drop(&mut self) {
+        unsafe { (self.cl.release[RELEASE_MEM_OBJECT])(self.memory) };
+    }
+}
+
+/// Prunes explicitly enumerated candidates on the first GPU found (or any
+/// OpenCL device, failing that), so that tens of millions of them can be
+/// narrowed down by each output, which makes it worth enumerating them much
+/// earlier (see `Tracker::set_gpu`).
+pub struct GpuPruner {
+    cl: OpenCl,
+    device: Handle,
+    context: Handle,
+    queue: Handle,
+    program: Handle,
+    /// Setting the arguments of a kernel isn't thread-safe, so pruning holds
+    /// this for the duration.
+    kernel: Mutex<Handle>,
+}
+
+// OpenCL objects may be used from any thread, except for kernels, whose
+// arguments are only ever set with the lock on them held
+unsafe impl Send for GpuPruner {}
+unsafe impl Sync for GpuPruner {}
+
+impl GpuPruner {
+    /// Loads OpenCL and compiles the kernel for its first GPU, or its first
+    /// device of any kind if it has no GPU.
+    pub fn new() -> Result<Self> {
+        let cl = OpenCl::load()?;
+
+        let mut platforms = [null_mut(); 8];
+        let mut platform_count = 0;
+
+        check(
+            unsafe { (cl.get_platform_ids)(8, platforms.as_mut_ptr(), &mut platform_count) },
+            "clGetPlatformIDs",
+        )?;
+
+        let platforms = &platforms[..(platform_count as usize).min(8)];
+
+        let device = [CL_DEVICE_TYPE_GPU, CL_DEVICE_TYPE_ALL]
+            .iter()
+            .flat_map(|&kind| platforms.iter().map(move |&platform| (platform, kind)))
+            .find_map(|(platform, kind)| {
+                let mut device = null_mut();
+                let status =
+                    unsafe { (cl.get_device_ids)(platform, kind, 1, &mut device, null_mut()) };
+
+                (status == CL_SUCCESS && !device.is_null()).then_some(device)
+            })
+            .ok_or_else(|| Error::other("no OpenCL device found"))?;
+
+        let mut status = CL_SUCCESS;
+
+        let context =
+            unsafe { (cl.create_context)(null(), 1, &device, null(), null_mut(), &mut status) };
+        check(status, "clCreateContext")?;
+
+        // from here on, dropping the pruner releases what was created so far
+        let mut pruner = Self {
+            cl,
+            device,
+            context,
+            queue: null_mut(),
+            program: null_mut(),
+            kernel: Mutex::new(null_mut()),
+        };
+
+        let cl = &pruner.cl;
+
+        pruner.queue = unsafe { (cl.create_command_queue)(context, device, 0, &mut status) };
+        check(status, "clCreateCommandQueue")?;
+
+        let source = KERNEL_SOURCE.replace("MULTIPLIER", &format!("{}UL", A.0));
+        let (source, length) = (source.as_ptr() as *const c_char, source.len());
+
+        pruner.program =
+            unsafe { (cl.create_program_with_source)(context, 1, &source, &length, &mut status) };
+        check(status, "clCreateProgramWithSource")?;
+
+        check(
+            unsafe { (cl.build_program)(pruner.program, 1, &device, null(), null(), null_mut()) },
+            "clBuildProgram",
+        )?;
+
+        let name = CString::new("prune").unwrap();
+        let kernel = unsafe { (cl.create_kernel)(pruner.program, name.as_ptr(), &mut status) };
+        check(status, "clCreateKernel")?;
+
+        pruner.kernel = Mutex::new(kernel);
+
+        Ok(pruner)
+    }
+
+    /// Returns the name of the device the candidates are pruned on.
+    pub fn device_name(&self) -> String {
+        let mut name = [0u8; 256];
+
+        let status = unsafe {
+            (self.cl.get_device_info)(
+                self.device,
+                CL_DEVICE_NAME,
+                name.len(),
+                name.as_mut_ptr() as *mut c_void,
+                null_mut(),
+            )
+        };
+
+        match CStr::from_bytes_until_nul(&name) {
+            Ok(name) if status == CL_SUCCESS => name.to_string_lossy().into_owned(),
+            _ => "an unknown device".to_owned(),
+        }
+    }
+
+    /// Keeps the candidates producing the next outputs, advanced past them,
+    /// as `Candidates::submit_next_outputs` does on the CPU, failing and
+    /// leaving them as they were if none of them does. The candidates are
+    /// kept in the order they were in.
+    pub fn submit_next_outputs(&self, states: &mut Vec<Pcg32State>, outputs: &[u32]) -> Result<()> {
+        if states.len() > u32::MAX as usize {
+            return Err(Error::other("too many candidates to prune on the GPU"));
+        }
+
+        let words: Vec<u64> = states
+            .iter()
+            .flat_map(|state| [state.state.0, state.inc.0])
+            .collect();
+
+        let mut matches = self.matching(&words, outputs)?;
+
+        if matches.is_empty() {
+            return Err(Error::other("output sequence not produced by PCG-XSH-RR"));
+        }
+
+        matches.sort_unstable();
+
+        *states = matches
+            .iter()
+            .map(|&index| states[index as usize].jump(outputs.len() as u64))
+            .collect();
+
+        Ok(())
+    }
+
+    /// Returns the index of the candidates producing the next outputs, in no
+    /// particular order.
+    fn matching(&self, words: &[u64], outputs: &[u32]) -> Result<Vec<u32>> {
+        let guard = self.kernel.lock().unwrap();
+        let kernel = *guard;
+        let count = (words.len() / 2) as u64;
+
+        let words = self.buffer(CL_MEM_READ_ONLY, words)?;
+        let outputs_buffer = self.buffer(CL_MEM_READ_ONLY, outputs)?;
+        let matches = self.buffer(CL_MEM_READ_WRITE, &vec![0u32; count.max(1) as usize])?;
+        let found = self.buffer(CL_MEM_READ_WRITE, &[0u32])?;
+
+        let output_count = outputs.len() as u32;
+
+        let arguments: [(usize, *const c_void); 6] = [
+            (
+                std::mem::size_of::<Handle>(),
+                &words.memory as *const _ as *const c_void,
+            ),
+            (
+                std::mem::size_of::<u64>(),
+                &count as *const _ as *const c_void,
+            ),
+            (
+                std::mem::size_of::<Handle>(),
+                &outputs_buffer.memory as *const _ as *const c_void,
+            ),
+            (
+                std::mem::size_of::<u32>(),
+                &output_count as *const _ as *const c_void,
+            ),
+            (
+                std::mem::size_of::<Handle>(),
+                &matches.memory as *const _ as *const c_void,
+            ),
+            (
+                std::mem::size_of::<Handle>(),
+                &found.memory as *const _ as *const c_void,
+            ),
+        ];
+
+        for (index, (size, value)) in arguments.iter().enumerate() {
+            check(
+                unsafe { (self.cl.set_kernel_arg)(kernel, index as u32, *size, *value) },
+                "clSetKernelArg",
+            )?;
+        }
+
+        let global_size = count as usize;
+
+        check(
+            unsafe {
+                (self.cl.enqueue_nd_range_kernel)(
+                    self.queue,
+                    kernel,
+                    1,
+                    null(),
+                    &global_size,
+                    null(),
+                    0,
+                    null(),
+                    null_mut(),
+                )
+            },
+            "clEnqueueNDRangeKernel",
+        )?;
+
+        let mut match_count = 0u32;
+        self.read(&found, std::slice::from_mut(&mut match_count))?;
+
+        let mut indices = vec![0u32; match_count as usize];
+        self.read(&matches, &mut indices)?;
+
+        Ok(indices)
+    }
+
+    /// Copies some words into a new buffer on the device.
+    fn buffer<T: Copy>(&self, flags: u64, words: &[T]) -> Result<Buffer<'_>> {
+        let mut status = CL_SUCCESS;
+
+        let memory = unsafe {
+            (self.cl.create_buffer)(
+                self.context,
+                flags | CL_MEM_COPY_HOST_PTR,
+                std::mem::size_of_val(words).max(1),
+                words.as_ptr() as *mut c_void,
+                &mut status,
+            )
+        };
+        check(status, "clCreateBuffer")?;
+
+        Ok(Buffer {
+            cl: &self.cl,
+            memory,
+        })
+    }
+
+    /// Reads the start of a buffer back from the device, once the commands
+    /// queued before have completed.
+    fn read<T: Copy>(&self, buffer: &Buffer, words: &mut [T]) -> Result<()> {
+        if words.is_empty() {
+            return Ok(());
+        }
+
+        check(
+            unsafe {
+                (self.cl.enqueue_read_buffer)(
+                    self.queue,
+                    buffer.memory,
+                    CL_TRUE,
+                    0,
+                    std::mem::size_of_val(words),
+                    words.as_mut_ptr() as *mut c_void,
+                    0,
+                    null(),
+                    null_mut(),
+                )
+            },
+            "clEnqueueReadBuffer",
+        )
+    }
+}
+
+impl Drop for GpuPruner {
+    fn drop(&mut self) {
+        let kernel = *self.kernel.get_mut().unwrap();
+
+        for (object, release) in [
+            (kernel, RELEASE_KERNEL),
+            (self.program, RELEASE_PROGRAM),
+            (self.queue, RELEASE_COMMAND_QUEUE),
+            (self.context, RELEASE_CONTEXT),
+        ] {
+            if !object.is_null() {
+                unsafe { (self.cl.release[release])(object) };
+            }
+        }
+
+        unsafe { libc::dlclose(self.cl.library) };
+    }
+}
@@ -0,0 +1,89 @@
+//! Constants for jumping PCG states ahead by any number of steps.
+
+use crate::{Pcg32State, A};
+use std::num::Wrapping as W;
+
+/// The constants `(A^k, (A^k - 1) / (A - 1))` for every power of two `k` up
+/// to 2^63, which advance any state by `k` steps to
+/// `A^k * state + (A^k - 1) / (A - 1) * inc`. Jumps of other lengths combine
+/// the constants for each bit of the length.
+pub struct JumpTable {
+    mult: [u64; 64],
+    plus: [u64; 64],
+}
+
+/// The shared table, computed at compile time.
+static SHARED: JumpTable = JumpTable::new();
+
+impl JumpTable {
+    const fn new() -> Self {
+        let (mut mult, mut plus) = ([0; 64], [0; 64]);
+        let (mut cur_mult, mut cur_plus) = (A.0, 1u64);
+        let mut bit = 0;
+
+        while bit < 64 {
+            mult[bit] = cur_mult;
+            plus[bit] = cur_plus;
+
+            cur_plus = cur_plus.wrapping_mul(cur_mult.wrapping_add(1));
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            bit += 1;
+        }
+
+        Self { mult, plus }
+    }
+
+    /// Returns the table shared by every jump.
+    pub fn shared() -> &'static Self {
+        &SHARED
+    }
+
+    /// Returns the constants advancing a state by 2^`bit` steps.
+    pub fn power_of_two(&self, bit: u32) -> (W<u64>, W<u64>) {
+        (W(self.mult[bit as usize]), W(self.plus[bit as usize]))
+    }
+
+    /// Returns the constants advancing a state by `delta` steps.
+    pub fn constants(&self, delta: u64) -> (W<u64>, W<u64>) {
+        let (mut acc_mult, mut acc_plus) = (W(1), W(0));
+
+        for bit in (0..64).filter(|bit| delta & (1 << bit) != 0) {
+            let (mult, plus) = self.power_of_two(bit);
+
+            acc_mult *= mult;
+            acc_plus = acc_plus * mult + plus;
+        }
+
+        (acc_mult, acc_plus)
+    }
+
+    /// Advances a state by `delta` steps.
+    pub fn jump(&self, state: &Pcg32State, delta: u64) -> Pcg32State {
+        Self::apply(state, self.constants(delta))
+    }
+
+    /// Returns the number of steps needed to advance a state to another.
+    pub fn distance(&self, state: &Pcg32State, target: W<u64>) -> u64 {
+        let (mut current, mut distance) = (state.state, 0);
+
+        for bit in 0..64 {
+            if (current ^ target).0 & (1 << bit) != 0 {
+                let (mult, plus) = self.power_of_two(bit);
+
+                current = mult * current + plus * state.inc;
+                distance |= 1 << bit;
+            }
+        }
+
+        distance
+    }
+
+    /// Advances a state by the number of steps some constants were computed
+    /// for, which saves combining them again for many states.
+    pub fn apply(state: &Pcg32State, (mult, plus): (W<u64>, W<u64>)) -> Pcg32State {
+        Pcg32State {
+            state: mult * state.state + plus * state.inc,
+            inc: state.inc,
+        }
+    }
+}
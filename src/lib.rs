@@ -0,0 +1,2968 @@
+//! Output prediction and state recovery for the PCG-XSH-RR generator.
+
+use bytemuck::{bytes_of_mut, cast_slice_mut};
+use itertools::iproduct;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result};
+use std::num::Wrapping as W;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod breaker;
+#[cfg(feature = "gpu")]
+mod gpu;
+mod jump;
+pub mod pcg_math;
+mod simd;
+mod solver;
+
+pub use breaker::{Breaker, BreakerBuilder, ObservationModel};
+#[cfg(feature = "gpu")]
+pub use gpu::{GpuPruner, GPU_MIN_CANDIDATES};
+pub use jump::JumpTable;
+pub use pcg_math::{compute_xsh_rr, invert_xsh_rr, A, A_INV};
+pub use simd::{set_force_scalar, SimdPath};
+pub use solver::{
+    SolverBackend, SolverConfig, SolverEntry, SolverFactory, SolverProgress, SolverRegistry,
+};
+
+// every engine can be moved to, or shared with, other threads (see the
+// documentation of each), which this keeps from silently changing
+const _: fn() = || {
+    fn thread_safe<T: Send + Sync>() {}
+
+    thread_safe::<LookupTable>();
+    thread_safe::<Predictor>();
+    thread_safe::<Snapshot>();
+    thread_safe::<Candidates>();
+    thread_safe::<Tracker<()>>();
+    thread_safe::<Breaker>();
+    thread_safe::<BreakerBuilder>();
+    thread_safe::<JumpTable>();
+    thread_safe::<CancellationToken>();
+    thread_safe::<SolverRegistry>();
+    #[cfg(feature = "gpu")]
+    thread_safe::<GpuPruner>();
+};
+
+/// Advises the kernel to back some memory with transparent huge pages.
+#[cfg(target_os = "linux")]
+fn advise_hugepages(table: &mut [u64]) {
+    let page = PAGE_ENTRIES * 8;
+    let start = table.as_mut_ptr() as usize;
+    let end = start + table.len() * 8;
+
+    // the advice must start on a page boundary, and is only a hint
+    let aligned = (start + page - 1) & !(page - 1);
+
+    unsafe {
+        libc::madvise(
+            aligned as *mut libc::c_void,
+            (end - aligned) & !(page - 1),
+            libc::MADV_HUGEPAGE,
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise_hugepages(_: &mut [u64]) {}
+
+/// The increment `rand_core::SeedableRng::seed_from_u64` expands seeds with
+const SEED_FROM_U64_INC: W<u64> = W(11_634_580_027_462_260_723);
+
+/// Recovers the `u64` which `rand_core::SeedableRng::seed_from_u64` expanded
+/// into a 16-byte seed, if it was produced that way. This is a brute-force
+/// search over the 2^32 states consistent with the seed's first word.
+pub fn invert_seed_from_u64(seed: [u8; 16]) -> Option<u64> {
+    let mut words = [0u32; 4];
+    bytes_of_mut(&mut words).copy_from_slice(&seed);
+    let words = words.map(u32::from_le);
+
+    for rotation in 0..32 {
+        let high = invert_xsh_rr(rotation, words[0]);
+
+        for low in 0..0x800_0000 {
+            let mut expander = Pcg32State {
+                state: high | W(low),
+                inc: SEED_FROM_U64_INC,
+            };
+
+            expander.advance();
+
+            if expander.next_u32() != words[1] {
+                continue;
+            }
+
+            // the lowest bit of the third word is lost when seeding
+            if expander.next_u32() | 1 != words[2] | 1 || expander.next_u32() != words[3] {
+                continue;
+            }
+
+            return Some((A_INV * ((high | W(low)) - SEED_FROM_U64_INC)).0);
+        }
+    }
+
+    None
+}
+
+/// Recovers the state which produced some consecutive outputs of a generator
+/// with a known increment. This is a brute-force search over the 2^32 states
+/// consistent with the first output, so at least three outputs should be
+/// given for the state to be unique.
+pub fn recover_state_with_inc(inc: W<u64>, outputs: &[u32]) -> Option<Pcg32State> {
+    let (&first, rest) = outputs.split_first()?;
+
+    for rotation in 0..32 {
+        let high = invert_xsh_rr(rotation, first);
+
+        for low in 0..0x800_0000 {
+            let state = Pcg32State {
+                state: high | W(low),
+                inc: inc | W(1),
+            };
+
+            let mut generator = state;
+            generator.advance();
+
+            if rest.iter().all(|&output| generator.next_u32() == output) {
+                return Some(state);
+            }
+        }
+    }
+
+    None
+}
+
+/// Draws a generator whose first outputs make initializing a predictor as
+/// slow as it gets: the rotations of its first three states are the last
+/// guessed, and the low bits of its second and third states differ by one,
+/// leaving the widest interval of candidates (2^27 - 1 of them). The same
+/// `rng` state always draws the same generator.
+pub fn worst_case_state(rng: &mut Pcg32State) -> Pcg32State {
+    let mut next_u64 = || W(((rng.next_u32() as u64) << 32) | rng.next_u32() as u64);
+
+    loop {
+        let s1 = next_u64() | W(0xf800_0000_0000_0000);
+        let low = s1 & W(0x7ff_ffff);
+
+        // the increment is only odd if the low bits differ by an odd amount
+        if low == W(0x7ff_ffff) {
+            continue;
+        }
+
+        let s2 = (next_u64() | W(0xf800_0000_0000_0000)) & !W(0x7ff_ffffu64) | (low + W(1));
+        let inc = s2 - A * s1;
+
+        let state = Pcg32State {
+            state: A_INV * (s1 - inc),
+            inc,
+        };
+
+        if state.state >> 59 == W(31) {
+            return state;
+        }
+    }
+}
+
+/// The table made by gen-table, relating the high bits of consecutive states.
+///
+/// A table never changes once loaded, so it is `Send` and `Sync`, and one
+/// copy can be shared by any number of threads behind an `Arc`. The only
+/// thing updated through a shared reference is the count of wide scans,
+/// which is atomic and only ever read as a statistic.
+pub struct LookupTable {
+    table: Entries,
+    stride: Stride,
+    /// Number of entries found outside of the window scanned for them.
+    wide_scans: AtomicUsize,
+}
+
+/// The entries of a table, read into memory or mapped from its file.
+enum Entries {
+    Owned(Box<[u64]>),
+    #[cfg(unix)]
+    Mapped(MappedEntries),
+}
+
+impl std::ops::Deref for Entries {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        match self {
+            Self::Owned(entries) => entries,
+            #[cfg(unix)]
+            Self::Mapped(mapped) => unsafe { std::slice::from_raw_parts(mapped.ptr, mapped.len) },
+        }
+    }
+}
+
+/// Entries mapped read-only from the file of a table, unmapped once dropped.
+#[cfg(unix)]
+struct MappedEntries {
+    ptr: *const u64,
+    len: usize,
+}
+
+// the mapping is private and never written to, so it can be read from any
+// thread like the entries of a table read into memory
+#[cfg(unix)]
+unsafe impl Send for MappedEntries {}
+#[cfg(unix)]
+unsafe impl Sync for MappedEntries {}
+
+#[cfg(unix)]
+impl MappedEntries {
+    /// Maps the entries of a table from its file, which must be the size of
+    /// a table, faulting them all in at once if prefaulting (only on Linux).
+    fn map(file: &File, prefault: bool) -> Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let size = TABLE_SIZE as usize;
+
+        #[cfg(target_os = "linux")]
+        let flags = match prefault {
+            true => libc::MAP_PRIVATE | libc::MAP_POPULATE,
+            false => libc::MAP_PRIVATE,
+        };
+
+        #[cfg(not(target_os = "linux"))]
+        let flags = {
+            let _ = prefault;
+            libc::MAP_PRIVATE
+        };
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ,
+                flags,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::last_os_error());
+        }
+
+        // queries land anywhere in the table, so reading ahead of them would
+        // only evict pages as likely to be queried next
+        unsafe { libc::madvise(ptr, size, libc::MADV_RANDOM) };
+
+        Ok(Self {
+            ptr: ptr as *const u64,
+            len: size / std::mem::size_of::<u64>(),
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for MappedEntries {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, TABLE_SIZE as usize) };
+    }
+}
+
+/// How the memory holding a lookup table is set up before it is loaded.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableOptions {
+    /// Fault in every page of the table in a single pass before loading it,
+    /// rather than one at a time as it is loaded.
+    pub prefault: bool,
+    /// Advise the kernel to back the table with transparent huge pages, to
+    /// save on TLB misses when querying it (only on Linux).
+    pub hugepages: bool,
+    /// Map the file of the table into memory rather than reading it (only
+    /// on Unix), so that the kernel only pages in the entries queried and can
+    /// evict them again under memory pressure, at the cost of page faults on
+    /// queries. Huge pages don't apply, and prefaulting reads it all at once.
+    pub mmap: bool,
+    /// Don't check the sample entries of the table once loaded, leaving it
+    /// to `verify` (its size is checked regardless).
+    pub skip_checks: bool,
+    /// Number of outputs separating the observed outputs the table was made
+    /// for (see gen-table's `--stride`), with 0 or 1 for consecutive outputs.
+    pub stride: u64,
+}
+
+/// How the states producing consecutive observed outputs are related when
+/// only every k-th output of a generator is observed: they follow another
+/// LCG, whose multiplier and increment are given by the constants jumping a
+/// state k steps ahead.
+#[derive(Clone, Copy, Debug)]
+struct Stride {
+    outputs: u64,
+    mult: W<u64>,
+    plus: W<u64>,
+    /// The inverse of the odd part of `plus`, to recover the increment of
+    /// the generator from the increment relating observed states.
+    plus_inv: W<u64>,
+}
+
+impl Stride {
+    fn new(outputs: u64) -> Self {
+        let outputs = outputs.max(1);
+        let (mult, plus) = JumpTable::shared().constants(outputs);
+
+        // Newton's iteration doubles the correct low bits of the inverse of
+        // an odd number, starting from the 3 bits of the number itself
+        let odd = plus >> plus.0.trailing_zeros() as usize;
+        let plus_inv = (0..5).fold(odd, |inv, _| inv * (W(2) - odd * inv));
+
+        Self {
+            outputs,
+            mult,
+            plus,
+            plus_inv,
+        }
+    }
+
+    /// Returns the increment relating the states of two consecutive observed
+    /// outputs, which is odd for odd strides.
+    fn increment(&self, sj: W<u64>, sk: W<u64>) -> W<u64> {
+        let inc = sk - self.mult * sj;
+
+        if self.outputs % 2 == 1 {
+            inc | W(1)
+        } else {
+            inc
+        }
+    }
+
+    /// Returns the generator state given the state of an observed output and
+    /// the increment relating it to the next. For even strides, the highest
+    /// bits of the increment of the generator can't be told apart from the
+    /// observed outputs alone, and are left clear.
+    fn state(&self, state: W<u64>, inc: W<u64>) -> Pcg32State {
+        let shift = self.plus.0.trailing_zeros() as usize;
+
+        Pcg32State {
+            state,
+            inc: ((inc >> shift) * self.plus_inv) | W(1),
+        }
+    }
+}
+
+/// Statistics about where the entries of a lookup table lie relative to the
+/// position queries estimate for them, around which they scan a window.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableStatistics {
+    pub entries: usize,
+    /// Number of distinct estimated positions.
+    pub buckets: usize,
+    /// Largest number of entries sharing an estimated position.
+    pub max_bucket: usize,
+    /// Lowest offset of an entry from its estimated position.
+    pub min_offset: isize,
+    /// Highest offset of an entry from its estimated position.
+    pub max_offset: isize,
+    /// Mean number of entries scanned to find an entry.
+    pub mean_scan: f64,
+    /// Largest number of entries scanned to find an entry.
+    pub max_scan: usize,
+    /// Number of entries outside of the window scanned for them.
+    pub missed: usize,
+}
+
+/// Size in bytes of the lookup table made by gen-table.
+const TABLE_SIZE: u64 = 0x800_0000 * 8;
+
+/// Values of zeta for which the entries of the table are checked on load.
+const SAMPLE_ZETAS: [u64; 8] = [
+    1, 2, 3, 0x123_4567, 0x2aa_aaaa, 0x555_5555, 0x765_4321, 0x7ff_ffff,
+];
+
+/// Number of table entries in a (small) page of memory.
+const PAGE_ENTRIES: usize = 4096 / 8;
+
+impl LookupTable {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with_progress(path, |_, _| {})
+    }
+
+    /// Loads the table like `open`, reporting how many of its bytes have been
+    /// loaded so far (and how many there are) as it goes.
+    pub fn open_with_progress<P, F>(path: P, progress: F) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        F: FnMut(usize, usize),
+    {
+        Self::open_with_options(path, TableOptions::default(), progress)
+    }
+
+    /// Loads the table like `open_with_progress`, setting up its memory as
+    /// configured by some options first.
+    pub fn open_with_options<P, F>(path: P, options: TableOptions, mut progress: F) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        F: FnMut(usize, usize),
+    {
+        let mut raw_table_file = File::open(path)?;
+
+        let size = raw_table_file.metadata()?.len();
+
+        if size != TABLE_SIZE {
+            return Err(Error::other(format!(
+                "table is {} bytes instead of {}, is it really a table made by gen-table?",
+                size, TABLE_SIZE
+            )));
+        }
+
+        #[cfg(unix)]
+        if options.mmap {
+            let table = Self {
+                table: Entries::Mapped(MappedEntries::map(&raw_table_file, options.prefault)?),
+                stride: Stride::new(options.stride),
+                wide_scans: AtomicUsize::new(0),
+            };
+
+            progress(size as usize, size as usize);
+
+            if !options.skip_checks {
+                table.verify()?;
+            }
+
+            return Ok(table);
+        }
+
+        let mut table = vec![0; 0x800_0000].into_boxed_slice();
+
+        if options.hugepages {
+            advise_hugepages(&mut table);
+        }
+
+        if options.prefault {
+            for page in table.chunks_mut(PAGE_ENTRIES) {
+                // a volatile write can't be optimized out, unlike the zero
+                // already there
+                unsafe { std::ptr::write_volatile(&mut page[0], 0) };
+            }
+        }
+
+        let bytes: &mut [u8] = cast_slice_mut(&mut table);
+        let total = bytes.len();
+
+        for (index, chunk) in bytes.chunks_mut(1 << 26).enumerate() {
+            raw_table_file.read_exact(chunk)?;
+            progress(((index + 1) << 26).min(total), total);
+        }
+
+        let table = Self {
+            table: Entries::Owned(table),
+            stride: Stride::new(options.stride),
+            wide_scans: AtomicUsize::new(0),
+        };
+
+        if !options.skip_checks {
+            table.verify()?;
+        }
+
+        Ok(table)
+    }
+
+    /// Computes the table `gen-table` makes for a stride in memory instead
+    /// of loading it, which takes a few seconds and as much memory as the
+    /// table itself.
+    pub fn generate(stride: u64) -> Self {
+        let stride = Stride::new(stride);
+
+        let mut table: Box<[u64]> = (0..0x800_0000)
+            .map(|zeta: u64| {
+                let product = stride.mult * (-W(zeta));
+                (((-(product >> 27)) << 27) | (product & W(0x7ff_ffff))).0
+            })
+            .collect();
+
+        table.sort_unstable();
+
+        Self {
+            table: Entries::Owned(table),
+            stride,
+            wide_scans: AtomicUsize::new(0),
+        }
+    }
+
+    /// Checks that the table holds the entries computed for a few sample
+    /// values, to catch a wrong or corrupted file before it is relied on.
+    pub fn verify(&self) -> Result<()> {
+        for &zeta in &SAMPLE_ZETAS {
+            let product = self.stride.mult * (-W(zeta));
+            let (n, beta) = (-(product >> 27), product & W(0x7ff_ffff));
+
+            if self.scan_table(n) != Some(beta) {
+                return Err(Error::other(format!(
+                    "table has no entry 0x{:09X} -> 0x{:07X} (computed for zeta = {}), \
+                     is it really a table made by gen-table{}?",
+                    n.0 & 0x1f_ffff_ffff,
+                    beta,
+                    zeta,
+                    match self.stride.outputs {
+                        1 => String::new(),
+                        outputs => format!(" with --stride {}", outputs),
+                    }
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of outputs separating the observed outputs the
+    /// table was made for, which is 1 for consecutive outputs.
+    pub fn stride(&self) -> u64 {
+        self.stride.outputs
+    }
+
+    /// Makes the table the one for the first of some strides whose sample
+    /// entries it holds (see `verify`), returning that stride, as nothing
+    /// else tells apart the tables made for different strides. The table is
+    /// left as it was if it holds the entries of none of them.
+    pub fn identify_stride<I: IntoIterator<Item = u64>>(&mut self, strides: I) -> Option<u64> {
+        let original = self.stride;
+
+        for outputs in strides {
+            self.stride = Stride::new(outputs);
+
+            if self.verify().is_ok() {
+                return Some(self.stride.outputs);
+            }
+        }
+
+        self.stride = original;
+        None
+    }
+
+    /// Returns the number of entries in the table.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns whether the table has no entries, which it never does.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Computes statistics about how far entries lie from the position
+    /// queries estimate for them.
+    pub fn statistics(&self) -> TableStatistics {
+        let mut stats = TableStatistics {
+            entries: self.table.len(),
+            buckets: 0,
+            max_bucket: 0,
+            min_offset: isize::MAX,
+            max_offset: isize::MIN,
+            mean_scan: 0.0,
+            max_scan: 0,
+            missed: 0,
+        };
+
+        let (mut bucket, mut bucket_size) = (None, 0);
+        let mut total_scan = 0;
+
+        for (index, entry) in self.table.iter().enumerate() {
+            let (lo, hi, estimate) = Self::scan_window(entry >> 27);
+
+            if bucket == Some(estimate) {
+                bucket_size += 1;
+            } else {
+                bucket = Some(estimate);
+                bucket_size = 1;
+                stats.buckets += 1;
+            }
+
+            stats.max_bucket = stats.max_bucket.max(bucket_size);
+
+            let offset = index as isize - estimate;
+            stats.min_offset = stats.min_offset.min(offset);
+            stats.max_offset = stats.max_offset.max(offset);
+
+            if index < lo || index > hi {
+                stats.missed += 1;
+            } else {
+                total_scan += index - lo + 1;
+                stats.max_scan = stats.max_scan.max(index - lo + 1);
+            }
+        }
+
+        let found = stats.entries - stats.missed;
+        stats.mean_scan = total_scan as f64 / found.max(1) as f64;
+        stats
+    }
+
+    /// Queries the lookup table for its value given N.
+    pub fn query(&self, n: W<u64>) -> Option<W<u64>> {
+        if let Some(beta) = self.scan_table(n) {
+            return Some(beta);
+        }
+
+        if let Some(beta) = self.scan_table(-n) {
+            return Some(-beta);
+        }
+
+        if n == W(1) {
+            return None;
+        }
+
+        if let Some(beta) = self.scan_table(W(1) + n) {
+            return Some(beta - W(0x800_0000));
+        }
+
+        if n == -W(1) {
+            return None;
+        }
+
+        if let Some(beta) = self.scan_table(W(1) - n) {
+            return Some(W(0x800_0000) - beta);
+        }
+
+        None
+    }
+
+    /// Returns the bounds of the window scanned for a key, along with the
+    /// position estimated for it.
+    fn scan_window(n2: u64) -> (usize, usize, isize) {
+        let estimate = (n2 >> 10) as isize;
+
+        let lo = (estimate - 2).max(0) as usize;
+        let hi = (estimate + 10).min(0x7ff_ffff) as usize;
+
+        (lo, hi, estimate)
+    }
+
+    fn scan_table(&self, n: W<u64>) -> Option<W<u64>> {
+        let n2 = n.0 & 0x1f_ffff_ffff;
+        let (lo, hi, _) = Self::scan_window(n2);
+
+        for entry in &self.table[lo..=hi] {
+            if entry >> 27 == n2 {
+                return Some(W(entry & 0x7ff_ffff));
+            }
+        }
+
+        // the table is sorted, so the entry can only be outside the window
+        // if the window doesn't bracket it; this is never the case for the
+        // table made by gen-table, but search the whole table if it happens
+        if self.table[lo] >> 27 < n2 && self.table[hi] >> 27 > n2 {
+            return None;
+        }
+
+        let index = self.table.partition_point(|entry| entry >> 27 < n2);
+
+        match self.table.get(index) {
+            Some(entry) if entry >> 27 == n2 => {
+                self.wide_scans.fetch_add(1, Ordering::Relaxed);
+                Some(W(entry & 0x7ff_ffff))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns how many queries found their entry outside of the window
+    /// scanned around its estimated position, by searching the whole table.
+    pub fn wide_scans(&self) -> usize {
+        self.wide_scans.load(Ordering::Relaxed)
+    }
+}
+
+/// Cancels the searches of the predictors it is given to, so that they can be
+/// stopped from another thread without waiting for them: clones share the
+/// same flag, which searches check between chunks of work before giving up.
+///
+/// A cancelled token stays cancelled, so a predictor must be given a new one
+/// (see `Predictor::set_cancellation`) to search again.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every search checking this token or any of its clones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Tracks the candidate states of a generator. The lookup table is shared
+/// through an `Arc`, so that any number of predictors can use a single copy.
+///
+/// Predictors are `Send` and `Sync`: they own their state apart from the
+/// table, which is never modified, and only change it through `&mut self`.
+/// A predictor can thus be moved to another thread, or queried from several
+/// threads at once, while predictors on other threads share its table.
+/// A clone shares the table and the cancellation token too.
+#[derive(Clone)]
+pub struct Predictor {
+    table: Arc<LookupTable>,
+    last_output: u32,
+    triple: Triple,
+    cancellation: CancellationToken,
+}
+
+impl Predictor {
+    /// Initializes the output predictor with four initial outputs, handing
+    /// back the table if they cannot have been produced by PCG-XSH-RR. The
+    /// table can be given by value, or shared as an `Arc<LookupTable>`.
+    ///
+    /// The rotations of the first three outputs are guessed in lexicographic
+    /// order, and the first guess consistent with the fourth output is kept,
+    /// so the same outputs always initialize the same predictor.
+    pub fn try_new<T: Into<Arc<LookupTable>>>(
+        table: T,
+        outputs: [u32; 4],
+    ) -> std::result::Result<Self, Arc<LookupTable>> {
+        Self::try_new_with_progress(table, outputs, |_, _| {})
+    }
+
+    /// Initializes the predictor like `try_new`, reporting how many of the
+    /// rotation guesses have been tried so far (and how many there are).
+    pub fn try_new_with_progress<T, F>(
+        table: T,
+        outputs: [u32; 4],
+        progress: F,
+    ) -> std::result::Result<Self, Arc<LookupTable>>
+    where
+        T: Into<Arc<LookupTable>>,
+        F: FnMut(usize, usize),
+    {
+        Self::try_new_with_cancellation(table, outputs, &CancellationToken::new(), progress)
+    }
+
+    /// Initializes the predictor like `try_new_with_progress`, giving up when
+    /// a token is cancelled, in which case the table is handed back as if the
+    /// outputs were inconsistent. The predictor keeps the token to cancel its
+    /// later searches (see `Candidates::enumerate` and the observations which
+    /// branch it).
+    pub fn try_new_with_cancellation<T, F>(
+        table: T,
+        outputs: [u32; 4],
+        cancellation: &CancellationToken,
+        mut progress: F,
+    ) -> std::result::Result<Self, Arc<LookupTable>>
+    where
+        T: Into<Arc<LookupTable>>,
+        F: FnMut(usize, usize),
+    {
+        let table = table.into();
+        let stride = table.stride;
+
+        for (s0_rot, s1_rot, s2_rot) in iproduct!(0..32, 0..32, 0..32) {
+            if s1_rot == 0 && s2_rot == 0 {
+                if cancellation.is_cancelled() {
+                    return Err(table);
+                }
+
+                progress(s0_rot as usize * 1024, 32768);
+            }
+
+            let s0_star = invert_xsh_rr(s0_rot, outputs[0]) >> 27;
+            let s1_star = invert_xsh_rr(s1_rot, outputs[1]) >> 27;
+            let s2_star = invert_xsh_rr(s2_rot, outputs[2]) >> 27;
+
+            let n = (stride.mult * (s1_star - s0_star) + (s1_star - s2_star)) & W(0x1f_ffff_ffff);
+
+            if let Some(beta) = table.query(n) {
+                let Some(epsilon) = EpsilonInterval::FULL.shift(beta) else {
+                    continue;
+                };
+
+                if Self::test_state(&stride, s1_star, s2_star, epsilon, beta, outputs[3]) {
+                    return Ok(Self {
+                        table,
+                        last_output: outputs[3],
+                        triple: Triple {
+                            sj_star: s1_star,
+                            sk_star: s2_star,
+
+                            beta,
+
+                            epsilon,
+                        },
+                        cancellation: cancellation.clone(),
+                    });
+                }
+            }
+        }
+
+        Err(table)
+    }
+
+    /// Initializes the output predictor with any number of consecutive
+    /// outputs (at least four), submitting those past the first four.
+    pub fn from_outputs<T: Into<Arc<LookupTable>>>(
+        table: T,
+        outputs: &[u32],
+    ) -> std::result::Result<Self, Inconsistency> {
+        Self::from_outputs_with_cancellation(table, outputs, &CancellationToken::new())
+    }
+
+    /// Initializes the predictor like `from_outputs`, giving up when a token
+    /// is cancelled (see `try_new_with_cancellation`).
+    pub fn from_outputs_with_cancellation<T: Into<Arc<LookupTable>>>(
+        table: T,
+        outputs: &[u32],
+        cancellation: &CancellationToken,
+    ) -> std::result::Result<Self, Inconsistency> {
+        let initial = match outputs {
+            [a, b, c, d, ..] => [*a, *b, *c, *d],
+            _ => return Err(Inconsistency::TooFewOutputs(outputs.len())),
+        };
+
+        let mut predictor =
+            Self::try_new_with_cancellation(table, initial, cancellation, |_, _| {}).map_err(
+                |_| match cancellation.is_cancelled() {
+                    true => Inconsistency::Cancelled,
+                    false => Inconsistency::Initialization,
+                },
+            )?;
+
+        for (index, &output) in outputs.iter().enumerate().skip(4) {
+            predictor
+                .submit_next_output(output)
+                .map_err(|_| Inconsistency::Output(index))?;
+        }
+
+        Ok(predictor)
+    }
+
+    /// Restores a predictor from a snapshot taken with the same table.
+    pub fn from_snapshot<T: Into<Arc<LookupTable>>>(table: T, snapshot: Snapshot) -> Self {
+        Self {
+            table: table.into(),
+            last_output: snapshot.last_output,
+            triple: snapshot.triple,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Replaces the token cancelling the searches of the predictor.
+    pub fn set_cancellation(&mut self, cancellation: CancellationToken) {
+        self.cancellation = cancellation;
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Drops the state of the predictor, handing back its table.
+    pub fn into_table(self) -> Arc<LookupTable> {
+        self.table
+    }
+
+    /// Returns the table of the predictor, to share with other predictors.
+    pub fn table(&self) -> &Arc<LookupTable> {
+        &self.table
+    }
+
+    /// Returns the state of the predictor, minus the lookup table.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            last_output: self.last_output,
+            triple: self.triple,
+        }
+    }
+
+    /// Submits the next output produced by the target PCG generator.
+    pub fn submit_next_output(&mut self, output: u32) -> Result<()> {
+        for sk_rot in 0..32 {
+            let si_star = self.triple.sj_star;
+            let sj_star = self.triple.sk_star;
+            let sk_star = invert_xsh_rr(sk_rot, self.last_output) >> 27;
+
+            let stride = self.table.stride;
+            let n = (stride.mult * (sj_star - si_star) + (sj_star - sk_star)) & W(0x1f_ffff_ffff);
+
+            if let Some(beta) = self.table.query(n) {
+                let Some(epsilon) = self.triple.epsilon.shift(beta) else {
+                    continue;
+                };
+
+                if Self::test_state(&stride, sj_star, sk_star, epsilon, beta, output) {
+                    self.triple = Triple {
+                        sj_star,
+                        sk_star,
+
+                        beta,
+
+                        epsilon,
+                    };
+
+                    self.last_output = output;
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(Error::other("output sequence not produced by PCG-XSH-RR"))
+    }
+
+    /// Returns the last output submitted to the predictor.
+    pub fn last_output(&self) -> u32 {
+        self.last_output
+    }
+
+    /// Predicts the next PCG output (the next observed one, if only every
+    /// k-th output is observed).
+    pub fn predict_future_output(&self) -> Prediction {
+        Self::predict_from(&self.table.stride, &self.triple)
+    }
+
+    /// Predicts the next output from the first and last candidates of a
+    /// triple.
+    fn predict_from(stride: &Stride, triple: &Triple) -> Prediction {
+        let epsilon2 = triple.epsilon.first();
+        let epsilon1 = W(epsilon2 as u64) - triple.beta;
+
+        let sj = (triple.sj_star << 27) + epsilon1;
+        let sk = (triple.sk_star << 27) + W(epsilon2 as u64);
+
+        let increment = stride.increment(sj, sk);
+
+        let state = stride.mult * sk + increment;
+
+        let output1 = compute_xsh_rr(stride.mult * state + increment);
+
+        let epsilon2 = triple.epsilon.last();
+        let epsilon1 = W(epsilon2 as u64) - triple.beta;
+
+        let sj = (triple.sj_star << 27) + epsilon1;
+        let sk = (triple.sk_star << 27) + W(epsilon2 as u64);
+
+        let increment = stride.increment(sj, sk);
+
+        let state = stride.mult * sk + increment;
+
+        let output2 = compute_xsh_rr(stride.mult * state + increment);
+
+        Prediction::new(output1, output2)
+    }
+
+    /// Returns the number of outputs separating the outputs submitted to the
+    /// predictor, which is 1 unless its table was made for a stride.
+    pub fn stride(&self) -> u64 {
+        self.table.stride()
+    }
+
+    /// Returns the number of candidate states left.
+    pub fn remaining_candidate_count(&self) -> usize {
+        self.triple.count()
+    }
+
+    /// Returns the set of all remaining candidate states.
+    pub fn remaining_candidates(&self) -> Vec<Pcg32State> {
+        let uncancellable = CancellationToken::new();
+
+        (self.triple)
+            .collect_candidates(&self.table.stride, 0, &uncancellable)
+            .unwrap()
+    }
+
+    /// Returns the remaining candidate states accepted by a filter.
+    pub fn remaining_candidates_where<F>(&self, mut filter: F) -> Vec<Pcg32State>
+    where
+        F: FnMut(&Pcg32State) -> bool,
+    {
+        self.candidates_iter()
+            .filter(|state| filter(state))
+            .collect()
+    }
+
+    /// Returns a lazy iterator over all remaining candidate states.
+    pub fn candidates_iter(&self) -> impl ExactSizeIterator<Item = Pcg32State> + '_ {
+        self.triple
+            .epsilon
+            .iter()
+            .map(move |epsilon_k| self.candidate(epsilon_k))
+    }
+
+    /// Returns the remaining candidate state at some index, in the order they
+    /// are enumerated by `candidates_iter`.
+    pub fn nth_candidate(&self, index: usize) -> Option<Pcg32State> {
+        self.triple
+            .epsilon
+            .nth(index)
+            .map(|epsilon_k| self.candidate(epsilon_k))
+    }
+
+    fn candidate(&self, epsilon_k: i32) -> Pcg32State {
+        self.triple.candidate(epsilon_k, &self.table.stride)
+    }
+
+    /// Points the predictor at the candidates of a snapshot.
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.last_output = snapshot.last_output;
+        self.triple = snapshot.triple;
+    }
+
+    /// Returns whether any remaining candidate state satisfies a predicate.
+    pub fn any_candidate_matches<F>(&self, mut predicate: F) -> bool
+    where
+        F: FnMut(&Pcg32State) -> bool,
+    {
+        self.candidates_iter().any(|state| predicate(&state))
+    }
+
+    fn test_state(
+        stride: &Stride,
+        sj_star: W<u64>,
+        sk_star: W<u64>,
+        epsilon: EpsilonInterval,
+        beta: W<u64>,
+        output: u32,
+    ) -> bool {
+        let (min, max) = (epsilon.first(), epsilon.last());
+
+        let epsilon1 = W(min as u64) - beta;
+        let sj = (sj_star << 27) + epsilon1;
+        let sk = (sk_star << 27) + W(min as u64);
+
+        let increment = stride.increment(sj, sk);
+        let min_state = stride.mult * sk + increment;
+
+        if output == compute_xsh_rr(min_state) {
+            return true;
+        }
+
+        let epsilon1 = W(max as u64) - beta;
+        let sj = (sj_star << 27) + epsilon1;
+        let sk = (sk_star << 27) + W(max as u64);
+
+        let increment = stride.increment(sj, sk);
+        let max_state = stride.mult * sk + increment;
+
+        if output == compute_xsh_rr(max_state) {
+            return true;
+        }
+
+        false
+    }
+}
+
+/// The state of a predictor, which can be persisted and later restored.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snapshot {
+    last_output: u32,
+    triple: Triple,
+}
+
+/// Where a sequence of outputs stopped being consistent with PCG-XSH-RR.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Inconsistency {
+    /// Fewer than the four outputs needed for initialization were given.
+    TooFewOutputs(usize),
+    /// The first four outputs cannot have been produced by PCG-XSH-RR.
+    Initialization,
+    /// The output at this (0-based) index contradicts the previous ones.
+    Output(usize),
+    /// No candidate state has any of the increments known to be possible.
+    Increment,
+    /// The search was cancelled (see `CancellationToken`) before it could
+    /// tell whether the outputs are consistent.
+    Cancelled,
+    /// The solver of this name isn't registered, lacks what it needs to get
+    /// started, or doesn't track candidate states (see `BreakerBuilder::solver`).
+    /// Only serialized, as solver names are static.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Solver(&'static str),
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooFewOutputs(count) => write!(f, "need 4 outputs, got {}", count),
+            Self::Initialization => write!(f, "first 4 outputs not produced by PCG-XSH-RR"),
+            Self::Output(index) => write!(f, "output #{} not produced by PCG-XSH-RR", index + 1),
+            Self::Increment => write!(f, "no candidate state has a known increment"),
+            Self::Cancelled => write!(f, "search cancelled"),
+            Self::Solver(name) => write!(f, "the `{}` solver can't initialize a breaker", name),
+        }
+    }
+}
+
+impl std::error::Error for Inconsistency {}
+
+/// A full PCG-XSH-RR generator state, laid out like the reference `pcg32_random_t`.
+///
+/// As in the reference implementation, `output` is the output the generator
+/// will produce next, after which it steps to the next state with `advance`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pcg32State {
+    /// The LCG state.
+    pub state: W<u64>,
+    /// The LCG increment, whose lowest bit is always set.
+    pub inc: W<u64>,
+}
+
+impl Pcg32State {
+    /// Creates a state, forcing the lowest bit of the increment to 1.
+    pub fn new(state: u64, inc: u64) -> Self {
+        Self {
+            state: W(state),
+            inc: W(inc | 1),
+        }
+    }
+
+    /// Creates the state `rand_pcg::Lcg64Xsh32::new(state, stream)` starts in.
+    pub fn from_rand_pcg(state: u64, stream: u64) -> Self {
+        let inc = W((stream << 1) | 1);
+
+        Self {
+            state: A * (W(state) + inc) + inc,
+            inc,
+        }
+    }
+
+    /// Returns the `(state, stream)` arguments which make
+    /// `rand_pcg::Lcg64Xsh32::new` start in this state.
+    pub fn to_rand_pcg(&self) -> (u64, u64) {
+        (
+            (A_INV * (self.state - self.inc) - self.inc).0,
+            self.inc.0 >> 1,
+        )
+    }
+
+    /// Creates the state `rand_pcg::Lcg64Xsh32::from_seed(seed)` starts in,
+    /// which is also how `SmallRng` was seeded while it was PCG-based.
+    pub fn from_rand_seed(seed: [u8; 16]) -> Self {
+        let mut words = [0u64; 2];
+        bytes_of_mut(&mut words).copy_from_slice(&seed);
+
+        let inc = W(u64::from_le(words[1]) | 1);
+
+        Self {
+            state: A * (W(u64::from_le(words[0])) + inc) + inc,
+            inc,
+        }
+    }
+
+    /// Returns the seed which makes `rand_pcg::Lcg64Xsh32::from_seed` start in
+    /// this state. The lowest bit of the seed's ninth byte is discarded when
+    /// seeding, so it is conventionally returned as 1.
+    pub fn to_rand_seed(&self) -> [u8; 16] {
+        let (state, _) = self.to_rand_pcg();
+
+        let mut seed = [0; 16];
+        seed[..8].copy_from_slice(&state.to_le_bytes());
+        seed[8..].copy_from_slice(&self.inc.0.to_le_bytes());
+        seed
+    }
+
+    /// Returns the output produced from this state.
+    pub fn output(&self) -> u32 {
+        compute_xsh_rr(self.state)
+    }
+
+    /// Steps the state forwards once.
+    pub fn advance(&mut self) {
+        self.state = pcg_math::advance(self.state, self.inc);
+    }
+
+    /// Steps the state backwards once.
+    pub fn rewind(&mut self) {
+        self.state = pcg_math::rewind(self.state, self.inc);
+    }
+
+    /// Returns the output produced from this state and steps forwards, like
+    /// `pcg32_random_r`, so that a state can double as a generator.
+    pub fn next_u32(&mut self) -> u32 {
+        let output = self.output();
+        self.advance();
+        output
+    }
+
+    /// Returns an output uniformly distributed below `bound` like
+    /// `pcg32_boundedrand_r`, along with how many outputs its rejection loop
+    /// consumed to produce it.
+    pub fn next_bounded(&mut self, bound: u32) -> (u32, usize) {
+        let threshold = bound.wrapping_neg() % bound;
+        let mut consumed = 0;
+
+        loop {
+            let output = self.next_u32();
+            consumed += 1;
+
+            if output >= threshold {
+                return (output % bound, consumed);
+            }
+        }
+    }
+
+    /// Advances the state by some number of steps, which may wrap around to
+    /// rewind it instead as the generator has a period of 2^64.
+    pub fn jump(&self, delta: u64) -> Self {
+        JumpTable::shared().jump(self, delta)
+    }
+
+    /// Returns the number of steps needed to advance to the given state.
+    pub fn distance_to(&self, target: W<u64>) -> u64 {
+        JumpTable::shared().distance(self, target)
+    }
+
+    /// Returns the output produced from the state following this one.
+    pub fn next_output(&self) -> u32 {
+        compute_xsh_rr(A * self.state + self.inc)
+    }
+}
+
+/// Number of values the low 27 bits of a state, which its output doesn't
+/// depend on, can take.
+const EPSILON_VALUES: i64 = 1 << 27;
+
+/// The values of the low 27 bits of the newest state of a triple (epsilon_k)
+/// still consistent with the outputs, as the half-open interval `min..max`.
+/// An interval is never empty and always lies within `0..2^27`, which is
+/// checked whenever one is made (or deserialized), so that counting and
+/// indexing its values can't overflow or yield values outside of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedEpsilonInterval"))]
+struct EpsilonInterval {
+    min: i32,
+    max: i32,
+}
+
+/// An interval as deserialized, before it is checked.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UncheckedEpsilonInterval {
+    min: i32,
+    max: i32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedEpsilonInterval> for EpsilonInterval {
+    type Error = &'static str;
+
+    fn try_from(unchecked: UncheckedEpsilonInterval) -> std::result::Result<Self, Self::Error> {
+        let (min, max) = (unchecked.min as i64, unchecked.max as i64);
+
+        Self::clamped(min, max)
+            .filter(|interval| (interval.min as i64, interval.max as i64) == (min, max))
+            .ok_or("epsilon interval empty or out of bounds")
+    }
+}
+
+impl EpsilonInterval {
+    /// Every value of the low bits, before any output has been checked.
+    const FULL: Self = Self {
+        min: 0,
+        max: EPSILON_VALUES as i32,
+    };
+
+    /// Returns the part of `min..max` within `0..2^27`, unless it is empty.
+    fn clamped(min: i64, max: i64) -> Option<Self> {
+        let (min, max) = (min.max(0), max.min(EPSILON_VALUES));
+
+        // both bounds are now within 0..=2^27, so they fit in an i32
+        (min < max).then_some(Self {
+            min: min as i32,
+            max: max as i32,
+        })
+    }
+
+    /// Returns the values of epsilon_k consistent with those of epsilon_j in
+    /// this interval, given a table entry relating them as
+    /// `epsilon_k = epsilon_j + beta`, unless there are none. Beta is signed
+    /// (wrapped around in a `u64`) and within 2^27 either way for entries of
+    /// a valid table, but sums saturate so that any value is safe.
+    fn shift(self, beta: W<u64>) -> Option<Self> {
+        let beta = beta.0 as i64;
+
+        Self::clamped(
+            (self.min as i64).saturating_add(beta),
+            (self.max as i64).saturating_add(beta),
+        )
+    }
+
+    /// Returns the number of values in the interval, which is at least one.
+    fn len(self) -> usize {
+        (self.max - self.min) as usize
+    }
+
+    fn first(self) -> i32 {
+        self.min
+    }
+
+    fn last(self) -> i32 {
+        self.max - 1
+    }
+
+    /// Returns the value at some index, in increasing order.
+    fn nth(self, index: usize) -> Option<i32> {
+        (index < self.len()).then(|| self.min + index as i32)
+    }
+
+    fn iter(self) -> std::ops::Range<i32> {
+        self.min..self.max
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Triple {
+    sj_star: W<u64>,
+    sk_star: W<u64>,
+
+    beta: W<u64>,
+
+    epsilon: EpsilonInterval,
+}
+
+impl Triple {
+    fn count(&self) -> usize {
+        self.epsilon.len()
+    }
+
+    /// Returns what determines the candidates of the triple besides the
+    /// interval, so that the intervals of triples with the same key hold the
+    /// same candidates wherever they overlap.
+    fn key(&self) -> (W<u64>, W<u64>, W<u64>) {
+        (self.sj_star, self.sk_star, self.beta)
+    }
+
+    fn candidate(&self, epsilon_k: i32, stride: &Stride) -> Pcg32State {
+        let sj = (self.sj_star << 27) + W(epsilon_k as u64) - self.beta;
+        let sk = (self.sk_star << 27) + W(epsilon_k as u64);
+
+        let inc = stride.increment(sj, sk);
+
+        stride.state(stride.mult * sk + inc, inc)
+    }
+
+    fn candidates<'a>(&'a self, stride: &'a Stride) -> impl Iterator<Item = Pcg32State> + 'a {
+        self.epsilon
+            .iter()
+            .map(move |epsilon_k| self.candidate(epsilon_k, stride))
+    }
+
+    /// Collects the candidates in the order of `candidates`, splitting the
+    /// work between up to `threads` threads (or one per core if zero) when
+    /// there are enough candidates, unless a token is cancelled first.
+    fn collect_candidates(
+        &self,
+        stride: &Stride,
+        threads: usize,
+        cancellation: &CancellationToken,
+    ) -> Option<Vec<Pcg32State>> {
+        let threads = match threads {
+            _ if self.count() < PARALLEL_CANDIDATES => 1,
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            threads => threads,
+        };
+
+        let mut states = vec![Pcg32State::new(0, 0); self.count()];
+        let chunk = self.count().div_ceil(threads).max(1);
+
+        let fill = |states: &mut [Pcg32State], first: usize| {
+            for (index, states) in states.chunks_mut(CANCELLATION_CHUNK).enumerate() {
+                if cancellation.is_cancelled() {
+                    return;
+                }
+
+                let epsilons = self.epsilon.iter().skip(first + index * CANCELLATION_CHUNK);
+
+                for (state, epsilon_k) in states.iter_mut().zip(epsilons) {
+                    *state = self.candidate(epsilon_k, stride);
+                }
+            }
+        };
+
+        if threads == 1 {
+            fill(&mut states, 0);
+        } else {
+            std::thread::scope(|scope| {
+                let fill = &fill;
+
+                for (index, states) in states.chunks_mut(chunk).enumerate() {
+                    scope.spawn(move || fill(states, index * chunk));
+                }
+            });
+        }
+
+        (!cancellation.is_cancelled()).then_some(states)
+    }
+}
+
+/// Number of candidates from which collecting them is split between threads.
+const PARALLEL_CANDIDATES: usize = 1 << 16;
+
+/// Number of candidates collected between checks for cancellation.
+const CANCELLATION_CHUNK: usize = 1 << 16;
+
+/// How an observation is derived from an output and the one before it, when
+/// outputs are not observed directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Derivation {
+    /// The output XORed with the one before it.
+    Xor,
+    /// The output minus the one before it, wrapping around.
+    Difference,
+}
+
+impl Derivation {
+    /// Returns the observation derived from an output and the one before it.
+    pub fn derive(self, previous: u32, output: u32) -> u32 {
+        match self {
+            Self::Xor => output ^ previous,
+            Self::Difference => output.wrapping_sub(previous),
+        }
+    }
+
+    /// Returns the output an observation was derived from, given the output
+    /// before it.
+    pub fn underive(self, previous: u32, observation: u32) -> u32 {
+        match self {
+            Self::Xor => observation ^ previous,
+            Self::Difference => previous.wrapping_add(observation),
+        }
+    }
+}
+
+/// The next output as predicted from the candidates left, which the first
+/// and last of them agree on unless it is ambiguous.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Prediction {
+    Unique(u32),
+    /// The distinct outputs predicted, in the order the candidates first
+    /// predict them (only the first and last implicitly tracked candidates
+    /// are consulted), so that the same candidates always list them in the
+    /// same order.
+    Ambiguous(Vec<u32>),
+}
+
+impl Prediction {
+    /// Returns the prediction made by the first and last candidates.
+    pub fn new(first: u32, last: u32) -> Self {
+        if first == last {
+            Self::Unique(first)
+        } else {
+            Self::Ambiguous(vec![first, last])
+        }
+    }
+
+    /// Returns the prediction made by every candidate, given the output each
+    /// of them predicts (at least one), keeping the first of any duplicates.
+    pub fn from_outputs<I: IntoIterator<Item = u32>>(outputs: I) -> Self {
+        let mut seen = HashSet::new();
+        let mut outputs: Vec<u32> = outputs
+            .into_iter()
+            .filter(|&output| seen.insert(output))
+            .collect();
+
+        match outputs.len() {
+            1 => Self::Unique(outputs.pop().unwrap()),
+            _ => Self::Ambiguous(outputs),
+        }
+    }
+
+    /// Returns every output predicted, without duplicates, the one of the
+    /// first candidate first.
+    pub fn outputs(&self) -> &[u32] {
+        match self {
+            Self::Unique(output) => std::slice::from_ref(output),
+            Self::Ambiguous(outputs) => outputs,
+        }
+    }
+
+    pub fn into_outputs(self) -> Vec<u32> {
+        match self {
+            Self::Unique(output) => vec![output],
+            Self::Ambiguous(outputs) => outputs,
+        }
+    }
+
+    /// Returns the output predicted by the first candidate, which is the
+    /// prediction to go with when a single output is needed.
+    pub fn first(&self) -> u32 {
+        self.outputs()[0]
+    }
+
+    /// Returns whether an output is among those predicted.
+    pub fn contains(&self, output: u32) -> bool {
+        self.outputs().contains(&output)
+    }
+}
+
+/// A single bit leaked by the generator in place of a full output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BitLeak {
+    /// The lowest bit of the output.
+    Parity,
+    /// The highest bit of the output.
+    TopBit,
+    /// The result of `rand`'s `gen_bool(0.5)`, which draws a `u64` from two
+    /// outputs (the second being its high half) and checks it is below 2^63.
+    Bool,
+}
+
+impl BitLeak {
+    /// Returns how many outputs the generator consumes to leak one bit.
+    pub fn outputs(self) -> usize {
+        match self {
+            Self::Parity | Self::TopBit => 1,
+            Self::Bool => 2,
+        }
+    }
+
+    /// Returns the bit leaked by the last of the outputs consumed, which is
+    /// the only one the bit depends on.
+    pub fn observe(self, output: u32) -> bool {
+        match self {
+            Self::Parity => output & 1 == 1,
+            Self::TopBit => output >> 31 == 1,
+            Self::Bool => output >> 31 == 0,
+        }
+    }
+}
+
+/// Returns the value an output is reduced to in `0..range` by scaling it as
+/// a float, as in `(output as f64 / 2^32 * range as f64) as u32`, which keeps
+/// the highest bits of the output rather than the lowest like a modulo.
+pub fn scale_output(output: u32, range: u32) -> u32 {
+    (output as f64 / 4_294_967_296.0 * range as f64) as u32
+}
+
+/// The multiplier of the RXS-M-XS output function.
+const RXS_M_XS_MULT: W<u64> = W(12_605_985_483_714_917_081);
+
+/// The inverse of the multiplier of the RXS-M-XS output function.
+const RXS_M_XS_MULT_INV: W<u64> = W(15_009_553_638_781_119_849);
+
+/// An output function of the PCG members with 64-bit state and 64-bit
+/// outputs. These functions are bijections, so every output leaks the whole
+/// state which produced it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Invertible64 {
+    /// XSL-RR-RR, as in `pcg_output_xsl_rr_rr_64_64`.
+    XslRrRr,
+    /// RXS-M-XS, as in `pcg_output_rxs_m_xs_64_64`.
+    RxsMXs,
+}
+
+impl Invertible64 {
+    /// Returns the output produced from a state.
+    pub fn output(self, state: W<u64>) -> u64 {
+        match self {
+            Self::XslRrRr => {
+                let (high, low) = ((state.0 >> 32) as u32, state.0 as u32);
+                let new_low = (high ^ low).rotate_right((state.0 >> 59) as u32);
+                let new_high = high.rotate_right(new_low & 31);
+
+                (new_high as u64) << 32 | new_low as u64
+            }
+            Self::RxsMXs => {
+                let word = ((state >> ((state.0 >> 59) as usize + 5)) ^ state) * RXS_M_XS_MULT;
+                ((word >> 43) ^ word).0
+            }
+        }
+    }
+
+    /// Returns the state which produced an output.
+    pub fn invert(self, output: u64) -> W<u64> {
+        match self {
+            Self::XslRrRr => {
+                let (new_high, new_low) = ((output >> 32) as u32, output as u32);
+                let high = new_high.rotate_left(new_low & 31);
+                let low = new_low.rotate_left(high >> 27) ^ high;
+
+                W((high as u64) << 32 | low as u64)
+            }
+            Self::RxsMXs => {
+                let xorshifted = W(output ^ (output >> 43)) * RXS_M_XS_MULT_INV;
+
+                // the shift leaves the top bits alone, so they give it away
+                let shift = (xorshifted.0 >> 59) as usize + 5;
+                let mut state = xorshifted;
+
+                for _ in 0..64 / shift {
+                    state = xorshifted ^ (state >> shift);
+                }
+
+                state
+            }
+        }
+    }
+}
+
+/// Recovers the state which produced the first of some consecutive outputs
+/// of a generator with an invertible output function: the first two outputs
+/// give away the state and the increment, and any others are checked against
+/// them.
+pub fn recover_invertible_64(function: Invertible64, outputs: &[u64]) -> Option<Pcg32State> {
+    let [first, second, ..] = *outputs else {
+        return None;
+    };
+
+    let state = function.invert(first);
+    let inc = function.invert(second) - A * state;
+
+    if inc.0 & 1 == 0 {
+        return None;
+    }
+
+    let mut current = state;
+
+    for &output in outputs {
+        if function.output(current) != output {
+            return None;
+        }
+
+        current = A * current + inc;
+    }
+
+    Some(Pcg32State { state, inc })
+}
+
+/// Returns the top 53 bits of the output a double in [0, 1) was drawn from,
+/// as in `(output >> 11) as f64 / 2^53` (how JavaScript engines and numpy
+/// draw doubles from 64-bit outputs), or `None` if it can't have been drawn
+/// that way.
+pub fn double_output_bits(value: f64) -> Option<u64> {
+    let bits = value * 9_007_199_254_740_992.0;
+
+    if !(0.0..9_007_199_254_740_992.0).contains(&bits) || bits.fract() != 0.0 {
+        return None;
+    }
+
+    Some(bits as u64)
+}
+
+/// Recovers the state which produced the first of some consecutive doubles
+/// drawn from a generator with an invertible output function (see
+/// `double_output_bits`). Each double hides the lowest 11 bits of its output,
+/// which are searched for in the first two doubles, and the states they give
+/// are checked against the others, so at least three doubles are needed; the
+/// state is only returned if it is the only one consistent with them all.
+pub fn recover_invertible_64_from_doubles(
+    function: Invertible64,
+    doubles: &[f64],
+) -> Option<Pcg32State> {
+    let bits = doubles
+        .iter()
+        .map(|&value| double_output_bits(value))
+        .collect::<Option<Vec<u64>>>()?;
+
+    let [first, second, _, ..] = *bits else {
+        return None;
+    };
+
+    let states = |bits: u64| -> Vec<W<u64>> {
+        (0..1 << 11)
+            .map(|low| function.invert(bits << 11 | low))
+            .collect()
+    };
+
+    let (firsts, seconds) = (states(first), states(second));
+    let mut recovered = None;
+
+    for (&state, &next) in iproduct!(&firsts, &seconds) {
+        let inc = next - A * state;
+
+        if inc.0 & 1 == 0 {
+            continue;
+        }
+
+        let mut current = A * next + inc;
+
+        let consistent = bits[2..].iter().all(|&bits| {
+            let matches = function.output(current) >> 11 == bits;
+            current = A * current + inc;
+            matches
+        });
+
+        if consistent {
+            if recovered.is_some() {
+                return None;
+            }
+
+            recovered = Some(Pcg32State { state, inc });
+        }
+    }
+
+    recovered
+}
+
+/// The set of generator states still consistent with the observed outputs,
+/// which is `Send` and `Sync` like the predictor tracking them.
+#[derive(Clone)]
+pub enum Candidates {
+    /// Tracked implicitly by the predictor while many states remain.
+    Implicit(Predictor),
+    /// Enumerated explicitly once few enough states remain.
+    Explicit(Vec<Pcg32State>),
+    /// Tracked implicitly like `Implicit`, but split into several branches
+    /// of the predictor, one for each sequence of outputs consistent with
+    /// observations which don't reveal the outputs themselves.
+    Branched {
+        predictor: Predictor,
+        branches: Vec<Snapshot>,
+    },
+}
+
+impl Candidates {
+    /// Returns the number of candidate states left.
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Implicit(predictor) => predictor.remaining_candidate_count(),
+            Self::Explicit(states) => states.len(),
+            Self::Branched { branches, .. } => {
+                // the branches may overlap, as the predictor over-approximates
+                let mut intervals: Vec<_> = branches
+                    .iter()
+                    .map(|branch| (branch.triple.key(), branch.triple.epsilon))
+                    .collect();
+
+                intervals.sort_unstable_by_key(|&(key, epsilon)| (key, epsilon.min));
+
+                let mut count = 0;
+                let mut covered = None;
+
+                for (key, epsilon) in intervals {
+                    let reached = match covered {
+                        Some((covered_key, reached)) if covered_key == key => reached,
+                        _ => epsilon.min,
+                    };
+
+                    count += (epsilon.max - epsilon.min.max(reached)).max(0) as usize;
+                    covered = Some((key, reached.max(epsilon.max)));
+                }
+
+                count
+            }
+        }
+    }
+
+    /// Returns the number of candidates in every branch, counting those in
+    /// several branches as many times.
+    fn branched_count(branches: &[Snapshot]) -> usize {
+        branches.iter().map(|branch| branch.triple.count()).sum()
+    }
+
+    /// Returns how many bits of uncertainty about the state are left, as the
+    /// base 2 logarithm of the number of candidates (zero once recovered).
+    pub fn entropy(&self) -> f64 {
+        (self.count().max(1) as f64).log2()
+    }
+
+    /// Submits the next output produced by the target PCG generator.
+    pub fn submit_next_output(&mut self, output: u32) -> Result<()> {
+        match self {
+            Self::Implicit(predictor) => predictor.submit_next_output(output),
+            Self::Explicit(states) => match simd::retain_next_output(states, output) {
+                true => Ok(()),
+                false => Err(Error::other("output sequence not produced by PCG-XSH-RR")),
+            },
+            Self::Branched { .. } => self.branch_where(1, |_, next| next == output),
+        }
+    }
+
+    /// Submits several consecutive outputs at once. Explicitly enumerated
+    /// candidates are tested against every output in turn, with precomputed
+    /// constants jumping straight to the state producing it, instead of
+    /// making a pass over all of them for each output; they are left as they
+    /// were if no candidate produces all the outputs.
+    pub fn submit_next_outputs(&mut self, outputs: &[u32]) -> Result<()> {
+        match self {
+            Self::Explicit(states) => {
+                let table = JumpTable::shared();
+                let constants: Vec<_> = (1..=outputs.len() as u64)
+                    .map(|delta| table.constants(delta))
+                    .collect();
+
+                let matches = |state: &Pcg32State| {
+                    constants
+                        .iter()
+                        .zip(outputs)
+                        .all(|(&jump, &output)| JumpTable::apply(state, jump).output() == output)
+                };
+
+                if !states.iter().any(matches) {
+                    return Err(Error::other("output sequence not produced by PCG-XSH-RR"));
+                }
+
+                states.retain(matches);
+
+                if let Some(&jump) = constants.last() {
+                    for state in states.iter_mut() {
+                        *state = JumpTable::apply(state, jump);
+                    }
+                }
+
+                Ok(())
+            }
+            _ => outputs
+                .iter()
+                .try_for_each(|&output| self.submit_next_output(output)),
+        }
+    }
+
+    /// Submits an observation derived from the next output and the one
+    /// before it, rather than the next output itself.
+    pub fn submit_next_derived(&mut self, observation: u32, derivation: Derivation) -> Result<()> {
+        match self {
+            Self::Implicit(predictor) => predictor
+                .submit_next_output(derivation.underive(predictor.last_output(), observation)),
+            _ => self.submit_next_where(1, |previous, next| {
+                derivation.derive(previous, next) == observation
+            }),
+        }
+    }
+
+    /// Submits a single bit leaked by the target PCG generator, which has
+    /// consumed `leak.outputs()` outputs to produce it.
+    pub fn submit_next_bit(&mut self, bit: bool, leak: BitLeak) -> Result<()> {
+        self.submit_next_where(leak.outputs(), |_, next| leak.observe(next) == bit)
+    }
+
+    /// Submits an observation drawn from the next `outputs` outputs, which
+    /// depends only on the last two of them and is checked by a predicate
+    /// given those two in order.
+    pub fn submit_next_where<F>(&mut self, outputs: usize, accept: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        match self {
+            Self::Explicit(states) => {
+                let table = JumpTable::shared();
+                let (skip, step) = (
+                    table.constants(outputs as u64 - 1),
+                    table.constants(outputs as u64),
+                );
+
+                let matches = |state: &Pcg32State| {
+                    let last = JumpTable::apply(state, skip);
+                    accept(last.output(), last.next_output())
+                };
+
+                if !states.iter().any(matches) {
+                    return Err(Error::other("output sequence not produced by PCG-XSH-RR"));
+                }
+
+                states.retain(matches);
+
+                for state in states.iter_mut() {
+                    *state = JumpTable::apply(state, step);
+                }
+
+                Ok(())
+            }
+            _ => self.branch_where(outputs, accept),
+        }
+    }
+
+    /// Submits the next output reduced to `0..range` by float scaling (see
+    /// `scale_output`) rather than the output itself.
+    pub fn submit_next_scaled(&mut self, value: u32, range: u32) -> Result<()> {
+        self.submit_next_where(1, |_, next| scale_output(next, range) == value)
+    }
+
+    /// Steps the predictor through `outputs` outputs, of which only the last
+    /// must be accepted by a predicate given it and the output before it,
+    /// branching wherever the predictor can't tell which of its two predicted
+    /// outputs was produced. Branches which no longer have any candidates are
+    /// dropped, and the predictor is used directly again once a single branch
+    /// is left. The branches are left as they were if the token of the
+    /// predictor is cancelled in the meantime.
+    fn branch_where<F>(&mut self, outputs: usize, accept: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        let (mut predictor, branches) = match std::mem::replace(self, Self::Explicit(vec![])) {
+            Self::Implicit(predictor) => {
+                let snapshot = predictor.snapshot();
+                (predictor, vec![snapshot])
+            }
+            Self::Branched {
+                predictor,
+                branches,
+            } => (predictor, branches),
+            Self::Explicit(_) => unreachable!(),
+        };
+
+        let mut current = branches.clone();
+        let mut cancelled = false;
+
+        'steps: for step in 1..=outputs {
+            let mut next = Vec::with_capacity(current.len());
+
+            for branch in std::mem::take(&mut current) {
+                if predictor.cancellation.is_cancelled() {
+                    cancelled = true;
+                    break 'steps;
+                }
+
+                predictor.restore(branch);
+                for output in predictor.predict_future_output().into_outputs() {
+                    if step == outputs && !accept(branch.last_output, output) {
+                        continue;
+                    }
+
+                    predictor.restore(branch);
+
+                    if predictor.submit_next_output(output).is_ok() {
+                        next.push(predictor.snapshot());
+                    }
+                }
+            }
+
+            current = Self::merge_branches(next);
+        }
+
+        let result = if cancelled {
+            current = branches;
+            Err(Error::new(ErrorKind::Interrupted, "search cancelled"))
+        } else if current.is_empty() {
+            current = branches;
+            Err(Error::other("output sequence not produced by PCG-XSH-RR"))
+        } else {
+            Ok(())
+        };
+
+        *self = if current.len() == 1 {
+            predictor.restore(current[0]);
+            Self::Implicit(predictor)
+        } else {
+            Self::Branched {
+                predictor,
+                branches: current,
+            }
+        };
+
+        result
+    }
+
+    /// Merges the branches which hold the same candidates and have the same
+    /// last output wherever their intervals overlap or touch, as the
+    /// branches of different outputs often end up tracking.
+    fn merge_branches(mut branches: Vec<Snapshot>) -> Vec<Snapshot> {
+        branches.sort_unstable_by_key(|branch| {
+            (
+                branch.triple.key(),
+                branch.last_output,
+                branch.triple.epsilon.min,
+            )
+        });
+
+        let mut merged: Vec<Snapshot> = Vec::with_capacity(branches.len());
+
+        for branch in branches {
+            if let Some(last) = merged.last_mut() {
+                if last.triple.key() == branch.triple.key()
+                    && last.last_output == branch.last_output
+                    && branch.triple.epsilon.min <= last.triple.epsilon.max
+                {
+                    last.triple.epsilon.max =
+                        last.triple.epsilon.max.max(branch.triple.epsilon.max);
+                    continue;
+                }
+            }
+
+            merged.push(branch);
+        }
+
+        merged
+    }
+
+    /// Advances every candidate past some unobserved outputs, enumerating
+    /// them first as the predictor can only track consecutive outputs, or
+    /// leaves them as they were and fails if that would enumerate more than
+    /// `limit` candidates.
+    pub fn skip_outputs(&mut self, count: usize, limit: usize) -> Result<()> {
+        if let Self::Implicit(_) | Self::Branched { .. } = self {
+            if self.count() > limit {
+                return Err(Error::other(format!(
+                    "skipping outputs needs the {} remaining candidates enumerated, more than \
+                     the limit of {}",
+                    self.count(),
+                    limit
+                )));
+            }
+
+            *self = Self::Explicit(self.to_vec());
+        }
+
+        if let Self::Explicit(states) = self {
+            for state in states.iter_mut() {
+                *state = state.jump(count as u64);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Steps past an output whose value is unknown. The predictor branches
+    /// on both of the outputs it predicts (or is given the output it predicts
+    /// unambiguously), so the candidates are never enumerated to do so, and
+    /// are left as they were if the predictor fails to step past it.
+    pub fn skip_unknown_output(&mut self) -> Result<()> {
+        if let Self::Explicit(states) = self {
+            for state in states.iter_mut() {
+                state.advance();
+            }
+
+            return Ok(());
+        }
+
+        self.branch_where(1, |_, _| true)
+    }
+
+    /// Predicts the next PCG output from every explicitly enumerated
+    /// candidate, or from the first and last candidates of each branch of
+    /// those tracked implicitly.
+    pub fn predict_future_output(&self) -> Prediction {
+        match self {
+            Self::Implicit(predictor) => predictor.predict_future_output(),
+            Self::Explicit(states) => {
+                Prediction::from_outputs(states.iter().map(Pcg32State::next_output))
+            }
+            Self::Branched {
+                predictor,
+                branches,
+            } => {
+                let stride = &predictor.table.stride;
+
+                Prediction::from_outputs(branches.iter().flat_map(|branch| {
+                    Predictor::predict_from(stride, &branch.triple).into_outputs()
+                }))
+            }
+        }
+    }
+
+    /// Returns how many candidates predict each of the predicted outputs, in
+    /// the order of `Prediction::outputs`, estimated from a random sample if
+    /// there are more than `samples` of them, drawing from `rng`. Implicitly
+    /// tracked candidates which don't produce the last output submitted (the
+    /// predictor only rules them out a step later) are left out, so the
+    /// estimates are scaled from the consistent samples.
+    pub fn prediction_support(&self, samples: usize, rng: &mut Pcg32State) -> Vec<usize> {
+        let prediction = self.predict_future_output();
+        let outputs = prediction.outputs();
+        let mut support = vec![0; outputs.len()];
+
+        // a predictor made for a stride predicts the next observed output
+        let skipped = self.table().map_or(0, |table| table.stride() - 1);
+
+        let next_observed = |state: Pcg32State| match skipped {
+            0 => state.next_output(),
+            _ => state.jump(skipped).next_output(),
+        };
+
+        let mut tally = |state: Pcg32State, last_output: u32| {
+            if state.output() != last_output {
+                return;
+            }
+
+            let output = next_observed(state);
+
+            if let Some(position) = outputs.iter().position(|&predicted| predicted == output) {
+                support[position] += 1;
+            }
+        };
+
+        let mut draw = |count: usize| {
+            let index = (rng.next_u32() as u64) << 32 | rng.next_u32() as u64;
+            (index % count as u64) as usize
+        };
+
+        // the hits are out of every sample, inconsistent ones included, so
+        // scaling them by the count estimates how many consistent candidates
+        // predict each output
+        let scale = |support: Vec<usize>, count: usize| {
+            support
+                .iter()
+                .map(|&hits| (hits as u64 * count as u64 / samples as u64) as usize)
+                .collect()
+        };
+
+        match self {
+            Self::Implicit(predictor) if predictor.remaining_candidate_count() > samples => {
+                let count = predictor.remaining_candidate_count();
+
+                for _ in 0..samples {
+                    let state = predictor.nth_candidate(draw(count)).unwrap();
+                    tally(state, predictor.last_output());
+                }
+
+                scale(support, count)
+            }
+            Self::Implicit(predictor) => {
+                for state in predictor.candidates_iter() {
+                    tally(state, predictor.last_output());
+                }
+
+                support
+            }
+            Self::Explicit(states) => {
+                for &state in states {
+                    tally(state, state.output());
+                }
+
+                support
+            }
+            Self::Branched {
+                predictor,
+                branches,
+            } if Self::branched_count(branches) > samples => {
+                // branches only overlap where their last outputs differ (see
+                // `merge_branches`), and a candidate produces at most one of
+                // them, so consistent candidates are all as likely to be
+                // drawn and tallied
+                let count = Self::branched_count(branches);
+                let stride = &predictor.table.stride;
+
+                for _ in 0..samples {
+                    let mut index = draw(count);
+
+                    for branch in branches {
+                        if let Some(epsilon_k) = branch.triple.epsilon.nth(index) {
+                            tally(
+                                branch.triple.candidate(epsilon_k, stride),
+                                branch.last_output,
+                            );
+                            break;
+                        }
+
+                        index -= branch.triple.count();
+                    }
+                }
+
+                scale(support, count)
+            }
+            Self::Branched {
+                predictor,
+                branches,
+            } => {
+                for branch in branches {
+                    for state in branch.triple.candidates(&predictor.table.stride) {
+                        tally(state, branch.last_output);
+                    }
+                }
+
+                support
+            }
+        }
+    }
+
+    /// Returns the generator state if it has been uniquely determined.
+    pub fn recovered_state(&self) -> Option<Pcg32State> {
+        match self {
+            Self::Explicit(states) if states.len() == 1 => Some(states[0]),
+            _ => None,
+        }
+    }
+
+    /// Returns the table the candidates are tracked with, unless they have
+    /// been enumerated explicitly.
+    pub fn table(&self) -> Option<&Arc<LookupTable>> {
+        match self {
+            Self::Implicit(predictor) | Self::Branched { predictor, .. } => Some(predictor.table()),
+            Self::Explicit(_) => None,
+        }
+    }
+
+    /// Enumerates the candidates explicitly if they aren't already, handing
+    /// back the table they no longer need so that it can be released.
+    pub fn enumerate(&mut self) -> Option<Arc<LookupTable>> {
+        self.enumerate_with_threads(0)
+    }
+
+    /// Enumerates the candidates like `enumerate`, on up to `threads` threads
+    /// (or one per core if zero).
+    ///
+    /// If the token of the predictor is cancelled first, the candidates are
+    /// left as they are and the table isn't handed back.
+    pub fn enumerate_with_threads(&mut self, threads: usize) -> Option<Arc<LookupTable>> {
+        let table = self.table()?.clone();
+        let cancellation = self.cancellation()?.clone();
+
+        *self = Self::Explicit(self.collect(threads, &cancellation)?);
+        Some(table)
+    }
+
+    /// Returns the set of all remaining candidate states.
+    pub fn to_vec(&self) -> Vec<Pcg32State> {
+        self.collect(0, &CancellationToken::new()).unwrap()
+    }
+
+    /// Returns the token cancelling the searches of the predictor, unless
+    /// the candidates have been enumerated explicitly.
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        match self {
+            Self::Implicit(predictor) | Self::Branched { predictor, .. } => {
+                Some(predictor.cancellation())
+            }
+            Self::Explicit(_) => None,
+        }
+    }
+
+    /// Replaces the token cancelling the searches of the predictor, if the
+    /// candidates haven't been enumerated explicitly.
+    pub fn set_cancellation(&mut self, cancellation: CancellationToken) {
+        if let Self::Implicit(predictor) | Self::Branched { predictor, .. } = self {
+            predictor.set_cancellation(cancellation);
+        }
+    }
+
+    fn collect(&self, threads: usize, cancellation: &CancellationToken) -> Option<Vec<Pcg32State>> {
+        match self {
+            Self::Implicit(predictor) => {
+                predictor
+                    .triple
+                    .collect_candidates(&predictor.table.stride, threads, cancellation)
+            }
+            Self::Explicit(states) => Some(states.clone()),
+            Self::Branched {
+                predictor,
+                branches,
+            } => {
+                // the branches may overlap, as the predictor over-approximates
+                let mut states = vec![];
+
+                for branch in branches {
+                    states.extend(branch.triple.collect_candidates(
+                        &predictor.table.stride,
+                        threads,
+                        cancellation,
+                    )?);
+                }
+
+                states.sort_unstable_by_key(|state| (state.state, state.inc));
+                states.dedup();
+                Some(states)
+            }
+        }
+    }
+}
+
+/// Receives progress notifications from a tracker; every method does
+/// nothing by default.
+pub trait Observer {
+    /// Called once the tracker is set up, with the number of candidates.
+    fn on_initialized(&mut self, _count: usize) {}
+
+    /// Called whenever an output reduces the number of candidates.
+    fn on_pruned(&mut self, _count: usize) {}
+
+    /// Called once the candidates are enumerated explicitly, with the table
+    /// they no longer need, just before the tracker lets go of it; the table
+    /// is freed then unless it is shared with something else.
+    fn on_table_released(&mut self, _table: &LookupTable) {}
+
+    /// Called after initialization and after each output with the predicted
+    /// next output, and the candidates it is predicted by.
+    fn on_prediction(&mut self, _prediction: &Prediction, _candidates: &Candidates) {}
+
+    /// Called once, when the generator state is uniquely determined.
+    fn on_recovered(&mut self, _state: &Pcg32State) {}
+}
+
+impl Observer for () {}
+
+/// Follows a generator through its outputs, enumerating the candidates once
+/// no more than `threshold` of them remain (never if zero), and notifies an
+/// observer of its progress. Like candidates, a tracker is `Send` and `Sync`
+/// as long as its observer is.
+pub struct Tracker<O: Observer> {
+    candidates: Candidates,
+    threshold: usize,
+    /// Number of threads enumerating the candidates, or zero for one per core.
+    threads: usize,
+    /// Where enough enumerated candidates are pruned, if not on the CPU.
+    #[cfg(feature = "gpu")]
+    gpu: Option<Arc<GpuPruner>>,
+    recovered: bool,
+    observer: O,
+}
+
+impl<O: Observer> Tracker<O> {
+    pub fn new(candidates: Candidates, threshold: usize, observer: O) -> Self {
+        Self::with_threads(candidates, threshold, 0, observer)
+    }
+
+    /// Sets up a tracker like `new`, enumerating the candidates on up to
+    /// `threads` threads (or one per core if zero).
+    pub fn with_threads(
+        candidates: Candidates,
+        threshold: usize,
+        threads: usize,
+        mut observer: O,
+    ) -> Self {
+        observer.on_initialized(candidates.count());
+
+        let mut tracker = Self {
+            candidates,
+            threshold,
+            threads,
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            recovered: false,
+            observer,
+        };
+
+        tracker.update();
+        tracker.report_prediction();
+        tracker
+    }
+
+    /// Prunes the candidates on a GPU from now on whenever at least
+    /// `GPU_MIN_CANDIDATES` of them are enumerated and an output is
+    /// submitted, which with a large threshold lets the state be recovered
+    /// from fewer outputs.
+    #[cfg(feature = "gpu")]
+    pub fn set_gpu(&mut self, gpu: Arc<GpuPruner>) {
+        self.gpu = Some(gpu);
+    }
+
+    /// Submits the next output produced by the target PCG generator.
+    pub fn submit_next_output(&mut self, output: u32) -> Result<()> {
+        self.submit_next_outputs(&[output])
+    }
+
+    /// Submits several consecutive outputs at once, notifying the observer
+    /// only after the last one.
+    pub fn submit_next_outputs(&mut self, outputs: &[u32]) -> Result<()> {
+        let count = self.candidates.count();
+
+        #[cfg(feature = "gpu")]
+        if let (Some(gpu), Candidates::Explicit(states)) = (&self.gpu, &mut self.candidates) {
+            if states.len() >= GPU_MIN_CANDIDATES {
+                gpu.submit_next_outputs(states, outputs)?;
+                self.after_output(count);
+
+                return Ok(());
+            }
+        }
+
+        match *outputs {
+            [output] => self.candidates.submit_next_output(output)?,
+            _ => self.candidates.submit_next_outputs(outputs)?,
+        }
+
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Submits an observation derived from the next output and the one
+    /// before it.
+    pub fn submit_next_derived(&mut self, observation: u32, derivation: Derivation) -> Result<()> {
+        let count = self.candidates.count();
+        self.candidates
+            .submit_next_derived(observation, derivation)?;
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Submits a single bit leaked by the target PCG generator.
+    pub fn submit_next_bit(&mut self, bit: bool, leak: BitLeak) -> Result<()> {
+        let count = self.candidates.count();
+        self.candidates.submit_next_bit(bit, leak)?;
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Submits an observation drawn from the next `outputs` outputs (see
+    /// `Candidates::submit_next_where`).
+    pub fn submit_next_where<F>(&mut self, outputs: usize, accept: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        let count = self.candidates.count();
+        self.candidates.submit_next_where(outputs, accept)?;
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Submits the next output reduced to `0..range` by float scaling.
+    pub fn submit_next_scaled(&mut self, value: u32, range: u32) -> Result<()> {
+        let count = self.candidates.count();
+        self.candidates.submit_next_scaled(value, range)?;
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Steps past an output whose value is unknown.
+    pub fn skip_unknown_output(&mut self) -> Result<()> {
+        let count = self.candidates.count();
+        self.candidates.skip_unknown_output()?;
+        self.after_output(count);
+
+        Ok(())
+    }
+
+    /// Advances every candidate past some unobserved outputs, failing if
+    /// that would enumerate more candidates than the threshold.
+    pub fn skip_outputs(&mut self, count: usize) -> Result<()> {
+        self.candidates.skip_outputs(count, self.threshold)
+    }
+
+    /// Returns whether the generator state has been uniquely determined.
+    pub fn is_recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Notifies the observer of the current predictions again.
+    pub fn report_prediction(&mut self) {
+        self.observer
+            .on_prediction(&self.candidates.predict_future_output(), &self.candidates);
+    }
+
+    pub fn candidates(&self) -> &Candidates {
+        &self.candidates
+    }
+
+    pub fn candidates_mut(&mut self) -> &mut Candidates {
+        &mut self.candidates
+    }
+
+    /// Replaces the candidates, e.g. with ones which took other outputs into
+    /// account, notifying the observer as after an output.
+    pub fn set_candidates(&mut self, candidates: Candidates) {
+        let count = self.candidates.count();
+
+        self.candidates = candidates;
+        self.after_output(count);
+    }
+
+    /// Returns the candidates, letting go of the observer.
+    pub fn into_candidates(self) -> Candidates {
+        self.candidates
+    }
+
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    fn after_output(&mut self, count: usize) {
+        if self.candidates.count() < count {
+            self.observer.on_pruned(self.candidates.count());
+        }
+
+        self.update();
+        self.report_prediction();
+    }
+
+    fn update(&mut self) {
+        if self.candidates.table().is_some() && self.candidates.count() <= self.threshold {
+            if let Some(table) = self.candidates.enumerate_with_threads(self.threads) {
+                self.observer.on_table_released(&table);
+            }
+        }
+
+        if let Some(state) = self.candidates.recovered_state() {
+            if !self.recovered {
+                self.recovered = true;
+                self.observer.on_recovered(&state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const MAX: i64 = EPSILON_VALUES;
+
+    fn interval(min: i32, max: i32) -> EpsilonInterval {
+        EpsilonInterval { min, max }
+    }
+
+    /// Returns the table for a stride, generated by the first test needing
+    /// it and shared with the others.
+    fn shared_table(stride: u64) -> Arc<LookupTable> {
+        static TABLES: Mutex<Vec<Arc<LookupTable>>> = Mutex::new(Vec::new());
+
+        let mut tables = TABLES.lock().unwrap();
+
+        match tables.iter().find(|table| table.stride() == stride) {
+            Some(table) => table.clone(),
+            None => {
+                let table = Arc::new(LookupTable::generate(stride));
+                tables.push(table.clone());
+                table
+            }
+        }
+    }
+
+    /// Returns the first outputs of a generator.
+    fn outputs(mut generator: Pcg32State, count: usize) -> Vec<u32> {
+        (0..count).map(|_| generator.next_u32()).collect()
+    }
+
+    #[test]
+    fn clamped_keeps_intervals_within_bounds() {
+        assert_eq!(
+            EpsilonInterval::clamped(0, MAX),
+            Some(EpsilonInterval::FULL)
+        );
+        assert_eq!(EpsilonInterval::clamped(5, 9), Some(interval(5, 9)));
+        assert_eq!(EpsilonInterval::clamped(-3, 9), Some(interval(0, 9)));
+        assert_eq!(
+            EpsilonInterval::clamped(MAX - 4, MAX + 4),
+            Some(interval(MAX as i32 - 4, MAX as i32))
+        );
+        assert_eq!(
+            EpsilonInterval::clamped(i64::MIN, i64::MAX),
+            Some(EpsilonInterval::FULL)
+        );
+    }
+
+    #[test]
+    fn clamped_rejects_empty_intervals() {
+        assert_eq!(EpsilonInterval::clamped(7, 7), None);
+        assert_eq!(EpsilonInterval::clamped(9, 5), None);
+        assert_eq!(EpsilonInterval::clamped(-9, 0), None);
+        assert_eq!(EpsilonInterval::clamped(MAX, MAX + 9), None);
+        assert_eq!(EpsilonInterval::clamped(i64::MIN, i64::MIN), None);
+        assert_eq!(EpsilonInterval::clamped(i64::MAX, i64::MAX), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialized_intervals_are_checked() {
+        use serde::de::value::{Error, MapDeserializer};
+
+        let deserialize = |min: i64, max: i64| {
+            let fields = vec![("min", min), ("max", max)];
+            EpsilonInterval::deserialize(MapDeserializer::<_, Error>::new(fields.into_iter()))
+        };
+
+        assert_eq!(deserialize(5, 9).unwrap(), interval(5, 9));
+        assert_eq!(deserialize(0, MAX).unwrap(), EpsilonInterval::FULL);
+
+        for (min, max) in [(9, 9), (9, 5), (-1, 5), (0, MAX + 1), (i64::MAX, 5)] {
+            assert!(
+                deserialize(min, max).is_err(),
+                "{}..{} was accepted",
+                min,
+                max
+            );
+        }
+    }
+
+    #[test]
+    fn shift_moves_and_clamps_intervals() {
+        let middle = interval(100, 200);
+
+        assert_eq!(middle.shift(W(0)), Some(middle));
+        assert_eq!(middle.shift(W(50)), Some(interval(150, 250)));
+        assert_eq!(middle.shift(W(-50i64 as u64)), Some(interval(50, 150)));
+        assert_eq!(middle.shift(W(-150i64 as u64)), Some(interval(0, 50)));
+        assert_eq!(middle.shift(W(-200i64 as u64)), None);
+
+        assert_eq!(
+            EpsilonInterval::FULL.shift(W(MAX as u64 - 1)),
+            Some(interval(MAX as i32 - 1, MAX as i32))
+        );
+        assert_eq!(EpsilonInterval::FULL.shift(W(MAX as u64)), None);
+        assert_eq!(
+            EpsilonInterval::FULL.shift(W(-(MAX - 1) as u64)),
+            Some(interval(0, 1))
+        );
+        assert_eq!(EpsilonInterval::FULL.shift(W(-MAX as u64)), None);
+    }
+
+    #[test]
+    fn shift_saturates_on_large_betas() {
+        // betas wrapping around to the most negative and positive values
+        for beta in [i64::MIN, i64::MIN + 1, i64::MAX, i64::MAX - 1] {
+            assert_eq!(EpsilonInterval::FULL.shift(W(beta as u64)), None);
+            assert_eq!(interval(0, 1).shift(W(beta as u64)), None);
+        }
+
+        assert_eq!(
+            EpsilonInterval::FULL.shift(W(u64::MAX)),
+            Some(interval(0, MAX as i32 - 1))
+        );
+    }
+
+    #[test]
+    fn len_and_nth_index_every_value() {
+        assert_eq!(EpsilonInterval::FULL.len(), 1 << 27);
+        assert_eq!(EpsilonInterval::FULL.nth(0), Some(0));
+        assert_eq!(
+            EpsilonInterval::FULL.nth((1 << 27) - 1),
+            Some(MAX as i32 - 1)
+        );
+        assert_eq!(EpsilonInterval::FULL.nth(1 << 27), None);
+        assert_eq!(EpsilonInterval::FULL.nth(usize::MAX), None);
+
+        let single = interval(42, 43);
+        assert_eq!(single.len(), 1);
+        assert_eq!((single.first(), single.last()), (42, 42));
+        assert_eq!(single.nth(0), Some(42));
+        assert_eq!(single.nth(1), None);
+
+        let small = interval(10, 14);
+        assert_eq!(small.len(), 4);
+        assert_eq!(
+            (0..6).map(|index| small.nth(index)).collect::<Vec<_>>(),
+            [Some(10), Some(11), Some(12), Some(13), None, None]
+        );
+        assert!(small.iter().eq(10..14));
+    }
+
+    #[test]
+    fn tables_are_identified_by_stride() {
+        // a copy, as identifying a table changes it
+        let mut table = LookupTable {
+            table: Entries::Owned(shared_table(1).table.to_vec().into_boxed_slice()),
+            stride: Stride::new(1),
+            wide_scans: AtomicUsize::new(0),
+        };
+
+        assert_eq!(table.identify_stride(vec![2, 3]), None);
+        assert_eq!(table.stride(), 1);
+
+        assert_eq!(table.identify_stride(vec![3, 1, 2]), Some(1));
+        assert_eq!(table.stride(), 1);
+        assert!(table.verify().is_ok());
+    }
+
+    #[test]
+    fn sampled_support_matches_enumeration() {
+        let table = shared_table(1);
+        let outputs = outputs(Pcg32State::new(0x0123_4567_89ab_cdef, 0x55), 10);
+
+        // the predictor rules out the candidates not producing the fifth and
+        // ninth outputs only a step later, which used to skew the sample
+        for known in 4..outputs.len() {
+            let predictor = Predictor::from_outputs(table.clone(), &outputs[..known]).unwrap();
+            let last_output = predictor.last_output();
+            let candidates = Candidates::Implicit(predictor);
+
+            let prediction = candidates.predict_future_output();
+            let support = candidates.prediction_support(1 << 16, &mut Pcg32State::new(1, 1));
+
+            let mut exact = vec![0; support.len()];
+
+            if let Candidates::Implicit(predictor) = &candidates {
+                for state in predictor.candidates_iter() {
+                    if state.output() == last_output {
+                        let output = state.next_output();
+                        let position = prediction.outputs().iter().position(|&o| o == output);
+                        exact[position.unwrap()] += 1;
+                    }
+                }
+            }
+
+            let share = |support: &[usize], index: usize| {
+                support[index] as f64 / support.iter().sum::<usize>() as f64
+            };
+
+            for index in 0..support.len() {
+                let error = share(&support, index) - share(&exact, index);
+                assert!(
+                    error.abs() < 0.01,
+                    "output #{}: {:?} for {:?}",
+                    known + 1,
+                    support,
+                    exact
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn branched_count_is_distinct() {
+        let table = shared_table(1);
+        let outputs = outputs(Pcg32State::new(0x0123_4567_89ab_cdef, 0x55), 24);
+
+        let predictor = Predictor::from_outputs(table, &outputs[..10]).unwrap();
+        let mut candidates = Candidates::Implicit(predictor);
+        let count = candidates.count();
+
+        // the parities of the 21st and 22nd outputs branch on overlapping
+        // intervals without ruling out any candidate
+        for &output in &outputs[10..] {
+            candidates
+                .submit_next_bit(output & 1 == 1, BitLeak::Parity)
+                .unwrap();
+
+            if let Candidates::Branched { .. } = candidates {
+                assert_eq!(candidates.count(), candidates.to_vec().len());
+            }
+
+            assert!(candidates.count() <= count);
+        }
+    }
+
+    /// Tracks a generator from its first `known` outputs through observations
+    /// of the outputs after them, submitted by `observe` given the output
+    /// before them and those left, which returns how many outputs it drew
+    /// on. Each output must be among those predicted for it, and once the
+    /// candidates with the generator's increment (which outputs alone take
+    /// thousands more to tell apart) come down to one, it must be the state
+    /// of the generator, predicting its outputs from then on.
+    fn recover_through<F>(generator: Pcg32State, known: usize, mut observe: F)
+    where
+        F: FnMut(&mut Tracker<()>, &[u32]) -> usize,
+    {
+        let outputs = outputs(generator, 400);
+
+        // enumerating the candidates early lets every observation prune them
+        let predictor = Predictor::from_outputs(shared_table(1), &outputs[..known]).unwrap();
+        let mut tracker = Tracker::new(Candidates::Implicit(predictor), 1 << 24, ());
+        let mut consumed = known;
+
+        let states = loop {
+            if let Candidates::Explicit(states) = tracker.candidates() {
+                let states: Vec<_> = (states.iter())
+                    .filter(|state| state.inc == generator.inc)
+                    .copied()
+                    .collect();
+
+                if states.len() <= 1 {
+                    break states;
+                }
+            }
+
+            assert!(consumed < 380, "not recovered after {} outputs", consumed);
+
+            let prediction = tracker.candidates().predict_future_output();
+            assert!(
+                prediction.outputs().contains(&outputs[consumed]),
+                "output #{} isn't in {:?}",
+                consumed + 1,
+                prediction
+            );
+
+            consumed += observe(&mut tracker, &outputs[consumed - 1..]);
+        };
+
+        assert_eq!(states, [generator.jump(consumed as u64 - 1)]);
+        tracker.set_candidates(Candidates::Explicit(states));
+
+        for &output in &outputs[consumed..consumed + 8] {
+            assert_eq!(
+                tracker.candidates().predict_future_output(),
+                Prediction::Unique(output)
+            );
+            tracker.submit_next_output(output).unwrap();
+        }
+    }
+
+    #[test]
+    fn leaked_bits_recover_the_generator() {
+        let generator = Pcg32State::new(0x0123_4567_89ab_cdef, 0x55);
+
+        // from enough outputs for the candidates to be enumerated, as bits
+        // alone narrow down the implicitly tracked ones very slowly
+        for leak in [BitLeak::Parity, BitLeak::TopBit, BitLeak::Bool] {
+            recover_through(generator, 32, |tracker, outputs| {
+                let drawn = leak.outputs();
+                tracker
+                    .submit_next_bit(leak.observe(outputs[drawn]), leak)
+                    .unwrap();
+                drawn
+            });
+        }
+    }
+
+    #[test]
+    fn derived_observations_recover_the_generator() {
+        let generator = Pcg32State::new(0xfedc_ba98_7654_3210, 0x2468_ace1);
+
+        for derivation in [Derivation::Xor, Derivation::Difference] {
+            recover_through(generator, 4, |tracker, outputs| {
+                let observation = derivation.derive(outputs[0], outputs[1]);
+                tracker
+                    .submit_next_derived(observation, derivation)
+                    .unwrap();
+                1
+            });
+        }
+    }
+
+    #[test]
+    fn scaled_outputs_recover_the_generator() {
+        let generator = Pcg32State::new(0x0123_4567_89ab_cdef, 0x2468_ace1);
+
+        for range in [6, 1000] {
+            recover_through(generator, 4, |tracker, outputs| {
+                let value = scale_output(outputs[1], range);
+                tracker.submit_next_scaled(value, range).unwrap();
+                1
+            });
+        }
+    }
+
+    #[test]
+    fn missing_outputs_are_branched_over() {
+        let generator = Pcg32State::new(0xfedc_ba98_7654_3210, 0x55);
+
+        // only every third output is seen, the two before it being unknown
+        recover_through(generator, 4, |tracker, outputs| {
+            let seen = outputs[3];
+            tracker
+                .submit_next_where(3, |_, next| next == seen)
+                .unwrap();
+            3
+        });
+    }
+
+    #[test]
+    fn stride_tables_predict_every_nth_output() {
+        let generator = Pcg32State::new(0x0123_4567_89ab_cdef, 0x2468_ace1);
+        let outputs: Vec<u32> = outputs(generator, 40).into_iter().step_by(2).collect();
+
+        let mut predictor = Predictor::from_outputs(shared_table(2), &outputs[..4]).unwrap();
+        assert_eq!(predictor.stride(), 2);
+
+        for (index, &output) in outputs.iter().enumerate().skip(4) {
+            let prediction = predictor.predict_future_output();
+            assert!(
+                prediction.outputs().contains(&output),
+                "output #{} isn't in {:?}",
+                2 * index + 1,
+                prediction
+            );
+
+            predictor.submit_next_output(output).unwrap();
+        }
+
+        // explicit enumeration is needed to single it out, but the state of
+        // the generator as of the last output is among the candidates left
+        let state = generator.jump(2 * (outputs.len() as u64 - 1));
+        assert_eq!(
+            predictor.remaining_candidates_where(|candidate| *candidate == state),
+            [state]
+        );
+    }
+
+    #[test]
+    fn breakers_start_solvers_by_name() {
+        let generator = Pcg32State::new(0x0123_4567_89ab_cdef, 0x55);
+        let outputs = outputs(generator, 8);
+
+        let mut breaker = BreakerBuilder::new(shared_table(1))
+            .solver("known-inc")
+            .increment(generator.inc.0)
+            .build(&outputs[..6])
+            .unwrap();
+
+        assert_eq!(breaker.recovered_state(), Some(generator.jump(5)));
+        assert_eq!(
+            breaker.predict_future_output(),
+            Prediction::Unique(outputs[6])
+        );
+        assert!(breaker.submit(outputs[6]).is_ok());
+
+        // a solver which doesn't track candidates can't initialize a breaker
+        struct Silent;
+
+        impl SolverBackend for Silent {
+            fn submit(&mut self, _: u32) -> Result<()> {
+                Ok(())
+            }
+
+            fn predict(&self) -> Prediction {
+                Prediction::Unique(0)
+            }
+
+            fn candidate_count(&self) -> usize {
+                1
+            }
+
+            fn recovered_state(&self) -> Option<Pcg32State> {
+                None
+            }
+        }
+
+        let mut registry = SolverRegistry::new();
+
+        registry.register(SolverEntry {
+            name: "silent",
+            description: "tracks nothing",
+            outputs: 1,
+            needs_table: false,
+            factory: |_, _| Ok(Box::new(Silent)),
+        });
+
+        for name in ["silent", "table"] {
+            let result = BreakerBuilder::new(shared_table(1))
+                .registry(registry.clone())
+                .solver(name)
+                .build(&outputs);
+
+            assert_eq!(result.err(), Some(Inconsistency::Solver(name)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "gpu")]
+    fn gpu_pruning_matches_the_cpu() {
+        // machines without an OpenCL device can't run this, so it passes
+        // there without checking anything
+        let gpu = match GpuPruner::new() {
+            Ok(gpu) => gpu,
+            Err(err) => {
+                eprintln!("skipping the GPU pruning test: {}", err);
+                return;
+            }
+        };
+
+        let mut generator = Pcg32State::new(0x0123_4567_89ab_cdef, 0x2468_ace1);
+        let target = Pcg32State::new(0xfedc_ba98_7654_3210, 0x1357_9bdf);
+
+        // random candidates, with copies of the target at the edges and in
+        // between
+        let mut states: Vec<Pcg32State> = (0..100_003)
+            .map(|_| Pcg32State::new(generator.next_u32() as u64, generator.next_u32() as u64))
+            .collect();
+
+        for index in [0, 1, 255, 256, 65_537, 100_002] {
+            states[index] = target;
+        }
+
+        let mut next = target;
+        let outputs: Vec<u32> = (0..3).map(|_| next.next_u32()).collect();
+
+        for count in 1..=outputs.len() {
+            let mut on_cpu = Candidates::Explicit(states.clone());
+            on_cpu.submit_next_outputs(&outputs[..count]).unwrap();
+
+            let mut on_gpu = states.clone();
+            gpu.submit_next_outputs(&mut on_gpu, &outputs[..count])
+                .unwrap();
+
+            assert_eq!(on_cpu.to_vec(), on_gpu, "{} outputs", count);
+        }
+
+        // no candidate produces both outputs, so they are left as they were
+        // (compared as a whole, to keep a failure from printing all of them)
+        let mut on_gpu = states.clone();
+        let wrong = [outputs[0], !outputs[1]];
+
+        assert!(gpu.submit_next_outputs(&mut on_gpu, &wrong).is_err());
+        assert!(on_gpu == states, "the candidates were changed");
+    }
+}
@@ -1,468 +1,8366 @@
-use bytemuck::{bytes_of_mut, cast_slice_mut};
-use itertools::iproduct;
+use bytemuck::{bytes_of_mut, cast_slice, cast_slice_mut};
 use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Result, Stdin};
+use pcg_breaker::{
+    double_output_bits, invert_seed_from_u64, recover_invertible_64,
+    recover_invertible_64_from_doubles, recover_state_with_inc, set_force_scalar, worst_case_state,
+    BitLeak, BreakerBuilder, CancellationToken, Candidates, Derivation, Invertible64, JumpTable,
+    LookupTable, ObservationModel, Observer, Pcg32State, Prediction, Predictor, SimdPath,
+    SolverBackend, SolverConfig, SolverEntry, SolverRegistry, TableOptions, Tracker, A_INV,
+};
+#[cfg(feature = "gpu")]
+use pcg_breaker::{GpuPruner, GPU_MIN_CANDIDATES};
+#[cfg(feature = "http")]
+use sources::HttpPoller;
+use sources::Json;
+#[cfg(feature = "nats")]
+use sources::NatsSubscriber;
+#[cfg(feature = "websocket")]
+use sources::{WebSocketClient, WEBSOCKET_STREAM};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::{read, read_to_string, write, File};
+use std::io::{
+    BufRead, BufReader, BufWriter, Error, ErrorKind, IsTerminal, Read, Result, Stdin, Write,
+};
 use std::num::Wrapping as W;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
-use std::process;
-use std::time::Instant;
+use std::process::{self, Command};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use structopt::clap::Shell;
 use structopt::StructOpt;
 
-/// XSH-RR output transformation from PCG.
-fn compute_xsh_rr(state: W<u64>) -> u32 {
-    let xorshifted = ((state >> 18) ^ state) >> 27;
-    let rotation = (state >> 59).0 as u32;
+/// Whether standard output is reserved for predictions by --emit-binary.
+static EMIT_BINARY: AtomicBool = AtomicBool::new(false);
 
-    (xorshifted.0 as u32).rotate_right(rotation)
+/// How many outputs the target produced before the first one read, as given
+/// by --first-index.
+static INDEX_OFFSET: AtomicUsize = AtomicUsize::new(0);
+
+/// Converts the index of an output read into the target's own numbering.
+fn display_index(index: usize) -> usize {
+    index + INDEX_OFFSET.load(Ordering::Relaxed)
+}
+
+/// Prints a line for the user, on standard error if standard output is
+/// reserved for predictions.
+macro_rules! report {
+    ($($arg:tt)*) => {
+        if $crate::EMIT_BINARY.load(std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
 }
 
-/// Inverts XSH-RR given one particular rotation guess.
-fn invert_xsh_rr(rotation: u32, output: u32) -> W<u64> {
-    let mut state = W(rotation as u64) << 59;
+// after `report!`, which the clients use too
+mod sources;
 
-    let recovered = W(output.rotate_left(rotation) as u64);
+/// Whether numbers may be formatted with digit group separators.
+static TOLERANT_NUMBERS: AtomicBool = AtomicBool::new(false);
 
-    state |= (recovered >> 19) << 46;
+/// Removes the digit group separators of locale-formatted numbers (spaces of
+/// any kind, `.`, `,` and apostrophes) with --tolerant-numbers.
+fn strip_separators(text: &str) -> Cow<'_, str> {
+    if !TOLERANT_NUMBERS.load(Ordering::Relaxed) {
+        return Cow::Borrowed(text);
+    }
 
-    state |= (((recovered >> 1) ^ (state >> 46)) & W(0x3ffff)) << 28;
+    text.chars()
+        .filter(|&c| !c.is_whitespace() && !matches!(c, '.' | ',' | '\'' | '\u{2019}'))
+        .collect()
+}
 
-    state |= ((recovered ^ (state >> 45)) & W(1)) << 27;
+/// An output as written, which may be read in more than one way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reading {
+    /// Written in a way which can only be read one way.
+    Exact(u32),
+    /// Hexadecimal digits including a letter, without a `0x` prefix.
+    PlainHex(u32),
+    /// Only decimal digits meaning something else in hexadecimal, and whether
+    /// they are 8 of them or zero-padded, like the fixed-width output of
+    /// `%08x`.
+    Digits {
+        decimal: u32,
+        hex: u32,
+        fixed_width: bool,
+    },
+}
 
-    state
+impl Reading {
+    /// Returns the output read as written on its own, which is decimal for
+    /// decimal digits.
+    fn value(self) -> u32 {
+        match self {
+            Self::Exact(output) | Self::PlainHex(output) => output,
+            Self::Digits { decimal, .. } => decimal,
+        }
+    }
 }
 
-/// The multiplicative constant from PCG-XSH-RR
-const A: W<u64> = W(6_364_136_223_846_793_005);
+/// Parses an output written in decimal, in hexadecimal with or without a
+/// `0x` prefix, or with any other prefix `parse_int` understands, telling
+/// them apart value by value so that mixed captures can be read as they are.
+/// Values made only of decimal digits could be either, which is for the
+/// input to settle (see `Input::settle`).
+fn parse_reading(line: &str) -> Result<Reading> {
+    let text = strip_separators(line);
+
+    let error = match parse::<u32>(&text) {
+        Ok(decimal) => {
+            let digits = text.bytes().all(|byte| byte.is_ascii_digit());
+
+            return Ok(match u32::from_str_radix(&text, 16) {
+                Ok(hex) if digits && hex != decimal => Reading::Digits {
+                    decimal,
+                    hex,
+                    fixed_width: text.len() == 8 || text.starts_with('0'),
+                },
+                _ => Reading::Exact(decimal),
+            });
+        }
+        Err(err) => err,
+    };
+
+    // hex digits including a letter can only be hexadecimal missing its 0x
+    let plain_hex = text.bytes().all(|byte| byte.is_ascii_hexdigit())
+        && text.bytes().any(|byte| byte.is_ascii_alphabetic());
 
-/// The multiplicative inverse of A (modulo 2^64)
-const A_INV: W<u64> = W(13_877_824_140_714_322_085);
+    match u32::from_str_radix(&text, 16) {
+        Ok(output) if plain_hex => Ok(Reading::PlainHex(output)),
+        _ => Err(Error::other(error)),
+    }
+}
 
+/// Parses an output like `parse_reading`, reading decimal digits as decimal.
 fn parse_output(line: &str) -> Result<u32> {
-    parse::<u32>(line).map_err(|err| Error::new(ErrorKind::Other, err))
+    parse_reading(line).map(Reading::value)
 }
 
-fn read_output_lines(stdin: &mut BufReader<Stdin>) -> Result<u32> {
-    let mut line = String::new();
-    stdin.read_line(&mut line)?;
-    parse_output(&line)
+/// Parses the speed at which recorded outputs are replayed.
+fn parse_replay_speed(text: &str) -> Result<f64> {
+    match text.parse::<f64>().map_err(Error::other)? {
+        speed if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(Error::other("the replay speed must be positive")),
+    }
 }
 
-fn read_output_bytes(stdin: &mut BufReader<Stdin>) -> Result<u32> {
-    let mut output = 0u32;
-    stdin.read_exact(bytes_of_mut(&mut output))?;
-    Ok(output)
+/// Parses the index of the first output, which is 1-based.
+fn parse_first_index(text: &str) -> Result<usize> {
+    match parse::<usize>(text).map_err(Error::other)? {
+        0 => Err(Error::other("outputs are numbered from 1")),
+        index => Ok(index),
+    }
 }
 
-struct LookupTable {
-    table: Box<[u64]>,
+/// An output, along with its index if it was explicitly given.
+type Observation = (Option<usize>, Reading);
+
+/// Where an observation was read from within its source, so that reports
+/// can point at it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Position {
+    #[default]
+    Unknown,
+    /// On this (1-based) line of text.
+    Line(usize),
+    /// At this byte offset of binary input.
+    Offset(u64),
+    /// In this (1-based) message received from the network.
+    #[cfg(any(feature = "nats", feature = "websocket"))]
+    Message(usize),
+    /// In the response to this (1-based) request.
+    #[cfg(feature = "http")]
+    Response(u64),
+    /// This (1-based) value given on the command line.
+    Argument(usize),
+    /// Printed by this (1-based) run of the oracle command.
+    Query(usize),
 }
 
-impl LookupTable {
-    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut raw_table_file = File::open(path)?;
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "an unknown position"),
+            Self::Line(line) => write!(f, "line {}", line),
+            Self::Offset(offset) => write!(f, "byte {}", offset),
+            #[cfg(any(feature = "nats", feature = "websocket"))]
+            Self::Message(message) => write!(f, "message {}", message),
+            #[cfg(feature = "http")]
+            Self::Response(request) => write!(f, "the response to request {}", request),
+            Self::Argument(value) => write!(f, "value {}", value),
+            Self::Query(query) => write!(f, "query {}", query),
+        }
+    }
+}
 
-        let mut table = vec![0; 0x800_0000].into_boxed_slice();
-        raw_table_file.read_exact(cast_slice_mut(&mut table))?;
+/// An observation along with where it was read from.
+type Located = (Position, Observation);
 
-        Ok(Self { table })
+/// Points an error reading a malformed observation at where it was read.
+fn locate_error(err: Error, position: Position, source: &str) -> Error {
+    match err.kind() {
+        ErrorKind::InvalidData => Error::new(
+            ErrorKind::InvalidData,
+            format!("{} at {} of {}", err, position, source),
+        ),
+        _ => err,
     }
+}
 
-    /// Queries the lookup table for its value given N.
-    pub fn query(&self, n: W<u64>) -> Option<W<u64>> {
-        if let Some(beta) = self.scan_table(n) {
-            return Some(beta);
-        }
+/// How far into standard input has been read.
+#[derive(Clone, Copy, Default)]
+struct Cursor {
+    lines: usize,
+    bytes: u64,
+    /// Where the observations read last were read from.
+    position: Position,
+}
 
-        if let Some(beta) = self.scan_table(-n) {
-            return Some(-beta);
-        }
+/// Outputs given on the command line (an alias so that structopt doesn't
+/// take the flag to be repeatable).
+type InlineOutputs = Vec<Observation>;
 
-        if n == W(1) {
-            return None;
-        }
+/// Parses the outputs given with --inline.
+fn parse_inline(text: &str) -> Result<InlineOutputs> {
+    let mut queue = VecDeque::new();
+    parse_observations(text, &mut queue)?;
+    Ok(queue.into())
+}
 
-        if let Some(beta) = self.scan_table(W(1) + n) {
-            return Some(beta - W(0x800_0000));
-        }
+/// Parses an output, optionally prefixed by its index as `index:value`.
+fn parse_observation(line: &str) -> Result<Observation> {
+    match line.split_once(':') {
+        Some((index, value)) => Ok((
+            Some(parse::<usize>(&strip_separators(index)).map_err(Error::other)?),
+            parse_reading(value)?,
+        )),
+        None => Ok((None, parse_reading(line)?)),
+    }
+}
+
+/// Set once the user interrupts the program with Ctrl-C.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_: libc::c_int) {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        // interrupted twice, so the user doesn't want to wait any longer
+        unsafe { libc::_exit(EXIT_INTERRUPTED) };
+    }
+}
+
+/// Handles Ctrl-C by setting `INTERRUPTED`, without restarting reads so that
+/// waiting for input can be given up on.
+fn install_interrupt_handler() {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut());
+    }
+}
 
-        if n == -W(1) {
-            return None;
+/// A reader which fails instead of retrying reads cut short by Ctrl-C.
+struct Interruptible<R>(R);
+
+impl<R: Read> Read for Interruptible<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            match self.0.read(buf) {
+                Err(err) if err.kind() == ErrorKind::Interrupted => {
+                    if INTERRUPTED.load(Ordering::SeqCst) {
+                        return Err(Error::other("interrupted"));
+                    }
+                }
+                result => return result,
+            }
         }
+    }
+}
+
+type StdinReader = BufReader<Interruptible<Stdin>>;
 
-        if let Some(beta) = self.scan_table(W(1) - n) {
-            return Some(W(0x800_0000) - beta);
+/// Parses the outputs on a line, which may be separated by whitespace or
+/// commas unless those are taken as digit group separators.
+/// Malformed outputs fail with `ErrorKind::InvalidData`, which `Source`
+/// points at where they were read from.
+fn parse_observations(line: &str, queue: &mut VecDeque<Observation>) -> Result<()> {
+    let parse =
+        |word| parse_observation(word).map_err(|err| Error::new(ErrorKind::InvalidData, err));
+
+    if TOLERANT_NUMBERS.load(Ordering::Relaxed) {
+        if !line.trim().is_empty() {
+            queue.push_back(parse(line)?);
         }
 
-        None
+        return Ok(());
     }
 
-    fn scan_table(&self, n: W<u64>) -> Option<W<u64>> {
-        let n2 = n.0 & 0x1f_ffff_ffff;
+    for word in line.split(|c: char| c.is_whitespace() || c == ',') {
+        if !word.is_empty() {
+            queue.push_back(parse(word)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_output_lines(
+    stdin: &mut StdinReader,
+    queue: &mut VecDeque<Observation>,
+    cursor: &mut Cursor,
+) -> Result<()> {
+    let mut line = String::new();
 
-        let estimate = (n2 >> 10) as isize;
+    // skip over blank lines
+    while queue.is_empty() {
+        line.clear();
 
-        let lo = (estimate - 2).max(0) as usize;
-        let hi = (estimate + 10).min(0x7ff_ffff) as usize;
+        let read = stdin.read_line(&mut line)?;
 
-        for entry in &self.table[lo..=hi] {
-            if entry >> 27 == n2 {
-                return Some(W(entry & 0x7ff_ffff));
-            }
+        if read == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
         }
 
-        None
+        cursor.lines += 1;
+        cursor.bytes += read as u64;
+        cursor.position = Position::Line(cursor.lines);
+
+        parse_observations(&line, queue)?;
     }
-}
 
-struct Predictor {
-    table: LookupTable,
-    last_output: u32,
-    triple: Triple,
+    Ok(())
 }
 
-impl Predictor {
-    /// Initializes the output predictor with four initial outputs.
-    fn new(table: LookupTable, outputs: [u32; 4]) -> Result<Self> {
-        for (s0_rot, s1_rot, s2_rot) in iproduct!(0..32, 0..32, 0..32) {
-            let s0_star = invert_xsh_rr(s0_rot, outputs[0]) >> 27;
-            let s1_star = invert_xsh_rr(s1_rot, outputs[1]) >> 27;
-            let s2_star = invert_xsh_rr(s2_rot, outputs[2]) >> 27;
+fn read_output_bytes(
+    stdin: &mut StdinReader,
+    queue: &mut VecDeque<Observation>,
+    cursor: &mut Cursor,
+) -> Result<()> {
+    let mut output = 0u32;
+    stdin.read_exact(bytes_of_mut(&mut output))?;
+    queue.push_back((None, Reading::Exact(output)));
 
-            let n = (A * (s1_star - s0_star) + (s1_star - s2_star)) & W(0x1f_ffff_ffff);
+    cursor.position = Position::Offset(cursor.bytes);
+    cursor.bytes += 4;
+    Ok(())
+}
 
-            if let Some(beta) = table.query(n) {
-                let epsilon_min: i32 = (beta.0 as i32).max(0);
-                let epsilon_max: i32 = (beta.0 as i32 + 134_217_728).min(134_217_728);
+/// A step of the transform chain applied to each output as it is read.
+#[derive(Clone, Copy, Debug)]
+enum Transform {
+    Add(u32),
+    Sub(u32),
+    Mul(u32),
+    Div(u32),
+    Xor(u32),
+    Mask(u32),
+    Shl(u32),
+    Shr(u32),
+    RotL(u32),
+    RotR(u32),
+    Mod(u32),
+}
 
-                if Self::test_state(s1_star, s2_star, epsilon_min, epsilon_max, beta, outputs[3]) {
-                    return Ok(Self {
-                        table,
-                        last_output: outputs[3],
-                        triple: Triple {
-                            sj_star: s1_star,
-                            sk_star: s2_star,
+impl Transform {
+    pub fn apply(self, value: u32) -> u32 {
+        match self {
+            Self::Add(x) => value.wrapping_add(x),
+            Self::Sub(x) => value.wrapping_sub(x),
+            Self::Mul(x) => value.wrapping_mul(x),
+            Self::Div(x) => value / x,
+            Self::Xor(x) => value ^ x,
+            Self::Mask(x) => value & x,
+            Self::Shl(x) => value.wrapping_shl(x),
+            Self::Shr(x) => value.wrapping_shr(x),
+            Self::RotL(x) => value.rotate_left(x),
+            Self::RotR(x) => value.rotate_right(x),
+            Self::Mod(x) => value % x,
+        }
+    }
 
-                            beta,
+    /// Returns whether the step maps different values to the same one, so
+    /// that it loses bits of the output.
+    fn is_lossy(self) -> bool {
+        match self {
+            Self::Mul(x) => x % 2 == 0,
+            Self::Div(x) => x > 1,
+            Self::Mask(x) => x != u32::MAX,
+            Self::Shl(x) | Self::Shr(x) => x % 32 != 0,
+            Self::Mod(_) => true,
+            _ => false,
+        }
+    }
+}
 
-                            epsilon_min,
-                            epsilon_max,
-                        },
-                    });
-                }
-            }
+/// Takes the step reducing outputs off the end of a transform chain, which
+/// must be `mask` of the lowest bits or `mod` as it only says how outputs
+/// are observed, rejecting any other step which loses bits of the output.
+fn take_reduction(transforms: &mut Vec<Transform>) -> Result<Option<ObservationModel>> {
+    let reduction = match transforms.last() {
+        Some(&Transform::Mask(mask)) if mask != u32::MAX && (mask + 1).is_power_of_two() => {
+            ObservationModel::LowBits(mask.count_ones())
         }
+        Some(&Transform::Mod(modulus)) => ObservationModel::Modulo(modulus),
+        _ => ObservationModel::Outputs,
+    };
 
-        Err(Error::new(
-            ErrorKind::Other,
-            "output sequence not produced by PCG-XSH-RR",
-        ))
+    if let ObservationModel::LowBits(_) | ObservationModel::Modulo(_) = reduction {
+        transforms.pop();
     }
 
-    /// Submits the next output produced by the target PCG generator.
-    pub fn submit_next_output(&mut self, output: u32) -> Result<()> {
-        for sk_rot in 0..32 {
-            let si_star = self.triple.sj_star;
-            let sj_star = self.triple.sk_star;
-            let sk_star = invert_xsh_rr(sk_rot, self.last_output) >> 27;
+    match transforms.iter().find(|step| step.is_lossy()) {
+        Some(step) => Err(Error::other(format!(
+            "transform `{}` loses bits of the outputs; only a final `mask` of their lowest \
+             bits or `mod` can, to observe them reduced",
+            format!("{:?}", step).to_lowercase()
+        ))),
+        None => Ok(match reduction {
+            ObservationModel::Outputs => None,
+            reduction => Some(reduction),
+        }),
+    }
+}
 
-            let n = (A * (sj_star - si_star) + (sj_star - sk_star)) & W(0x1f_ffff_ffff);
+/// Parses a transform written as `name(argument)`, e.g. `mask(0xffff)`.
+fn parse_transform(text: &str) -> Result<Transform> {
+    let invalid = || Error::other(format!("invalid transform `{}`", text));
 
-            if let Some(beta) = self.table.query(n) {
-                let epsilon_min = (self.triple.epsilon_min + beta.0 as i32).max(0);
-                let epsilon_max = (self.triple.epsilon_max + beta.0 as i32).min(134_217_728);
+    let (name, argument) = text.trim().split_once('(').ok_or_else(invalid)?;
+    let argument = argument.strip_suffix(')').ok_or_else(invalid)?;
+    let argument = parse::<u32>(argument).map_err(|_| invalid())?;
 
-                if Self::test_state(sj_star, sk_star, epsilon_min, epsilon_max, beta, output) {
-                    self.triple = Triple {
-                        sj_star,
-                        sk_star,
+    Ok(match name {
+        "add" => Transform::Add(argument),
+        "sub" => Transform::Sub(argument),
+        "mul" => Transform::Mul(argument),
+        "div" if argument != 0 => Transform::Div(argument),
+        "xor" => Transform::Xor(argument),
+        "mask" => Transform::Mask(argument),
+        "shl" => Transform::Shl(argument),
+        "shr" => Transform::Shr(argument),
+        "rotl" => Transform::RotL(argument),
+        "rotr" => Transform::RotR(argument),
+        "mod" if argument != 0 => Transform::Mod(argument),
+        _ => return Err(invalid()),
+    })
+}
 
-                        beta,
+fn parse_derivation(text: &str) -> Result<Derivation> {
+    match text {
+        "xor" => Ok(Derivation::Xor),
+        "sub" => Ok(Derivation::Difference),
+        _ => Err(Error::other("derivation must be `xor` or `sub`")),
+    }
+}
 
-                        epsilon_min,
-                        epsilon_max,
-                    };
+fn parse_bit_leak(text: &str) -> Result<BitLeak> {
+    match text {
+        "parity" => Ok(BitLeak::Parity),
+        "top" => Ok(BitLeak::TopBit),
+        "bool" => Ok(BitLeak::Bool),
+        _ => Err(Error::other("leaked bit must be `parity`, `top` or `bool`")),
+    }
+}
 
-                    self.last_output = output;
-                    return Ok(());
-                }
-            }
-        }
+fn parse_invertible_64(text: &str) -> Result<Invertible64> {
+    match text {
+        "xsl-rr-rr" => Ok(Invertible64::XslRrRr),
+        "rxs-m-xs" => Ok(Invertible64::RxsMXs),
+        _ => Err(Error::other(
+            "output function must be `xsl-rr-rr` or `rxs-m-xs`",
+        )),
+    }
+}
 
-        Err(Error::new(
-            ErrorKind::Other,
-            "output sequence not produced by PCG-XSH-RR",
-        ))
+/// Parses how outputs are observed for `estimate`.
+fn parse_model(text: &str) -> Result<ObservationModel> {
+    match text.split_once(':') {
+        Some(("low", bits)) => match parse::<u32>(bits) {
+            Ok(bits @ 1..=32) => Ok(ObservationModel::LowBits(bits)),
+            _ => Err(Error::other("number of low bits must be 1 to 32")),
+        },
+        Some(("mod", modulus)) => Ok(ObservationModel::Modulo(parse_bound(modulus)?)),
+        Some(("float", range)) => Ok(ObservationModel::Scaled(parse_bound(range)?)),
+        _ => match text {
+            "u32" => Ok(ObservationModel::Outputs),
+            "xor" | "sub" => Ok(ObservationModel::Derived(parse_derivation(text)?)),
+            _ => parse_bit_leak(text)
+                .map(ObservationModel::Bits)
+                .map_err(|_| {
+                    Error::other(
+                        "observation model must be `u32`, `low:K`, `mod:N`, `float:N`, `parity`, \
+                     `top`, `bool`, `xor` or `sub`",
+                    )
+                }),
+        },
     }
+}
+
+/// Parses the mean and standard deviation of a normal distribution, written
+/// as `mean,std_dev`.
+fn parse_normal(text: &str) -> Result<(f64, f64)> {
+    let invalid = || Error::other("normal distribution must be `mean,std_dev`");
 
-    /// Returns either one or two future PCG outputs.
-    pub fn predict_future_output(&self) -> [u32; 2] {
-        let epsilon2 = self.triple.epsilon_min;
-        let epsilon1 = W(epsilon2 as u64) - self.triple.beta;
+    let (mean, std_dev) = text.split_once(',').ok_or_else(invalid)?;
+    let mean: f64 = mean.trim().parse().map_err(|_| invalid())?;
+    let std_dev: f64 = std_dev.trim().parse().map_err(|_| invalid())?;
+
+    if !mean.is_finite() || !std_dev.is_finite() {
+        return Err(invalid());
+    }
 
-        let sj = (self.triple.sj_star << 27) + epsilon1;
-        let sk = (self.triple.sk_star << 27) + W(epsilon2 as u64);
+    if std_dev <= 0.0 {
+        return Err(Error::other("standard deviation must be positive"));
+    }
 
-        let increment = (sk - A * sj) | W(1);
+    Ok((mean, std_dev))
+}
 
-        let state = A * sk + increment;
+/// Parses a sample written in decimal, returning it along with how far the
+/// value it was rounded from can be, going by how many digits it was given
+/// with.
+fn parse_sample(text: &str) -> Result<(f64, f64)> {
+    let value: f64 = text
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+        .ok_or_else(|| Error::other(format!("invalid sample `{}`", text)))?;
 
-        let output1 = compute_xsh_rr(A * state + increment);
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().unwrap_or(0)),
+        None => (text, 0),
+    };
 
-        let epsilon2 = self.triple.epsilon_max - 1;
-        let epsilon1 = W(epsilon2 as u64) - self.triple.beta;
+    let decimals = mantissa
+        .split_once('.')
+        .map_or(0, |(_, digits)| digits.len());
 
-        let sj = (self.triple.sj_star << 27) + epsilon1;
-        let sk = (self.triple.sk_star << 27) + W(epsilon2 as u64);
+    // leave some room for the target computing the sample slightly apart
+    let tolerance = 0.5 * 10f64.powi(exponent - decimals as i32) * (1.0 + 1e-9)
+        + value.abs() * f64::EPSILON * 16.0;
 
-        let increment = (sk - A * sj) | W(1);
+    Ok((value, tolerance))
+}
 
-        let state = A * sk + increment;
+fn parse_stride(text: &str) -> Result<usize> {
+    match parse::<usize>(text).map_err(Error::other)? {
+        0 => Err(Error::other("stride must be nonzero")),
+        stride => Ok(stride),
+    }
+}
 
-        let output2 = compute_xsh_rr(A * state + increment);
+fn parse_cache_size(text: &str) -> Result<usize> {
+    match parse::<usize>(text).map_err(Error::other)? {
+        0 => Err(Error::other("cache must hold at least one double")),
+        size => Ok(size),
+    }
+}
 
-        [output1, output2]
+fn parse_sample_outputs(text: &str) -> Result<usize> {
+    match parse::<usize>(text).map_err(Error::other)? {
+        0 => Err(Error::other("samples must consume at least one output")),
+        outputs => Ok(outputs),
     }
+}
+
+/// Parses a size in bytes, optionally followed by `K`, `M`, `G` or `T` for
+/// binary multiples, e.g. `512M`.
+fn parse_memory_size(text: &str) -> Result<u64> {
+    let invalid = || Error::other(format!("invalid memory size `{}`, e.g. 512M or 2G", text));
+
+    let text = text.trim();
+    let (digits, shift) = match text.char_indices().last().ok_or_else(invalid)? {
+        (index, 'k' | 'K') => (&text[..index], 10),
+        (index, 'm' | 'M') => (&text[..index], 20),
+        (index, 'g' | 'G') => (&text[..index], 30),
+        (index, 't' | 'T') => (&text[..index], 40),
+        _ => (text, 0),
+    };
 
-    /// Returns the number of candidate states left.
-    pub fn remaining_candidate_count(&self) -> usize {
-        (self.triple.epsilon_max - self.triple.epsilon_min) as usize
+    let size = digits.parse::<u64>().map_err(|_| invalid())?;
+    size.checked_shl(shift)
+        .filter(|&bytes| bytes >> shift == size)
+        .ok_or_else(invalid)
+}
+
+fn parse_bound(text: &str) -> Result<u32> {
+    match parse::<u32>(text).map_err(Error::other)? {
+        0 => Err(Error::other("bound must be nonzero")),
+        bound => Ok(bound),
     }
+}
 
-    /// Returns the set of all remaining candidate states.
-    pub fn remaining_candidates(&self) -> Vec<FullState> {
-        let mut states = Vec::with_capacity(self.remaining_candidate_count());
+/// Reads at least one output (several when a line holds several) into a
+/// queue, moving the cursor past them.
+type ReadOutput = fn(&mut StdinReader, &mut VecDeque<Observation>, &mut Cursor) -> Result<()>;
 
-        for epsilon_k in self.triple.epsilon_min..self.triple.epsilon_max {
-            let sj = (self.triple.sj_star << 27) + W(epsilon_k as u64) - self.triple.beta;
-            let sk = (self.triple.sk_star << 27) + W(epsilon_k as u64);
+/// How outputs are given on standard input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InputFormat {
+    Text,
+    Binary,
+    /// Either of the above, told apart from the first bytes of input.
+    Auto,
+}
 
-            let inc = (sk - A * sj) | W(1);
+impl FromStr for InputFormat {
+    type Err = Error;
 
-            states.push(FullState {
-                state: A * sk + inc,
-                inc,
-            });
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "text" => Ok(Self::Text),
+            "binary" => Ok(Self::Binary),
+            "auto" => Ok(Self::Auto),
+            _ => Err(Error::other(
+                "input format must be `text`, `binary` or `auto`",
+            )),
         }
+    }
+}
 
-        states
+/// Guesses the format of the input from its first bytes, which are text if
+/// they only contain characters that can appear in outputs given as text.
+fn sniff_format(stdin: &mut StdinReader) -> InputFormat {
+    let is_text = |byte: &u8| byte.is_ascii_hexdigit() || b"xX:,\r\n\t ".contains(byte);
+
+    match stdin.fill_buf() {
+        Ok(bytes) if !bytes.iter().all(is_text) => InputFormat::Binary,
+        _ => InputFormat::Text,
     }
+}
 
-    fn test_state(
-        sj_star: W<u64>,
-        sk_star: W<u64>,
-        min: i32,
-        max: i32,
-        beta: W<u64>,
-        output: u32,
-    ) -> bool {
-        let epsilon1 = W(min as u64) - beta;
-        let sj = (sj_star << 27) + epsilon1;
-        let sk = (sk_star << 27) + W(min as u64);
+/// Where raw outputs are read from.
+enum Source {
+    /// Read along with any other outputs on the same line, which are queued.
+    Stdin(StdinReader, ReadOutput, VecDeque<Observation>, Cursor),
+    /// Read ahead by a background thread, so that waiting for outputs can
+    /// time out and the producer isn't kept waiting.
+    Thread(Arc<RingBuffer>, Option<Duration>, usize, Position),
+    /// Queried one at a time by running a shell command, counting the runs.
+    Oracle(String, usize),
+    /// Given all at once on the command line with --inline, counting those
+    /// taken so far.
+    Inline(VecDeque<Observation>, usize),
+    /// Appended to a log file, with --follow.
+    Follow(Follower),
+    /// Recorded along with when they were observed, with --replay-timing.
+    Replay(Replayer),
+    /// Published to a NATS subject, with --nats.
+    #[cfg(feature = "nats")]
+    Nats(NatsSubscriber),
+    /// Sent in WebSocket messages, with --ws.
+    #[cfg(feature = "websocket")]
+    WebSocket(WebSocketClient),
+    /// Returned by an HTTP endpoint polled with --http-poll.
+    #[cfg(feature = "http")]
+    Http(HttpPoller),
+}
 
-        let increment = (sk - A * sj) | W(1);
-        let min_state = A * sk + increment;
+impl Source {
+    /// Opens the source, returning it along with the format of the outputs
+    /// it provides (never `Auto`).
+    pub fn new(format: InputFormat, args: &Opt) -> (Self, InputFormat) {
+        let (idle_timeout, buffer) = (args.idle_timeout, args.buffer);
 
-        if output == compute_xsh_rr(min_state) {
-            return true;
+        if let Some(command) = &args.oracle_command {
+            return (Self::Oracle(command.to_owned(), 0), InputFormat::Text);
         }
 
-        let epsilon1 = W((max - 1) as u64) - beta;
-        let sj = (sj_star << 27) + epsilon1;
-        let sk = (sk_star << 27) + W((max - 1) as u64);
+        #[cfg(feature = "nats")]
+        if let Some(address) = &args.nats {
+            return (Self::Nats(NatsSubscriber::new(address)), InputFormat::Text);
+        }
 
-        let increment = (sk - A * sj) | W(1);
-        let max_state = A * sk + increment;
+        #[cfg(feature = "websocket")]
+        if let Some(url) = &args.ws {
+            let rng = Pcg32State::from_rand_pcg(args.seed.unwrap_or_default(), WEBSOCKET_STREAM);
+            let client = WebSocketClient::new(url, args.ws_field.as_deref(), rng);
+            return (Self::WebSocket(client), InputFormat::Text);
+        }
 
-        if output == compute_xsh_rr(max_state) {
-            return true;
+        #[cfg(feature = "http")]
+        if let Some(url) = &args.http_poll {
+            let interval = Duration::from_millis(args.interval);
+            let poller = HttpPoller::new(url, args.extract.as_deref(), interval);
+            return (Self::Http(poller), InputFormat::Text);
         }
 
-        false
-    }
-}
+        if let Some(path) = &args.follow {
+            let timeout = idle_timeout.map(Duration::from_secs);
+            return (
+                Self::Follow(Follower::new(path, timeout)),
+                InputFormat::Text,
+            );
+        }
 
-#[derive(Debug)]
-struct FullState {
-    state: W<u64>,
-    inc: W<u64>,
-}
+        if let Some(path) = &args.replay_timing {
+            let replayer = Replayer::new(path, args.replay_speed);
+            return (Self::Replay(replayer), InputFormat::Text);
+        }
 
-struct Triple {
-    sj_star: W<u64>,
-    sk_star: W<u64>,
+        if let Some(observations) = &args.inline {
+            return (
+                Self::Inline(observations.iter().copied().collect(), 0),
+                InputFormat::Text,
+            );
+        }
 
-    beta: W<u64>,
+        let mut stdin = BufReader::new(Interruptible(std::io::stdin()));
 
-    epsilon_min: i32,
-    epsilon_max: i32,
-}
+        let format = match format {
+            InputFormat::Auto => sniff_format(&mut stdin),
+            format => format,
+        };
 
-fn display_predictions(count: usize, outputs: [u32; 2]) {
-    if outputs[0] == outputs[1] {
-        println!("\n[+] Output #{} will be 0x{:08X}\n", count, outputs[0]);
-    } else {
-        println!(
-            "\n[+] Output #{} will be 0x{:08X} OR 0x{:08X}\n",
-            count, outputs[0], outputs[1]
+        let read_output: ReadOutput = match format {
+            InputFormat::Binary => read_output_bytes,
+            _ => read_output_lines,
+        };
+
+        if idle_timeout.is_none() && buffer.is_none() {
+            let cursor = Cursor::default();
+            return (
+                Self::Stdin(stdin, read_output, VecDeque::new(), cursor),
+                format,
+            );
+        }
+
+        // without --buffer, the producer waits when the program falls behind
+        let ring = Arc::new(RingBuffer::new(buffer.unwrap_or(1024), buffer.is_some()));
+        let writer = Arc::clone(&ring);
+
+        thread::spawn(move || {
+            let (mut queue, mut cursor) = (VecDeque::new(), Cursor::default());
+
+            loop {
+                let result = read_output(&mut stdin, &mut queue, &mut cursor)
+                    .map_err(|err| locate_error(err, cursor.position, "standard input"));
+                let failed = result.is_err();
+
+                let observations = queue
+                    .drain(..)
+                    .map(|observation| Ok((cursor.position, observation)));
+
+                if !writer.push(observations.chain(result.err().map(Err))) || failed {
+                    break;
+                }
+            }
+        });
+
+        let source = Self::Thread(
+            ring,
+            idle_timeout.map(Duration::from_secs),
+            0,
+            Position::Unknown,
         );
+
+        (source, format)
     }
-}
 
-fn run(args: Opt) -> Result<()> {
-    println!("{}", ASCII_HEADER);
+    /// Reads the next non-blank line as is, for observations which aren't
+    /// outputs, which can only be read from standard input one per line.
+    pub fn read_line(&mut self) -> Result<String> {
+        match self {
+            Self::Stdin(stdin, _, queue, cursor) if queue.is_empty() => {
+                let mut line = String::new();
 
-    println!("[-] Starting clock.");
-    let start_time = Instant::now();
+                while line.trim().is_empty() {
+                    line.clear();
 
-    let table = LookupTable::open(&args.table).map_err(|err| {
-        println!("[!] Failed to load precomputed table!");
-        err
-    })?;
+                    let read = stdin.read_line(&mut line)?;
 
-    println!("[+] Loaded precomputed table.");
+                    if read == 0 {
+                        return Err(ErrorKind::UnexpectedEof.into());
+                    }
 
-    println!("[-] Reading 4 outputs to initialize the predictor.");
+                    cursor.lines += 1;
+                    cursor.bytes += read as u64;
+                }
 
-    let mut stdin = BufReader::new(std::io::stdin());
+                Ok(line.trim().to_owned())
+            }
+            _ => Err(Error::other(
+                "samples can only be read from standard input, one per line",
+            )),
+        }
+    }
 
-    let read_output = if args.binary {
-        read_output_bytes
-    } else {
-        read_output_lines
-    };
+    /// Reads the next observation, pointing errors reading a malformed one
+    /// at where it was read (see `position`).
+    pub fn read_output(&mut self) -> Result<Observation> {
+        let result = self.read_next();
 
-    let mut predictor = Predictor::new(
-        table,
-        [
-            read_output(&mut stdin)?,
-            read_output(&mut stdin)?,
-            read_output(&mut stdin)?,
-            read_output(&mut stdin)?,
-        ],
-    )?;
+        match self {
+            // the background thread already points them out
+            Self::Thread(..) => result,
+            _ => result.map_err(|err| locate_error(err, self.position(), &self.name())),
+        }
+    }
 
-    println!(
-        "[+] Predictor initialized after {:.2} seconds.",
-        start_time.elapsed().as_secs_f64(),
-    );
+    fn read_next(&mut self) -> Result<Observation> {
+        match self {
+            Self::Stdin(stdin, read_output, queue, cursor) => {
+                if queue.is_empty() {
+                    read_output(stdin, queue, cursor)?;
+                }
+
+                Ok(queue.pop_front().unwrap())
+            }
+            Self::Inline(observations, taken) => {
+                *taken += 1;
+
+                observations
+                    .pop_front()
+                    .ok_or_else(|| ErrorKind::UnexpectedEof.into())
+            }
+            Self::Follow(follower) => follower.read_output(),
+            Self::Replay(replayer) => replayer.read_output(),
+            #[cfg(feature = "nats")]
+            Self::Nats(subscriber) => subscriber.read_output(),
+            #[cfg(feature = "websocket")]
+            Self::WebSocket(client) => client.read_output(),
+            #[cfg(feature = "http")]
+            Self::Http(poller) => poller.read_output(),
+            Self::Thread(ring, timeout, dropped, position) => {
+                let (skipped, (read_at, observation)) = ring.pop(*timeout)?;
+                *dropped += skipped;
+                *position = read_at;
+                Ok(observation)
+            }
+            Self::Oracle(command, queries) => {
+                *queries += 1;
 
-    if !args.recovery {
-        display_predictions(5, predictor.predict_future_output());
+                query_oracle(command).inspect_err(|err| {
+                    report!("[!] Oracle query failed: {}", err);
+                })
+            }
+        }
     }
 
-    let mut remaining_candidates = vec![];
-    let mut outputs = 4;
+    /// Returns where the last observation was read from within the source.
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Stdin(_, _, _, cursor) => cursor.position,
+            Self::Thread(_, _, _, position) => *position,
+            Self::Oracle(_, queries) => Position::Query(*queries),
+            Self::Inline(_, taken) => Position::Argument(*taken),
+            Self::Follow(follower) => follower.position,
+            Self::Replay(replayer) => replayer.position,
+            #[cfg(feature = "nats")]
+            Self::Nats(subscriber) => subscriber.position,
+            #[cfg(feature = "websocket")]
+            Self::WebSocket(client) => client.position,
+            #[cfg(feature = "http")]
+            Self::Http(poller) => poller.position,
+        }
+    }
 
-    while let Ok(output) = read_output(&mut stdin) {
-        outputs += 1;
+    /// Names the source, as positions are given within it.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Stdin(..) | Self::Thread(..) => "standard input".to_owned(),
+            Self::Oracle(command, _) => format!("`{}`", command),
+            Self::Inline(..) => "--inline".to_owned(),
+            Self::Follow(follower) => follower.path.display().to_string(),
+            Self::Replay(replayer) => replayer.path.display().to_string(),
+            #[cfg(feature = "nats")]
+            Self::Nats(subscriber) => format!("NATS {}", subscriber.address),
+            #[cfg(feature = "websocket")]
+            Self::WebSocket(client) => client.url.clone(),
+            #[cfg(feature = "http")]
+            Self::Http(poller) => poller.url.clone(),
+        }
+    }
 
-        if !args.recovery {
-            println!(
-                "[-] Reading output #{} (with value 0x{:08X})",
-                outputs,
-                output
-            );
+    /// Returns how many outputs were dropped by --buffer since last asked,
+    /// having been read too far ahead.
+    pub fn take_dropped(&mut self) -> usize {
+        match self {
+            Self::Thread(_, _, dropped, _) => std::mem::take(dropped),
+            _ => 0,
+        }
+    }
+}
+
+/// Outputs read ahead from standard input by a background thread. When full,
+/// the reader either waits for room or, with --buffer, drops the oldest
+/// outputs so that the producer is never kept waiting.
+struct RingBuffer {
+    state: Mutex<RingState>,
+    changed: Condvar,
+}
+
+struct RingState {
+    /// Each output along with how many were dropped just before it.
+    queue: VecDeque<(usize, Result<Located>)>,
+    capacity: usize,
+    overwrite: bool,
+    /// Outputs dropped since the last one queued, when none is left.
+    dropped: usize,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize, overwrite: bool) -> Self {
+        Self {
+            state: Mutex::new(RingState {
+                queue: VecDeque::with_capacity(capacity.min(1 << 20)),
+                capacity: capacity.max(1),
+                overwrite,
+                dropped: 0,
+            }),
+            changed: Condvar::new(),
         }
+    }
 
-        if remaining_candidates.is_empty() {
-            const THRESHOLD: usize = 1000;
+    /// Adds outputs read, returning false once nobody is left to take them.
+    pub fn push<I>(self: &Arc<Self>, results: I) -> bool
+    where
+        I: Iterator<Item = Result<Located>>,
+    {
+        let mut state = self.state.lock().unwrap();
 
-            let count = predictor.remaining_candidate_count();
+        for result in results {
+            while state.queue.len() >= state.capacity {
+                if Arc::strong_count(self) == 1 {
+                    return false;
+                }
+
+                if !state.overwrite {
+                    let wait = Duration::from_millis(100);
+                    state = self.changed.wait_timeout(state, wait).unwrap().0;
+                    continue;
+                }
 
-            predictor.submit_next_output(output)?;
+                // outputs given with an index leave no gap when dropped
+                let (dropped, oldest) = state.queue.pop_front().unwrap();
+                let dropped = dropped + matches!(oldest, Ok((_, (None, _)))) as usize;
 
-            if args.recovery && predictor.remaining_candidate_count() != count {
-                println!(
-                    "[+] Pruned to {} states after {} outputs and {:.2} seconds.",
-                    predictor.remaining_candidate_count(),
-                    outputs,
-                    start_time.elapsed().as_secs_f64()
-                );
+                match state.queue.front_mut() {
+                    Some((next, _)) => *next += dropped,
+                    None => state.dropped += dropped,
+                }
+            }
+
+            let dropped = std::mem::take(&mut state.dropped);
+            state.queue.push_back((dropped, result));
+            self.changed.notify_all();
+        }
+
+        Arc::strong_count(self) > 1
+    }
+
+    /// Takes the oldest output along with how many outputs were dropped just
+    /// before it, waiting up to some time for one.
+    pub fn pop(&self, timeout: Option<Duration>) -> Result<(usize, Located)> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if let Some((dropped, result)) = state.queue.pop_front() {
+                self.changed.notify_all();
+                return result.map(|observation| (dropped, observation));
             }
 
-            if args.recovery && predictor.remaining_candidate_count() <= THRESHOLD {
-                remaining_candidates = predictor.remaining_candidates();
-            } else if !args.recovery {
-                display_predictions(outputs + 1, predictor.predict_future_output());
+            let wait = deadline.map_or(Duration::MAX, |deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            });
+
+            if wait.is_zero() {
+                return Err(ErrorKind::TimedOut.into());
             }
-        } else {
-            for state in &mut remaining_candidates {
-                state.state = A * state.state + state.inc;
+
+            // wake up regularly to notice Ctrl-C, which may not have been
+            // delivered to the thread reading standard input
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return Err(Error::other("interrupted"));
             }
 
-            let count = remaining_candidates.len();
+            let wait = wait.min(Duration::from_millis(100));
+            state = self.changed.wait_timeout(state, wait).unwrap().0;
+        }
+    }
+}
+
+/// Keeps track of how fast outputs arrive and, with --stall-alert, warns from
+/// a background thread when none has arrived for a while. Only the time spent
+/// waiting for outputs counts, so that a hung target can be told apart from a
+/// slow solve.
+struct InputRate {
+    state: Mutex<RateState>,
+}
+
+#[derive(Default)]
+struct RateState {
+    first_arrival: Option<Instant>,
+    last_arrival: Option<Instant>,
+    arrivals: usize,
+    /// When the wait for the next output started, while waiting for it.
+    waiting_since: Option<Instant>,
+    longest_wait: Duration,
+    /// How many stall alerts were made during the current wait.
+    alerts: u32,
+    candidates: Option<usize>,
+}
 
-            remaining_candidates.retain(|state| compute_xsh_rr(state.state) == output);
+impl RateState {
+    /// Returns the number of outputs arriving per second so far.
+    fn rate(&self) -> Option<f64> {
+        let span = self.last_arrival?.duration_since(self.first_arrival?);
 
-            if remaining_candidates.len() != count {
-                println!(
-                    "[+] Pruned to {} states after {} outputs and {:.2} seconds.",
-                    remaining_candidates.len(),
-                    outputs,
-                    start_time.elapsed().as_secs_f64()
-                );
+        match span.is_zero() {
+            true => None,
+            false => Some((self.arrivals - 1) as f64 / span.as_secs_f64()),
+        }
+    }
+}
+
+impl InputRate {
+    pub fn new(stall_alert: Option<Duration>) -> Arc<Self> {
+        let rate = Arc::new(Self {
+            state: Mutex::new(RateState::default()),
+        });
+
+        if let Some(threshold) = stall_alert {
+            let watched = Arc::clone(&rate);
+            thread::spawn(move || watched.watch(threshold));
+        }
+
+        rate
+    }
+
+    /// Alerts whenever the wait for an output grows by another `threshold`,
+    /// until there is nothing left to watch.
+    fn watch(self: Arc<Self>, threshold: Duration) {
+        while Arc::strong_count(&self) > 1 {
+            thread::sleep(threshold.min(Duration::from_secs(1)));
+
+            let mut state = self.state.lock().unwrap();
+
+            let waited = match state.waiting_since {
+                Some(since) => since.elapsed(),
+                None => continue,
+            };
+
+            if waited < threshold * (state.alerts + 1) {
+                continue;
             }
 
-            if remaining_candidates.is_empty() {
-                return Err(Error::new(
-                    ErrorKind::Other,
-                    "output sequence not produced by PCG-XSH-RR",
-                ));
-            } else if let [recovered] = remaining_candidates.as_mut_slice() {
-                println!("[-] State recovery complete, rewinding state...");
+            state.alerts += 1;
 
-                for _ in 0..outputs - 1 {
-                    recovered.state = A_INV * (recovered.state - recovered.inc);
-                }
+            report!(
+                "[!] No output received for {} seconds{}, still waiting{}.",
+                waited.as_secs(),
+                state
+                    .rate()
+                    .map(|rate| format!(" (previously {:.1} outputs per second)", rate))
+                    .unwrap_or_default(),
+                state
+                    .candidates
+                    .map(|count| format!(" with {} candidate states left", count))
+                    .unwrap_or_default()
+            );
+        }
+    }
 
-                println!(
-                    "[+] Generator internal state fully recovered after {:.2} seconds:",
-                    start_time.elapsed().as_secs_f64()
-                );
+    /// Records that the program is waiting for the next output.
+    pub fn waiting(&self) {
+        self.state.lock().unwrap().waiting_since = Some(Instant::now());
+    }
 
-                println!("\n    pcg32_random_t state = {{");
-                println!("        .state = 0x{:016X}", recovered.state);
-                println!("        .inc   = 0x{:016X}", recovered.inc);
-                println!("    }};\n");
+    /// Records that the wait is over, whether an output arrived or not.
+    pub fn arrived(&self, output: bool) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
 
-                return Ok(());
+        if let Some(since) = state.waiting_since.take() {
+            state.longest_wait = state.longest_wait.max(now - since);
+
+            if std::mem::take(&mut state.alerts) > 0 && output {
+                report!(
+                    "[-] Output received after waiting {} seconds, resuming.",
+                    (now - since).as_secs()
+                );
             }
         }
+
+        if output {
+            state.first_arrival.get_or_insert(now);
+            state.last_arrival = Some(now);
+            state.arrivals += 1;
+        }
     }
 
-    if args.recovery {
-        println!("[-] Not enough outputs available to complete state recovery.");
+    pub fn set_candidates(&self, count: usize) {
+        self.state.lock().unwrap().candidates = Some(count);
     }
 
-    Ok(())
+    /// Returns the number of outputs arriving per second, if known, and the
+    /// longest wait for one.
+    pub fn statistics(&self) -> (Option<f64>, Duration) {
+        let state = self.state.lock().unwrap();
+        (state.rate(), state.longest_wait)
+    }
 }
 
-fn main() {
-    if let Err(err) = run(Opt::from_args()) {
-        eprintln!("\nfatal error: {}", err);
-        process::exit(1); // report failure
-    }
+/// Reads outputs from a log file as lines are appended to it, like `tail -F`:
+/// the file may not exist yet, and is read again from the start if it is
+/// truncated or replaced by a shorter one (e.g. when the log is rotated).
+struct Follower {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+    /// How far into the file has been read.
+    offset: u64,
+    /// The last line read, until it is complete.
+    line: String,
+    /// Number of complete lines read since the file was last opened.
+    lines: usize,
+    position: Position,
+    queue: VecDeque<Observation>,
+    idle_timeout: Option<Duration>,
 }
 
-#[derive(StructOpt)]
-#[structopt(about)]
-struct Opt {
-    #[structopt(long = "recovery")]
-    recovery: bool,
+impl Follower {
+    pub fn new(path: &Path, idle_timeout: Option<Duration>) -> Self {
+        Self {
+            path: path.to_owned(),
+            reader: None,
+            offset: 0,
+            line: String::new(),
+            lines: 0,
+            position: Position::Unknown,
+            queue: VecDeque::new(),
+            idle_timeout,
+        }
+    }
 
-    #[structopt(long = "binary")]
-    binary: bool,
+    pub fn read_output(&mut self) -> Result<Observation> {
+        let mut last_read = Instant::now();
 
-    #[structopt(parse(from_os_str))]
-    table: PathBuf,
-}
+        while self.queue.is_empty() {
+            let read = match &mut self.reader {
+                Some(reader) => reader.read_line(&mut self.line)?,
+                None => 0,
+            };
 
-const ASCII_HEADER: &str = r#"
-  ____   ___  ___    ____  ____  ____   __   __ _  ____  ____
- (  _ \ / __)/ __)  (  _ \(  _ \(  __) / _\ (  / )(  __)(  _ \
-  ) __/( (__( (_ \   ) _ ( )   / ) _) /    \ )  (  ) _)  )   /
- (__)   \___)\___/  (____/(__\_)(____)\_/\_/(__\_)(____)(__\_)
+            self.offset += read as u64;
 
-         PCG-XSH-RR Output Prediction & State Recovery
-"#;
+            if read > 0 {
+                last_read = Instant::now();
+
+                if self.line.ends_with('\n') {
+                    self.lines += 1;
+                    self.position = Position::Line(self.lines);
+
+                    parse_observations(&self.line, &mut self.queue)?;
+                    self.line.clear();
+                }
+
+                continue;
+            }
+
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return Err(Error::other("interrupted"));
+            }
+
+            if self
+                .idle_timeout
+                .is_some_and(|timeout| last_read.elapsed() >= timeout)
+            {
+                return Err(ErrorKind::TimedOut.into());
+            }
+
+            let length = std::fs::metadata(&self.path).map(|metadata| metadata.len());
+
+            if self.reader.is_none() || length.is_ok_and(|length| length < self.offset) {
+                self.reader = File::open(&self.path).ok().map(BufReader::new);
+                self.offset = 0;
+                self.lines = 0;
+                self.line.clear();
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(self.queue.pop_front().unwrap())
+    }
+}
+
+/// Replays outputs recorded as JSON lines along with when they were observed,
+/// such as `{"time": 1700000000.25, "output": "0x1A2B3C4D"}`, waiting between
+/// them as long as they originally took to arrive (divided by the speed). The
+/// times are in seconds, from any origin; the output may be a number or a
+/// string holding outputs written as on standard input.
+struct Replayer {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+    speed: f64,
+    /// The time of the first output recorded, and when it was replayed.
+    origin: Option<(f64, Instant)>,
+    lines: usize,
+    position: Position,
+    queue: VecDeque<Observation>,
+}
+
+impl Replayer {
+    pub fn new(path: &Path, speed: f64) -> Self {
+        Self {
+            path: path.to_owned(),
+            reader: None,
+            speed,
+            origin: None,
+            lines: 0,
+            position: Position::Unknown,
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn read_output(&mut self) -> Result<Observation> {
+        if self.reader.is_none() {
+            self.reader = Some(BufReader::new(File::open(&self.path)?));
+        }
+
+        let mut line = String::new();
+
+        while self.queue.is_empty() {
+            line.clear();
+
+            if self.reader.as_mut().unwrap().read_line(&mut line)? == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+
+            self.lines += 1;
+            self.position = Position::Line(self.lines);
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = Json::parse(&line)?;
+            let field = |name| {
+                record
+                    .get(name)
+                    .ok_or_else(|| Error::other(format!("no `{}` in `{}`", name, line.trim())))
+            };
+
+            let time = match field("time")? {
+                Json::Number(time) => time.parse::<f64>().map_err(Error::other)?,
+                _ => {
+                    return Err(Error::other(
+                        "the time of a recorded output must be a number",
+                    ))
+                }
+            };
+
+            let (first, start) = *self.origin.get_or_insert((time, Instant::now()));
+            let due = start + Duration::from_secs_f64(((time - first) / self.speed).max(0.0));
+
+            while Instant::now() < due {
+                if INTERRUPTED.load(Ordering::SeqCst) {
+                    return Err(Error::other("interrupted"));
+                }
+
+                thread::sleep((due - Instant::now()).min(Duration::from_millis(100)));
+            }
+
+            field("output")?.outputs(&mut self.queue)?;
+        }
+
+        Ok(self.queue.pop_front().unwrap())
+    }
+}
+
+/// Runs the oracle command once, reading a single output from its stdout.
+fn query_oracle(command: &str) -> Result<Observation> {
+    let result = Command::new("sh").arg("-c").arg(command).output()?;
+
+    if !result.status.success() {
+        return Err(Error::other(format!(
+            "command exited with {}",
+            result.status
+        )));
+    }
+
+    parse_observation(String::from_utf8_lossy(&result.stdout).trim())
+}
+
+/// Reads outputs from standard input along with their (1-based) indices,
+/// which default to following on from the previous output.
+struct Input {
+    source: Source,
+    last_index: usize,
+    rate: Arc<InputRate>,
+    /// Whether the byte order of binary outputs is still to be determined.
+    detect_byte_order: bool,
+
+    byteswap: bool,
+    dedupe_consecutive: bool,
+    every_nth: usize,
+    transforms: Vec<Transform>,
+
+    last_raw_output: Option<u32>,
+    raw_outputs: usize,
+    /// Outputs dropped by --buffer (among those kept by --every-nth) which
+    /// are yet to be skipped over.
+    dropped: usize,
+
+    binary: bool,
+    /// The first outputs read, before any input transform, for diagnostics.
+    first_outputs: Vec<u32>,
+
+    /// Whether observations are derived from consecutive outputs.
+    derived: bool,
+    /// How to recover outputs from observations, once the first is known.
+    derivation: Option<Derivation>,
+    /// The known first output, yet to be returned before any observation.
+    first_output: Option<u32>,
+    previous_output: Option<u32>,
+
+    /// Number of outputs from one observation to the next, with --stride.
+    stride: usize,
+
+    /// Where the latest outputs were read from, by index.
+    positions: VecDeque<(usize, Position)>,
+    /// Whether hexadecimal without a `0x` prefix has been read.
+    plain_hex: bool,
+    /// The other reading of the latest outputs which could be read either
+    /// as decimal or as hexadecimal, by index.
+    alternatives: VecDeque<(usize, u32)>,
+}
+
+/// Number of outputs whose position in their source is remembered.
+const REMEMBERED_POSITIONS: usize = 1 << 12;
+
+impl Input {
+    pub fn new(args: &Opt, last_index: usize) -> Self {
+        let format = if args.binary {
+            InputFormat::Binary
+        } else {
+            args.input_format
+        };
+
+        let (source, detected) = Source::new(format, args);
+
+        if format == InputFormat::Auto {
+            report!(
+                "[-] Detected {} input.",
+                match detected {
+                    InputFormat::Binary => "binary",
+                    _ => "text",
+                }
+            );
+        }
+
+        let stall_alert =
+            Some(Duration::from_secs(args.stall_alert)).filter(|alert| !alert.is_zero());
+
+        Self {
+            source,
+            last_index,
+            rate: InputRate::new(stall_alert),
+            // transforms make the byte order of raw outputs ambiguous
+            detect_byte_order: format == InputFormat::Auto
+                && detected == InputFormat::Binary
+                && !args.byteswap
+                && args.transform.is_empty(),
+
+            byteswap: args.byteswap,
+            dedupe_consecutive: args.dedupe_consecutive,
+            every_nth: args.every_nth.max(1),
+            transforms: args.transform.clone(),
+
+            last_raw_output: None,
+            raw_outputs: 0,
+            dropped: 0,
+
+            binary: detected == InputFormat::Binary,
+            first_outputs: vec![],
+
+            derived: args.derived.is_some(),
+            derivation: args.first_output.and(args.derived),
+            first_output: args.first_output.filter(|_| args.derived.is_some()),
+            previous_output: args.first_output,
+
+            stride: args.stride,
+
+            positions: VecDeque::new(),
+            plain_hex: false,
+            alternatives: VecDeque::new(),
+        }
+    }
+
+    /// Describes where an output was read from, as ` at line 12 of <path>`,
+    /// or as nothing if it isn't known (or remembered).
+    pub fn located(&self, index: usize) -> String {
+        let position = self.position(index);
+        self.describe(position)
+    }
+
+    /// Describes where a range of outputs was read from, like `located`.
+    pub fn located_range(&self, first: usize, last: usize) -> String {
+        match (self.position(first), self.position(last)) {
+            (Position::Unknown, _) | (_, Position::Unknown) => String::new(),
+            (first, last) if first == last => self.describe(first),
+            (first, last) => format!(" at {} to {} of {}", first, last, self.source.name()),
+        }
+    }
+
+    fn position(&self, index: usize) -> Position {
+        self.positions
+            .iter()
+            .rev()
+            .find(|&&(read, _)| read == index)
+            .map_or(Position::Unknown, |&(_, position)| position)
+    }
+
+    fn describe(&self, position: Position) -> String {
+        match position {
+            Position::Unknown => String::new(),
+            position => format!(" at {} of {}", position, self.source.name()),
+        }
+    }
+
+    /// Reads the next line of observations which aren't outputs.
+    pub fn next_line(&mut self) -> Result<String> {
+        if self.binary {
+            return Err(Error::other("samples can't be read as binary"));
+        }
+
+        self.rate.waiting();
+        let result = self.source.read_line();
+        self.rate.arrived(result.is_ok());
+
+        result
+    }
+
+    /// Reads the next sample drawn by the target in place of outputs, along
+    /// with how far off it can be (see `parse_sample`).
+    pub fn next_sample(&mut self) -> Result<(f64, f64)> {
+        parse_sample(&self.next_line()?)
+    }
+
+    /// Reads the next output along with its index.
+    pub fn next_output(&mut self) -> Result<(usize, u32)> {
+        if let Some(first_output) = self.first_output.take() {
+            self.last_index += 1;
+            return Ok((self.last_index, first_output));
+        }
+
+        let (index, mut output, alternative) = self.next_raw_output()?;
+
+        if self.derived && index.is_some() {
+            return Err(Error::other(
+                "derived observations can't be given with an index",
+            ));
+        }
+
+        let dropped = std::mem::take(&mut self.dropped);
+
+        if dropped > 0 && index.is_none() {
+            if self.derived {
+                return Err(Error::other(
+                    "dropped outputs break the derived observations",
+                ));
+            }
+
+            report!(
+                "[!] Dropped {} outputs read too far ahead, skipping over them.",
+                dropped
+            );
+        }
+
+        if let (Some(derivation), Some(previous)) = (self.derivation, self.previous_output) {
+            output = derivation.underive(previous, output);
+            self.previous_output = Some(output);
+        }
+
+        let index = match index {
+            Some(index) => index
+                .checked_sub(INDEX_OFFSET.load(Ordering::Relaxed))
+                .filter(|&index| index > 0)
+                .ok_or_else(|| {
+                    Error::other(format!(
+                        "output #{} given before --first-index{}",
+                        index,
+                        self.describe(self.source.position())
+                    ))
+                })?,
+            // observations are `stride` outputs apart, from the first output
+            None if self.last_index == 0 => 1 + dropped * self.stride,
+            None => self.last_index + (dropped + 1) * self.stride,
+        };
+
+        if index <= self.last_index {
+            return Err(Error::other(format!(
+                "output #{} given after output #{}{}",
+                display_index(index),
+                display_index(self.last_index),
+                self.describe(self.source.position())
+            )));
+        }
+
+        if self.positions.len() == REMEMBERED_POSITIONS {
+            self.positions.pop_front();
+        }
+
+        self.positions.push_back((index, self.source.position()));
+
+        // an underived observation depends on the reading of the one before
+        if let (Some(alternative), None) = (alternative, self.derivation) {
+            if self.alternatives.len() == REMEMBERED_POSITIONS {
+                self.alternatives.pop_front();
+            }
+
+            self.alternatives.push_back((index, alternative));
+        }
+
+        self.last_index = index;
+        Ok((index, output))
+    }
+
+    /// Returns the other reading of an output, if it could be read both as
+    /// decimal and as hexadecimal, after the same input transforms.
+    pub fn alternative(&self, index: usize) -> Option<u32> {
+        (self.alternatives.iter())
+            .find(|&&(alternative_index, _)| alternative_index == index)
+            .map(|&(_, alternative)| alternative)
+    }
+
+    /// Settles how an output is read. Decimal digits are read as decimal,
+    /// unless they are fixed-width once hexadecimal without a `0x` prefix has
+    /// been read, and the other reading is handed back along with them.
+    fn settle(&mut self, reading: Reading) -> (u32, Option<u32>) {
+        match reading {
+            Reading::Exact(output) => (output, None),
+            Reading::PlainHex(output) => {
+                if !std::mem::replace(&mut self.plain_hex, true) {
+                    report!(
+                        "[!] Reading `{:x}` as hexadecimal without a 0x prefix; from now on, \
+                         outputs made only of 8 decimal digits or zero-padded are read as \
+                         hexadecimal too, and other decimal digits as decimal.",
+                        output
+                    );
+                }
+
+                (output, None)
+            }
+            Reading::Digits {
+                decimal,
+                hex,
+                fixed_width,
+            } => match self.plain_hex && fixed_width {
+                true => (hex, Some(decimal)),
+                false => (decimal, Some(hex)),
+            },
+        }
+    }
+
+    /// Reads the next output which isn't discarded by the input transforms,
+    /// along with its other reading if it has one.
+    fn next_raw_output(&mut self) -> Result<(Option<usize>, u32, Option<u32>)> {
+        loop {
+            self.rate.waiting();
+            let result = self.source.read_output();
+            self.rate.arrived(result.is_ok());
+
+            let (index, reading) = result?;
+            let (mut output, mut alternative) = self.settle(reading);
+            let dropped = self.source.take_dropped();
+
+            if dropped > 0 {
+                // count how many of the dropped outputs would have been kept
+                let kept = |raw: usize| raw.div_ceil(self.every_nth);
+                self.dropped += kept(self.raw_outputs + dropped) - kept(self.raw_outputs);
+                self.raw_outputs += dropped;
+            }
+
+            if self.first_outputs.len() < DIAGNOSIS_OUTPUTS {
+                self.first_outputs.push(output);
+            }
+
+            if self.byteswap {
+                output = output.swap_bytes();
+                alternative = alternative.map(u32::swap_bytes);
+            }
+
+            if self.dedupe_consecutive && self.last_raw_output.replace(output) == Some(output) {
+                continue;
+            }
+
+            self.raw_outputs += 1;
+
+            if (self.raw_outputs - 1).is_multiple_of(self.every_nth) {
+                let transform =
+                    |output| (self.transforms.iter()).fold(output, |value, step| step.apply(value));
+
+                return Ok((index, transform(output), alternative.map(transform)));
+            }
+        }
+    }
+}
+
+/// Known properties of the generator, used to discard candidates.
+#[derive(Default)]
+struct CandidateFilter {
+    inc_equals: Option<u64>,
+    inc_allowed: Option<HashSet<u64>>,
+    inc_low_entropy: bool,
+    inc_address: bool,
+
+    state_mask: u64,
+    state_value: u64,
+    state_index: usize,
+}
+
+impl CandidateFilter {
+    pub fn from_args(args: &Opt) -> Result<Self> {
+        let inc_allowed = match &args.inc_in_file {
+            Some(path) => Some(
+                read_to_string(path)?
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| parse::<u64>(line).map(|inc| inc | 1).map_err(Error::other))
+                    .collect::<Result<_>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            inc_equals: args.inc_equals.map(|inc| inc | 1),
+            inc_allowed,
+            inc_low_entropy: args.inc_low_entropy,
+            inc_address: args.unique,
+
+            state_mask: args.state_mask,
+            state_value: args.state_value & args.state_mask,
+            state_index: args.state_index,
+        })
+    }
+
+    /// Returns whether any property of the generator is known.
+    pub fn is_active(&self) -> bool {
+        self.inc_equals.is_some()
+            || self.inc_allowed.is_some()
+            || self.inc_low_entropy
+            || self.inc_address
+            || self.state_mask != 0
+    }
+
+    /// Returns whether a candidate satisfies every known property, given the
+    /// number of outputs produced by the generator up to that candidate.
+    pub fn matches(&self, candidate: &Pcg32State, outputs: usize) -> bool {
+        let inc = candidate.inc.0 | 1;
+
+        if self.inc_equals.is_some_and(|equals| equals != inc) {
+            return false;
+        }
+
+        if let Some(allowed) = &self.inc_allowed {
+            if !allowed.contains(&inc) {
+                return false;
+            }
+        }
+
+        // increments of the form (seq << 1) | 1 with a 32-bit stream selector
+        if self.inc_low_entropy && inc >> 33 != 0 {
+            return false;
+        }
+
+        if self.inc_address && unique_address(inc).is_none() {
+            return false;
+        }
+
+        if self.state_mask != 0 {
+            let delta = (self.state_index as u64).wrapping_sub(outputs as u64);
+
+            if candidate.jump(delta).state.0 & self.state_mask != self.state_value {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Writes candidate states to a file, preceded by the number of outputs
+/// which had been consumed at the point the candidates were obtained.
+fn dump_candidates<P: AsRef<Path>>(path: P, outputs: usize, states: &[Pcg32State]) -> Result<()> {
+    let mut data = Vec::with_capacity(1 + 2 * states.len());
+    data.push(outputs as u64);
+
+    for state in states {
+        data.push(state.state.0);
+        data.push(state.inc.0);
+    }
+
+    write(path, cast_slice(&data))
+}
+
+/// Reads candidate states previously written by `dump_candidates`.
+fn load_candidates<P: AsRef<Path>>(path: P) -> Result<(usize, Vec<Pcg32State>)> {
+    let bytes = read(path)?;
+
+    if bytes.len() % 16 != 8 {
+        return Err(Error::other("malformed candidate file"));
+    }
+
+    let mut data = vec![0u64; bytes.len() / 8];
+    cast_slice_mut(&mut data).copy_from_slice(&bytes);
+
+    let states = data[1..]
+        .chunks_exact(2)
+        .map(|pair| Pcg32State {
+            state: W(pair[0]),
+            inc: W(pair[1]),
+        })
+        .collect();
+
+    Ok((data[0] as usize, states))
+}
+
+/// Prints the progress of the tracker to the console.
+struct Console {
+    label: Option<String>,
+    rng: Pcg32State,
+    start_time: Instant,
+    outputs: usize,
+    predictions: bool,
+    recovery: bool,
+    seed_search: Option<u64>,
+    rand_seed: bool,
+    pcg_cpp: bool,
+    unique: bool,
+    tokens: Option<TokenFormat>,
+    bounded: Option<u32>,
+    sampler: Option<(Sampler, usize)>,
+    progress: bool,
+    /// Whether to estimate how many more outputs are needed for recovery.
+    oracle: bool,
+    /// Whether to report the bits of uncertainty left after each output.
+    entropy: bool,
+    /// How many table queries needed a wide scan, once the table is released.
+    wide_scans: usize,
+    predictions_csv: Option<PredictionLog>,
+    /// Where to publish the progress for commands sent to --control.
+    control: Option<Arc<Mutex<ControlSnapshot>>>,
+}
+
+impl Console {
+    /// Records and displays the prediction made for the output at some
+    /// index, along with the support of each predicted output if known.
+    fn predict(&mut self, index: usize, prediction: &Prediction, support: Option<&[usize]>) {
+        if let Some(log) = &mut self.predictions_csv {
+            if let Err(err) = log.predict(index, prediction) {
+                report!("[!] Failed to write to the predictions CSV: {}", err);
+                self.predictions_csv = None;
+            }
+        }
+
+        if self.predictions {
+            if EMIT_BINARY.load(Ordering::Relaxed) {
+                emit_binary(prediction.first());
+            }
+
+            display_predictions(
+                &output_name(self.label.as_deref(), index),
+                prediction,
+                support,
+            );
+        }
+    }
+
+    /// Records the output actually observed at some index, to check it
+    /// against the prediction made for it.
+    fn observe(&mut self, index: usize, output: u32) {
+        if let Some(log) = &mut self.predictions_csv {
+            if let Err(err) = log.observe(index, output) {
+                report!("[!] Failed to write to the predictions CSV: {}", err);
+                self.predictions_csv = None;
+            }
+        }
+    }
+}
+
+/// Records every prediction made, along with the output it turned out to be,
+/// as CSV for --predictions-csv.
+struct PredictionLog {
+    writer: BufWriter<File>,
+    /// The latest prediction, until the output it is for is observed.
+    pending: Option<(usize, Prediction)>,
+}
+
+impl PredictionLog {
+    fn create(path: &Path) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "index,predicted,alternative,actual,correct")?;
+
+        Ok(Self {
+            writer,
+            pending: None,
+        })
+    }
+
+    /// Records the prediction for an upcoming output, writing out the one
+    /// before it if its output was never observed (e.g. when skipped).
+    fn predict(&mut self, index: usize, prediction: &Prediction) -> Result<()> {
+        match self.pending.replace((index, prediction.clone())) {
+            Some(previous) => self.write(previous, None),
+            None => Ok(()),
+        }
+    }
+
+    fn observe(&mut self, index: usize, output: u32) -> Result<()> {
+        match self.pending.take() {
+            Some(pending) if pending.0 == index => self.write(pending, Some(output)),
+            Some(pending) => self.write(pending, None),
+            None => Ok(()),
+        }
+    }
+
+    fn write(
+        &mut self,
+        (index, prediction): (usize, Prediction),
+        actual: Option<u32>,
+    ) -> Result<()> {
+        let alternative = match prediction.outputs() {
+            [_, alternative, ..] => format!("0x{:08X}", alternative),
+            _ => String::new(),
+        };
+
+        let (actual, correct) = match actual {
+            Some(actual) => (
+                format!("0x{:08X}", actual),
+                prediction.contains(actual).to_string(),
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        writeln!(
+            self.writer,
+            "{},0x{:08X},{},{},{}",
+            display_index(index),
+            prediction.first(),
+            alternative,
+            actual,
+            correct
+        )
+    }
+}
+
+impl Drop for PredictionLog {
+    fn drop(&mut self) {
+        // the last prediction is for an output which was never read
+        if let Some(pending) = self.pending.take() {
+            let _ = self.write(pending, None);
+        }
+    }
+}
+
+/// Most candidate states copied for the control socket after each output,
+/// beyond which they can't be checkpointed through it.
+const CONTROL_STATES: usize = 1 << 16;
+
+/// A Unix socket accepting commands with --control while outputs keep being
+/// read, one per line, each answered by one or more lines. Commands are
+/// answered by background threads from the progress published after each
+/// output, so they don't wait for the next output to arrive.
+struct ControlSocket {
+    path: PathBuf,
+}
+
+/// The progress as of the latest output, as needed to answer commands.
+#[derive(Default)]
+struct ControlSnapshot {
+    outputs: usize,
+    candidates: usize,
+    /// The predicted next output.
+    prediction: Option<Prediction>,
+    /// The candidate states, unless there are too many to copy.
+    states: Option<Vec<Pcg32State>>,
+}
+
+impl ControlSocket {
+    pub fn bind(path: &Path, snapshot: Arc<Mutex<ControlSnapshot>>) -> Result<Self> {
+        // a socket left behind by an earlier run would fail the bind
+        if std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let snapshot = Arc::clone(&snapshot);
+                thread::spawn(move || Self::serve(stream, &snapshot));
+            }
+        });
+
+        report!("[-] Accepting commands on {}.", path.display());
+
+        Ok(Self {
+            path: path.to_owned(),
+        })
+    }
+
+    fn serve(stream: UnixStream, snapshot: &Mutex<ControlSnapshot>) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+
+            let reply = match words.next() {
+                Some(command) => {
+                    let snapshot = snapshot.lock().unwrap();
+                    Self::execute(&snapshot, command, words.next())
+                }
+                None => continue,
+            };
+
+            match reply {
+                Ok(reply) => writeln!(writer, "{}", reply)?,
+                Err(err) => writeln!(writer, "error: {}", err)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute(
+        snapshot: &ControlSnapshot,
+        command: &str,
+        argument: Option<&str>,
+    ) -> Result<String> {
+        let recovered = match &snapshot.states {
+            Some(states) if states.len() == 1 => Some(states[0]),
+            _ => None,
+        };
+
+        match command {
+            "status" => Ok(format!(
+                "outputs {}, {} candidate states, {}",
+                display_index(snapshot.outputs),
+                snapshot.candidates,
+                if recovered.is_some() {
+                    "recovered"
+                } else {
+                    "recovering"
+                }
+            )),
+            "predict" => {
+                let count: usize = match argument {
+                    Some(count) => parse(count).map_err(Error::other)?,
+                    None => 1,
+                };
+
+                let first = display_index(snapshot.outputs + 1);
+
+                match (recovered, &snapshot.prediction) {
+                    (Some(state), _) => Ok((0..count)
+                        .map(|k| {
+                            format!(
+                                "#{} 0x{:08X}",
+                                first + k,
+                                state.jump(k as u64).next_output()
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")),
+                    (None, Some(prediction)) if count == 1 => Ok(format!(
+                        "#{} {}",
+                        first,
+                        (prediction.outputs().iter())
+                            .map(|output| format!("0x{:08X}", output))
+                            .collect::<Vec<_>>()
+                            .join(" or ")
+                    )),
+                    (None, Some(_)) => Err(Error::other(
+                        "only the next output can be predicted until the state is recovered",
+                    )),
+                    (None, None) => Err(Error::other("no prediction made yet")),
+                }
+            }
+            "state" => match recovered {
+                Some(state) => {
+                    let state = state.jump(1u64.wrapping_sub(snapshot.outputs as u64));
+                    Ok(format!(
+                        ".state = 0x{:016X}, .inc = 0x{:016X}",
+                        state.state, state.inc
+                    ))
+                }
+                None => Err(Error::other(format!(
+                    "not recovered yet, with {} candidate states left",
+                    snapshot.candidates
+                ))),
+            },
+            "checkpoint" => match &snapshot.states {
+                Some(states) => {
+                    let path = argument.unwrap_or(CHECKPOINT_FILE);
+                    dump_candidates(path, snapshot.outputs, states)?;
+
+                    Ok(format!(
+                        "wrote {} candidate states to {}, resume from output #{}",
+                        states.len(),
+                        path,
+                        display_index(snapshot.outputs + 1)
+                    ))
+                }
+                None => Err(Error::other(
+                    "too many candidate states left to write a checkpoint",
+                )),
+            },
+            "help" => Ok("commands: status, predict [count], state, checkpoint [path]".to_owned()),
+            _ => Err(Error::other(format!(
+                "unknown command `{}`, try `help`",
+                command
+            ))),
+        }
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Observer for Console {
+    fn on_pruned(&mut self, count: usize) {
+        if self.recovery {
+            // the predictor starts out with up to 2^27 candidates
+            let converged = 1.0 - (count as f64).log2() / 27.0;
+
+            report!(
+                "[+] Pruned to {} states{} after {} outputs{} and {:.2} seconds.",
+                count,
+                if self.progress {
+                    format!(" ({:.0}% converged)", 100.0 * converged.clamp(0.0, 1.0))
+                } else {
+                    String::new()
+                },
+                self.outputs,
+                self.label
+                    .as_ref()
+                    .map(|label| format!(" of `{}`", label))
+                    .unwrap_or_default(),
+                self.start_time.elapsed().as_secs_f64()
+            );
+        }
+
+        // a heuristic: the bits pruned so far, from the 2^27 candidates the
+        // predictor starts out with after four outputs, are taken to keep
+        // being pruned at the same rate (enumerated candidates are pruned
+        // much faster, so this errs on the high side)
+        let pruned = 27.0 - (count as f64).log2();
+
+        if self.oracle && count > 1 && self.outputs > 4 && pruned > 0.0 {
+            let rate = pruned / (self.outputs - 4) as f64;
+
+            report!(
+                "[-] Roughly {} more outputs needed to recover the state (heuristic, from \
+                 {:.1} bits pruned per output so far).",
+                ((count as f64).log2() / rate).ceil(),
+                rate
+            );
+        }
+    }
+
+    fn on_table_released(&mut self, table: &LookupTable) {
+        self.wide_scans = table.wide_scans();
+
+        // in --labeled mode, the table is kept for generators yet to come
+        if self.label.is_none() {
+            report!("[-] Enumerated the candidates, releasing the table.");
+        }
+    }
+
+    fn on_prediction(&mut self, prediction: &Prediction, candidates: &Candidates) {
+        if let Some(control) = &self.control {
+            let mut snapshot = control.lock().unwrap();
+
+            snapshot.outputs = self.outputs;
+            snapshot.candidates = candidates.count();
+            snapshot.prediction = Some(prediction.clone());
+            snapshot.states = match candidates {
+                Candidates::Explicit(states) if states.len() <= CONTROL_STATES => {
+                    Some(states.clone())
+                }
+                _ => None,
+            };
+        }
+
+        if self.entropy {
+            report!(
+                "[-] After {} outputs{}, the state is determined to within {:.1} bits ({} candidates{}).",
+                self.outputs,
+                self.label
+                    .as_ref()
+                    .map(|label| format!(" of `{}`", label))
+                    .unwrap_or_default(),
+                candidates.entropy(),
+                candidates.count(),
+                match candidates {
+                    Candidates::Branched { branches, .. } => {
+                        format!(" in {} branches", branches.len())
+                    }
+                    _ => String::new(),
+                }
+            );
+        }
+
+        // a predictor made for a stride predicts the next observed output
+        let next = self.outputs
+            + candidates
+                .table()
+                .map_or(1, |table| table.stride() as usize);
+
+        let support = match prediction {
+            Prediction::Ambiguous(_) if self.predictions => {
+                Some(candidates.prediction_support(SUPPORT_SAMPLES, &mut self.rng))
+            }
+            _ => None,
+        };
+
+        self.predict(next, prediction, support.as_deref());
+
+        if let Some(bound) = self.bounded.filter(|_| self.predictions) {
+            display_bounded_predictions(prediction, candidates, bound);
+        }
+    }
+
+    fn on_recovered(&mut self, state: &Pcg32State) {
+        if self.recovery {
+            if let Some(label) = &self.label {
+                report!(
+                    "[+] Recovered the state of the generator labeled `{}`.",
+                    label
+                );
+            }
+
+            let initial = display_recovered_state(*state, self.outputs, self.start_time);
+
+            if let Some(name) = default_increment_name(initial.inc.0) {
+                report!(
+                    "[+] The increment is the default one of {}, so only the seed remains to be found.",
+                    name
+                );
+            }
+
+            match self.seed_search {
+                Some(max_steps) => search_seed(initial, max_steps),
+                None if default_increment_name(initial.inc.0).is_some() => {
+                    search_seed(initial, DEFAULT_SEED_SEARCH)
+                }
+                None => {}
+            }
+
+            if self.rand_seed {
+                display_rand_seed(initial);
+            }
+
+            if self.pcg_cpp {
+                display_pcg_cpp_seed(initial);
+            }
+
+            if self.unique {
+                display_unique_address(initial);
+            }
+
+            if let Some(format) = &self.tokens {
+                display_tokens(*state, self.label.as_deref(), self.outputs + 1, format);
+            }
+
+            if let Some((sampler, count)) = &self.sampler {
+                display_samples(
+                    *state,
+                    self.label.as_deref(),
+                    self.outputs + 1,
+                    sampler,
+                    *count,
+                );
+            }
+        }
+    }
+}
+
+/// Names an output by its index, qualified by the label of its generator.
+fn output_name(label: Option<&str>, index: usize) -> String {
+    match label {
+        Some(label) => format!("#{} of `{}`", display_index(index), label),
+        None => format!("#{}", display_index(index)),
+    }
+}
+
+/// Writes a predicted output to standard output as a raw little-endian word.
+fn emit_binary(output: u32) {
+    let mut stdout = std::io::stdout().lock();
+
+    if stdout
+        .write_all(&output.to_le_bytes())
+        .and_then(|_| stdout.flush())
+        .is_err()
+    {
+        // the consumer has stopped reading, so there is nobody left to tell
+        process::exit(0);
+    }
+}
+
+/// Largest number of missing outputs the predictor branches on, before the
+/// candidates are enumerated, rather than jumping past them.
+const MAX_BRANCHED_OUTPUTS: usize = 8;
+
+/// Number of outputs before an inconsistent one which --max-errors checks
+/// for being the actual inconsistent output, while candidates are tracked
+/// implicitly.
+const RECHECKED_OUTPUTS: usize = 8;
+
+/// Number of candidates sampled to estimate the support of each prediction.
+const SUPPORT_SAMPLES: usize = 1 << 16;
+
+fn display_predictions(name: &str, prediction: &Prediction, support: Option<&[usize]>) {
+    match support {
+        None => report!(
+            "\n[+] Output {} will be 0x{:08X}\n",
+            name,
+            prediction.first()
+        ),
+        Some(support) => {
+            let total = support.iter().sum::<usize>().max(1) as f64;
+
+            let outputs: Vec<String> = (prediction.outputs().iter().zip(support))
+                .map(|(output, &states)| {
+                    format!(
+                        "0x{:08X} ({:.1}%, {} states)",
+                        output,
+                        100.0 * states as f64 / total,
+                        states
+                    )
+                })
+                .collect();
+
+            report!("\n[+] Output {} will be {}\n", name, outputs.join(" OR "));
+        }
+    }
+}
+
+/// Predicts what `pcg32_boundedrand(bound)` will return if called on the
+/// next output, simulating its rejection loop once the state is recovered.
+fn display_bounded_predictions(prediction: &Prediction, candidates: &Candidates, bound: u32) {
+    if let Some(state) = candidates.recovered_state() {
+        let mut generator = state;
+        generator.advance();
+
+        let (value, consumed) = generator.next_bounded(bound);
+
+        report!(
+            "[+] pcg32_boundedrand({}) will return {} (consuming {} outputs)\n",
+            bound,
+            value,
+            consumed
+        );
+
+        return;
+    }
+
+    let threshold = bound.wrapping_neg() % bound;
+
+    let values: Vec<String> = (prediction.outputs().iter())
+        .map(|&output| match output {
+            output if output >= threshold => format!("{}", output % bound),
+            _ => "a value from the outputs after it (rejected)".to_owned(),
+        })
+        .collect();
+
+    report!(
+        "[+] pcg32_boundedrand({}) will return {}\n",
+        bound,
+        values.join(" OR ")
+    );
+}
+
+/// Reports the progress of a long-running step, as a bar redrawn in place
+/// when standard error is a terminal and as a line every few seconds
+/// otherwise.
+struct Progress {
+    label: &'static str,
+    enabled: bool,
+    terminal: bool,
+    last_update: Instant,
+}
+
+impl Progress {
+    pub fn new(label: &'static str, enabled: bool) -> Self {
+        Self {
+            label,
+            enabled,
+            terminal: std::io::stderr().is_terminal(),
+            last_update: Instant::now(),
+        }
+    }
+
+    pub fn update(&mut self, done: usize, total: usize) {
+        let interval = if self.terminal {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(5)
+        };
+
+        if !self.enabled || self.last_update.elapsed() < interval {
+            return;
+        }
+
+        self.last_update = Instant::now();
+        let fraction = done as f64 / total.max(1) as f64;
+
+        if self.terminal {
+            let filled = (fraction * 40.0) as usize;
+
+            eprint!(
+                "\r{} [{:<40}] {:>3.0}%",
+                self.label,
+                "#".repeat(filled),
+                100.0 * fraction
+            );
+        } else {
+            report!("[-] {}: {:.0}% done.", self.label, 100.0 * fraction);
+        }
+    }
+
+    /// Clears the bar, if one was drawn.
+    pub fn finish(&mut self) {
+        if self.enabled && self.terminal {
+            eprint!("\r{:60}\r", "");
+        }
+    }
+}
+
+/// Exit status reported when giving up after --idle-timeout.
+const EXIT_IDLE_TIMEOUT: i32 = 2;
+
+/// Exit status reported when interrupted by Ctrl-C, as if killed by SIGINT.
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// File the candidates are written to when interrupted without --dump-candidates.
+const CHECKPOINT_FILE: &str = "pcg-breaker-checkpoint.bin";
+
+fn run(mut args: Opt, summary: &mut Summary) -> Result<i32> {
+    report!("{}", ASCII_HEADER);
+
+    report!("[-] Starting clock.");
+    let start_time = Instant::now();
+
+    // resolved once, so that every consumer of internal randomness (sources
+    // included) draws from a generator seeded with it
+    let seed = args.seed.unwrap_or_else(clock_seed);
+    args.seed = Some(seed);
+    report!("[-] Seeding internal randomness with 0x{:016X}.", seed);
+
+    report!(
+        "[-] Pruning enumerated candidates with the {} path.",
+        SimdPath::current().name()
+    );
+
+    let registry = SolverRegistry::with_builtins();
+
+    let solver = *registry.get(&args.solver).ok_or_else(|| {
+        Error::other(format!(
+            "no solver named `{}` (see `pcg-breaker solvers`)",
+            args.solver
+        ))
+    })?;
+
+    check_solver_flags(&args, &solver)?;
+
+    if let Some(budget) = args.memory_budget {
+        plan_memory(&mut args, &solver, budget)?;
+    }
+
+    if let Some(profile) = &args.profile {
+        report!(
+            "[-] Using profile `{}` from {}.",
+            profile,
+            args.config
+                .as_deref()
+                .unwrap_or_else(|| Path::new(CONFIG_FILE))
+                .display()
+        );
+    }
+
+    // with the generate-table feature, the table is generated in memory
+    // instead (see `load_table`)
+    if solver.needs_table
+        && args.table.is_none()
+        && args.tables.is_empty()
+        && args.seed_candidates.is_none()
+        && !cfg!(feature = "generate-table")
+    {
+        return Err(Error::other(format!(
+            "a table path is required by the `{}` solver without --seed-candidates",
+            solver.name
+        )));
+    }
+
+    let filter = CandidateFilter::from_args(&args)?;
+
+    // set up before anything else, so that a missing GPU fails the run early
+    #[cfg(feature = "gpu")]
+    let gpu = match args.gpu {
+        true => {
+            let gpu = GpuPruner::new()?;
+            report!(
+                "[-] Pruning at least {} enumerated candidates on {}.",
+                GPU_MIN_CANDIDATES,
+                gpu.device_name()
+            );
+            Some(Arc::new(gpu))
+        }
+        false => None,
+    };
+
+    if args.derived.is_some() && args.first_output.is_none() && args.seed_candidates.is_none() {
+        return Err(Error::other(
+            "derived observations need --first-output unless resuming with --seed-candidates",
+        ));
+    }
+
+    let reduction = take_reduction(&mut args.transform)?;
+
+    if reduction.is_some()
+        && (args.bits.is_some()
+            || args.float_scale.is_some()
+            || args.normal.is_some()
+            || args.discrete.is_some()
+            || args.consumption.is_some()
+            || args.derived.is_some()
+            || args.labeled
+            || args.resync > 0
+            || args.result.is_some())
+    {
+        return Err(Error::other(
+            "a final `mask` or `mod` transform only works with outputs otherwise observed as \
+             they are",
+        ));
+    }
+
+    // the default stride would conflict with every option otherwise
+    let partial = reduction.is_some()
+        || args.bits.is_some()
+        || args.float_scale.is_some()
+        || args.normal.is_some()
+        || args.discrete.is_some()
+        || args.consumption.is_some();
+
+    if args.max_stride.is_some() && args.stride > 1 {
+        return Err(Error::other(
+            "--max-stride infers the stride, so it can't be given with --stride",
+        ));
+    }
+
+    let strided = args.stride > 1 || args.max_stride.is_some();
+
+    if args.race && (partial || args.derived.is_some() || args.labeled || args.resync > 0) {
+        return Err(Error::other(
+            "--race only works with outputs observed as they are",
+        ));
+    }
+
+    if strided && (partial || args.derived.is_some() || args.labeled || args.resync > 0) {
+        return Err(Error::other(
+            "--stride only works with outputs observed as they are",
+        ));
+    }
+
+    if args.labeled {
+        return run_labeled(&args, &filter, seed, start_time, summary);
+    }
+
+    let (candidates, mut input, window) = if let Some(path) = &args.seed_candidates {
+        let (outputs, mut states) = load_candidates(path).inspect_err(|_| {
+            report!("[!] Failed to load candidate states!");
+        })?;
+
+        states.retain(|state| filter.matches(state, outputs));
+
+        if states.is_empty() {
+            return Err(Error::other("no candidate states to start from"));
+        }
+
+        report!(
+            "[+] Loaded {} candidate states after {} outputs.",
+            states.len(),
+            outputs
+        );
+
+        summary.end_phase("candidate load");
+
+        (
+            Candidates::Explicit(states),
+            Input::new(&args, outputs),
+            vec![],
+        )
+    } else {
+        // with --buffer, outputs are read ahead while the table is loading
+        let early_input = args.buffer.map(|_| Input::new(&args, 0));
+
+        // solvers which do without the table still use it if given one
+        let tables = match solver.needs_table || args.table.is_some() || !args.tables.is_empty() {
+            true => load_tables(&args, summary)?,
+            false => TableSet::default(),
+        };
+
+        let table = tables.get(args.stride);
+
+        report!(
+            "[-] Reading {} outputs to initialize the predictor.",
+            solver.outputs
+        );
+
+        let mut input = early_input.unwrap_or_else(|| Input::new(&args, 0));
+        let mut window: Vec<(usize, u32)> = Vec::with_capacity(solver.outputs);
+        let mut retries = 0;
+
+        // the options racing or inferring the stride are only accepted for
+        // solvers needing the table (see `check_solver_flags`)
+        let config = SolverConfig {
+            table,
+            inc: args.inc_equals.map(|inc| inc | 1),
+            // enumerated below, once narrowed down by the known constraints
+            threshold: 0,
+            threads: 0,
+            progress: None,
+            cancellation: CancellationToken::new(),
+        };
+
+        let candidates = match args.max_stride {
+            _ if args.race => Candidates::Implicit(race_interpretations(
+                &tables,
+                &mut input,
+                &args,
+                &mut window,
+            )?),
+            Some(max_stride) => {
+                Candidates::Implicit(infer_stride(&tables, &mut input, max_stride, &mut window)?)
+            }
+            None => loop {
+                while window.len() < solver.outputs {
+                    let (index, output) = input.next_output()?;
+
+                    // the outputs must be consecutive, so start over after a gap
+                    if window
+                        .last()
+                        .is_some_and(|&(last, _)| index != last + args.stride)
+                    {
+                        window.clear();
+                    }
+
+                    window.push((index, output));
+                }
+
+                let outputs: Vec<u32> = window.iter().map(|&(_, output)| output).collect();
+
+                let progress =
+                    Arc::new(Mutex::new(Progress::new("Initializing", !args.no_progress)));
+
+                let config = SolverConfig {
+                    progress: Some({
+                        let progress = Arc::clone(&progress);
+                        Arc::new(move |done, total| progress.lock().unwrap().update(done, total))
+                    }),
+                    ..config.clone()
+                };
+
+                let mut result = registry.start(solver.name, &config, &outputs);
+
+                progress.lock().unwrap().finish();
+
+                if input.detect_byte_order && result.is_err() {
+                    let swapped: Vec<u32> =
+                        outputs.iter().map(|output| output.swap_bytes()).collect();
+                    result = registry.start(solver.name, &config, &swapped);
+
+                    if result.is_ok() {
+                        report!("[+] Detected opposite-endian outputs, byteswapping them.");
+                        input.byteswap = true;
+                    }
+                }
+
+                input.detect_byte_order &= result.is_err();
+
+                let (first, last) = (window[0].0, window[window.len() - 1].0);
+
+                match result {
+                    Ok(started) => match started.candidates() {
+                        Some(_) => break started.into_candidates().unwrap(),
+                        None => {
+                            check_backend_flags(&args, reduction.is_some(), &solver)?;
+
+                            report!(
+                                "[+] Solver initialized on outputs #{} to #{} after {:.2} seconds.",
+                                display_index(first),
+                                display_index(last),
+                                start_time.elapsed().as_secs_f64(),
+                            );
+
+                            summary.end_phase("initialization");
+
+                            return follow_backend(
+                                started, &solver, input, &args, seed, start_time, summary,
+                            );
+                        }
+                    },
+                    // only inconsistent outputs are worth sliding the window
+                    Err(err) if err.kind() != ErrorKind::InvalidData => return Err(err),
+                    Err(err) if retries == args.init_retries => {
+                        if let Some(table) = config.table {
+                            diagnose_initialization(table, &input, &args);
+                        }
+
+                        return Err(Error::other(format!(
+                            "{}: outputs #{} to #{} are inconsistent{}",
+                            err,
+                            display_index(first),
+                            display_index(last),
+                            input.located_range(first, last)
+                        )));
+                    }
+                    Err(_) => {
+                        report!(
+                            "[!] Outputs #{} to #{} are inconsistent{}, sliding the window.",
+                            display_index(first),
+                            display_index(last),
+                            input.located_range(first, last)
+                        );
+
+                        retries += 1;
+                        window.remove(0);
+                    }
+                }
+            },
+        };
+
+        report!(
+            "[+] Predictor initialized on outputs #{} to #{} after {:.2} seconds.",
+            display_index(window[0].0),
+            display_index(window[window.len() - 1].0),
+            start_time.elapsed().as_secs_f64(),
+        );
+
+        let mut candidates = candidates;
+
+        if !args.no_recovery {
+            enumerate_candidates(&mut candidates, &filter, input.last_index)?;
+        }
+
+        summary.end_phase("initialization");
+        (candidates, input, window)
+    };
+
+    let mut outputs = input.last_index;
+
+    let snapshot = Arc::new(Mutex::new(ControlSnapshot::default()));
+
+    let control = args
+        .control
+        .as_deref()
+        .map(|path| ControlSocket::bind(path, Arc::clone(&snapshot)))
+        .transpose()?;
+
+    let console = Console {
+        label: None,
+        rng: Pcg32State::from_rand_pcg(seed, 0),
+        start_time,
+        outputs,
+        predictions: !args.no_predictions,
+        recovery: !args.no_recovery,
+        seed_search: args.seed_search,
+        rand_seed: args.rand_seed,
+        pcg_cpp: args.pcg_cpp,
+        unique: args.unique,
+        tokens: TokenFormat::from_args(&args),
+        bounded: args.bounded,
+        sampler: args
+            .sample
+            .clone()
+            .map(|sampler| (sampler, args.sample_count)),
+        progress: !args.no_progress,
+        oracle: args.oracle || args.oracle_command.is_some(),
+        entropy: args.entropy,
+        wide_scans: 0,
+        predictions_csv: args
+            .predictions_csv
+            .as_deref()
+            .map(PredictionLog::create)
+            .transpose()?,
+        control: control.as_ref().map(|_| Arc::clone(&snapshot)),
+    };
+
+    let threshold = if args.no_recovery { 0 } else { args.threshold };
+    let mut tracker = Tracker::new(candidates, threshold, console);
+
+    #[cfg(feature = "gpu")]
+    if let Some(gpu) = gpu {
+        tracker.set_gpu(gpu);
+    }
+
+    let mut resyncs = vec![];
+    let mut corrupted = vec![];
+
+    // the last outputs submitted to implicitly tracked candidates, with the
+    // candidates before each, as the predictor can let an inconsistent output
+    // through and only rule it out a few outputs later
+    let mut recent: VecDeque<(usize, u32, Candidates)> = VecDeque::new();
+
+    // the outputs consumed, only kept to record them with --result
+    let mut consumed = if args.result.is_some() {
+        window
+    } else {
+        vec![]
+    };
+
+    // outputs read ahead as a chunk, yet to be submitted one at a time
+    let mut pending = VecDeque::new();
+    let mut deferred = None;
+
+    let mut idle = false;
+
+    loop {
+        if args.then_predict && tracker.is_recovered() && !tracker.observer_mut().predictions {
+            report!("[+] Switching to predicting outputs as they keep coming.");
+
+            tracker.observer_mut().predictions = true;
+            tracker.report_prediction();
+        }
+
+        if ((args.no_predictions && !args.then_predict)
+            || args.oracle
+            || args.oracle_command.is_some())
+            && tracker.is_recovered()
+        {
+            break;
+        }
+
+        summary.outputs = outputs;
+        summary.candidates = Some(tracker.candidates().count());
+        input.rate.set_candidates(tracker.candidates().count());
+
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if let Some(Consumption { draws }) = &args.consumption {
+            let line = match input.next_line() {
+                Ok(line) => line,
+                Err(err) => {
+                    idle = err.kind() == ErrorKind::TimedOut;
+                    break;
+                }
+            };
+
+            let mut values = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|value| !value.is_empty());
+
+            let observed = draws.iter().filter(|draw| !matches!(draw, Draw::Skip));
+
+            if values.clone().count() != observed.count() {
+                return Err(Error::other(format!(
+                    "expected a value for each observed draw, not `{}`",
+                    line
+                )));
+            }
+
+            if tracker.observer_mut().predictions {
+                report!(
+                    "[-] Reading iteration from output #{} (with values {})",
+                    display_index(outputs + 1),
+                    line
+                );
+            }
+
+            for &draw in draws {
+                outputs += draw.outputs();
+                input.last_index = outputs;
+                tracker.observer_mut().outputs = outputs;
+
+                let value = match draw {
+                    Draw::Skip => "",
+                    _ => values.next().unwrap(),
+                };
+
+                if let Err(err) = draw.submit(&mut tracker, value) {
+                    if corrupted.len() == args.max_errors {
+                        return Err(err);
+                    }
+
+                    report!(
+                        "[!] Value {} drawn up to output #{} is inconsistent, treating it as unknown.",
+                        value,
+                        display_index(outputs)
+                    );
+
+                    corrupted.push(outputs);
+
+                    for _ in 0..draw.outputs() {
+                        tracker.skip_unknown_output()?;
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        if let Some(normal) = args.normal {
+            let sample = match input.next_sample() {
+                Ok(sample) => sample,
+                Err(err) => {
+                    idle = err.kind() == ErrorKind::TimedOut;
+                    break;
+                }
+            };
+
+            if let Err(err) = submit_normal(&mut tracker, &mut input, &mut outputs, sample, normal)
+            {
+                if corrupted.len() == args.max_errors {
+                    return Err(err);
+                }
+
+                report!(
+                    "[!] Sample #{} is inconsistent, treating it as unknown.",
+                    display_index(outputs)
+                );
+
+                corrupted.push(outputs);
+
+                // a sample is drawn from outputs which are all unknown
+                for _ in 0..2 {
+                    tracker.skip_unknown_output()?;
+                }
+            }
+
+            continue;
+        }
+
+        let chunking = args.chunk_size > 1
+            && !tracker.observer_mut().predictions
+            && !args.oracle
+            && args.oracle_command.is_none()
+            && args.derived.is_none()
+            && args.bits.is_none()
+            && args.float_scale.is_none()
+            && args.discrete.is_none()
+            && reduction.is_none()
+            && args.resync == 0;
+
+        if chunking && pending.is_empty() && deferred.is_none() {
+            if let Candidates::Explicit(_) = tracker.candidates() {
+                while pending.len() < args.chunk_size {
+                    match input.next_output() {
+                        Ok(observation) => pending.push_back(observation),
+                        Err(err) => {
+                            deferred = Some(err);
+                            break;
+                        }
+                    }
+                }
+
+                let consecutive = pending
+                    .iter()
+                    .enumerate()
+                    .all(|(offset, &(index, _))| index == outputs + offset + 1);
+
+                if consecutive && !pending.is_empty() {
+                    let chunk: Vec<u32> = pending.iter().map(|&(_, output)| output).collect();
+                    tracker.observer_mut().outputs = outputs + chunk.len();
+
+                    // on failure, the outputs are submitted one at a time
+                    // to find out which are inconsistent
+                    if tracker.submit_next_outputs(&chunk).is_ok() {
+                        if args.result.is_some() {
+                            consumed.extend(pending.drain(..));
+                        }
+
+                        outputs += chunk.len();
+                        pending.clear();
+                        continue;
+                    }
+
+                    tracker.observer_mut().outputs = outputs;
+                }
+            }
+        }
+
+        let observation = match pending.pop_front() {
+            Some(observation) => Ok(observation),
+            None => deferred.take().map_or_else(|| input.next_output(), Err),
+        };
+
+        let (index, mut output) = match observation {
+            Ok(observation) => observation,
+            Err(err) => {
+                if err.kind() == ErrorKind::InvalidData {
+                    report!("[!] Stopped reading outputs: {}.", err);
+                }
+
+                idle = err.kind() == ErrorKind::TimedOut;
+                break;
+            }
+        };
+
+        if args.bits.is_some() && index != outputs + 1 {
+            return Err(Error::other("leaked bits can't be given with an index"));
+        }
+
+        if args.float_scale.is_some() && index != outputs + 1 {
+            return Err(Error::other("scaled outputs can't be given with an index"));
+        }
+
+        if args.discrete.is_some() && index != outputs + 1 {
+            return Err(Error::other("samples can't be given with an index"));
+        }
+
+        // a predictor made for a stride is given every observation as is
+        let next = match tracker.candidates() {
+            Candidates::Implicit(predictor) => outputs + predictor.stride() as usize,
+            _ => outputs + 1,
+        };
+
+        // a few outputs missing before this one are branched on, while more
+        // of them need the candidates enumerated to jump past them
+        let mut missing = 0;
+
+        if index != next {
+            recent.clear();
+
+            let table = tracker.candidates().table();
+
+            if table.is_some_and(|table| table.stride() == 1)
+                && index - outputs - 1 <= MAX_BRANCHED_OUTPUTS
+            {
+                missing = index - outputs - 1;
+            } else {
+                tracker.skip_outputs(index - outputs - 1).map_err(|err| {
+                    Error::other(format!(
+                        "can't skip to output #{}: {} (only up to {} missing outputs are \
+                         skipped before then; give more consecutive outputs first, or raise \
+                         --threshold)",
+                        display_index(index),
+                        err,
+                        MAX_BRANCHED_OUTPUTS
+                    ))
+                })?;
+            }
+        }
+
+        let mut index = index;
+
+        if let Some(state) = tracker.candidates().recovered_state() {
+            let skipped = (0..=args.resync).find(|&k| state.jump(k as u64).next_output() == output);
+
+            if let Some(skipped @ 1..) = skipped {
+                index += skipped;
+
+                report!(
+                    "[!] Detected {} unobserved outputs before output #{}, resynchronizing.",
+                    skipped,
+                    display_index(index)
+                );
+
+                tracker.skip_outputs(skipped)?;
+                input.last_index = index;
+                resyncs.push((index, skipped));
+                recent.clear();
+            }
+        }
+
+        outputs = index;
+        tracker.observer_mut().outputs = outputs;
+
+        if tracker.observer_mut().predictions
+            && args.bits.is_none()
+            && args.float_scale.is_none()
+            && args.discrete.is_none()
+            && reduction.is_none()
+        {
+            report!(
+                "[-] Reading output #{} (with value 0x{:08X})",
+                display_index(outputs),
+                output
+            );
+        }
+
+        // observations which aren't outputs can't be checked against them
+        if args.bits.is_none()
+            && args.float_scale.is_none()
+            && args.discrete.is_none()
+            && reduction.is_none()
+            && (args.derived.is_none() || args.first_output.is_some())
+        {
+            tracker.observer_mut().observe(outputs, output);
+
+            if args.result.is_some() {
+                consumed.push((outputs, output));
+            }
+        }
+
+        let plain = args.derived.is_none()
+            && args.bits.is_none()
+            && args.float_scale.is_none()
+            && args.discrete.is_none()
+            && reduction.is_none();
+
+        let before = match tracker.candidates() {
+            Candidates::Explicit(_) => None,
+            candidates => (plain && missing == 0 && corrupted.len() < args.max_errors)
+                .then(|| candidates.clone()),
+        };
+
+        // single values may be submitted again below, read the other way
+        let observed = outputs;
+
+        let submit = |tracker: &mut Tracker<Console>, output: u32| {
+            match (args.derived, args.float_scale, reduction) {
+                // without a known first output, observations are checked as is
+                (Some(derivation), _, _) if args.first_output.is_none() => match missing {
+                    0 => tracker.submit_next_derived(output, derivation),
+                    _ => tracker.submit_next_where(missing + 1, |previous, next| {
+                        derivation.derive(previous, next) == output
+                    }),
+                },
+                (_, Some(range), _) => submit_scaled(tracker, observed, output, range),
+                (_, _, Some(model)) => submit_reduced(tracker, observed, output, model, missing),
+                _ => match missing {
+                    0 => tracker.submit_next_output(output),
+                    _ => tracker.submit_next_where(missing + 1, |_, next| next == output),
+                },
+            }
+        };
+
+        let mut result = match (args.bits, args.discrete) {
+            (Some(leak), _) => submit_bit(&mut tracker, &mut input, &mut outputs, output, leak),
+            (_, Some(distribution)) => submit_discrete(
+                &mut tracker,
+                &mut input,
+                &mut outputs,
+                output,
+                distribution,
+                args.sample_outputs,
+            ),
+            _ => submit(&mut tracker, output),
+        };
+
+        // decimal digits may have been meant as hexadecimal, or the other way
+        if let (Err(_), None, None) = (&result, args.bits, args.discrete) {
+            if let Some(alternative) = input.alternative(outputs) {
+                if submit(&mut tracker, alternative).is_ok() {
+                    report!(
+                        "[!] Output #{}{} is only consistent read as 0x{:08X} rather than \
+                         0x{:08X}, reading it that way.",
+                        display_index(outputs),
+                        input.located(outputs),
+                        alternative,
+                        output
+                    );
+
+                    output = alternative;
+                    result = Ok(());
+
+                    if let Some(last) = consumed
+                        .last_mut()
+                        .filter(|&&mut (index, _)| index == outputs)
+                    {
+                        last.1 = output;
+                    }
+                }
+            }
+        }
+
+        let stride = before
+            .as_ref()
+            .and_then(Candidates::table)
+            .map_or(1, |table| table.stride() as usize);
+
+        if recent
+            .back()
+            .is_some_and(|&(index, _, _)| index + stride != outputs)
+        {
+            recent.clear();
+        }
+
+        let err = match result {
+            Ok(()) => {
+                match before {
+                    Some(candidates) => recent.push_back((outputs, output, candidates)),
+                    None => recent.clear(),
+                }
+
+                if recent.len() > RECHECKED_OUTPUTS {
+                    recent.pop_front();
+                }
+
+                continue;
+            }
+            Err(err) => err,
+        };
+
+        // one of the last outputs may have been the inconsistent one, if it
+        // was let through by candidates the predictor hadn't ruled out yet,
+        // in which case the outputs since then are consistent without it
+        let retried = (0..recent.len()).rev().find_map(|start| {
+            let (index, _, candidates) = &recent[start];
+            let mut candidates = candidates.clone();
+
+            candidates.skip_unknown_output().ok()?;
+
+            for &(_, output, _) in recent.range(start + 1..) {
+                candidates.submit_next_output(output).ok()?;
+            }
+
+            candidates.submit_next_output(output).ok()?;
+            Some((*index, candidates))
+        });
+
+        recent.clear();
+
+        if let Some((index, candidates)) = retried {
+            report!(
+                "[!] Output #{} is inconsistent{}, treating it as unknown.",
+                display_index(index),
+                input.located(index)
+            );
+
+            corrupted.push(index);
+            consumed.retain(|&(consumed, _)| consumed != index);
+            tracker.set_candidates(candidates);
+            continue;
+        }
+
+        let err = Error::new(
+            err.kind(),
+            format!(
+                "{}: output #{} is inconsistent{}",
+                err,
+                display_index(outputs),
+                input.located(outputs)
+            ),
+        );
+
+        if corrupted.len() == args.max_errors {
+            return Err(err);
+        }
+
+        report!(
+            "[!] Output #{} is inconsistent{}, treating it as unknown.",
+            display_index(outputs),
+            input.located(outputs)
+        );
+
+        corrupted.push(outputs);
+
+        if consumed.last().is_some_and(|&(index, _)| index == outputs) {
+            consumed.pop();
+        }
+
+        // a bit or sample is drawn from outputs which are all unknown, as
+        // are any missing before an output
+        let drawn = match (args.bits, args.discrete) {
+            (Some(leak), _) => leak.outputs(),
+            (_, Some(_)) => args.sample_outputs,
+            _ => missing + 1,
+        };
+
+        for _ in 0..drawn {
+            tracker.skip_unknown_output()?;
+        }
+    }
+
+    if idle {
+        report!(
+            "[!] No output received for {} seconds, giving up with {} candidate states left.",
+            args.idle_timeout.unwrap_or_default(),
+            tracker.candidates().count()
+        );
+
+        tracker.report_prediction();
+    }
+
+    summary.outputs = outputs;
+    summary.candidates = Some(tracker.candidates().count());
+    summary.wide_scans = match tracker.candidates().table() {
+        Some(table) => table.wide_scans(),
+        None => tracker.observer_mut().wide_scans,
+    };
+    summary.recovered = tracker
+        .candidates()
+        .recovered_state()
+        .map(|state| state.jump(1u64.wrapping_sub(outputs as u64)));
+    summary.input_rate = Some(input.rate.statistics());
+    summary.end_phase("tracking");
+
+    let interrupted = INTERRUPTED.load(Ordering::SeqCst);
+
+    if interrupted {
+        report!(
+            "\n[!] Interrupted after {} outputs with {} candidate states left.",
+            outputs,
+            tracker.candidates().count()
+        );
+
+        tracker.report_prediction();
+    }
+
+    let candidates = tracker.candidates();
+
+    if !args.no_recovery && !tracker.is_recovered() {
+        report!("[-] Not enough outputs available to complete state recovery.");
+    }
+
+    if !resyncs.is_empty() {
+        report!("[+] Resynchronized {} times:", resyncs.len());
+
+        for (index, skipped) in resyncs {
+            report!(
+                "    skipped {} outputs before output #{}",
+                skipped,
+                display_index(index)
+            );
+        }
+    }
+
+    if !corrupted.is_empty() {
+        report!(
+            "[!] Treated {} outputs as corrupted: {}",
+            corrupted.len(),
+            corrupted
+                .iter()
+                .map(|&index| format!("#{}", display_index(index)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    if let Some(path) = &args.scan_memory {
+        if let Candidates::Explicit(states) = &candidates {
+            scan_memory(path, args.struct_layout, args.scan_align, states, outputs)?;
+        } else {
+            report!("[!] Not enough outputs available to scan memory for candidates.");
+        }
+    }
+
+    if let Some(path) = &args.gap_capture {
+        match candidates.recovered_state() {
+            Some(state) => locate_capture(path, state, outputs)?,
+            None => report!("[!] Not enough outputs available to locate the later capture."),
+        }
+    }
+
+    let checkpoint = match &candidates {
+        // the implicit candidates would take gigabytes to write out
+        Candidates::Explicit(_) if interrupted => Some(PathBuf::from(CHECKPOINT_FILE)),
+        _ => None,
+    };
+
+    if let Some(path) = args.dump_candidates.as_ref().or(checkpoint.as_ref()) {
+        dump_candidates(path, outputs, &candidates.to_vec())?;
+
+        report!(
+            "[+] Wrote {} candidate states to {}.",
+            candidates.count(),
+            path.display()
+        );
+
+        if interrupted {
+            report!(
+                "[-] Resume with --seed-candidates {}, from output #{}.",
+                path.display(),
+                display_index(outputs + 1)
+            );
+        }
+    } else if interrupted {
+        report!("[!] Too many candidate states left to write a checkpoint.");
+    }
+
+    if let Some(path) = &args.result {
+        match summary.recovered {
+            Some(state) => {
+                write_result(path, state, &consumed)?;
+                report!("[+] Wrote the result to {}.", path.display());
+            }
+            None => report!("[!] No state recovered, so no result written."),
+        }
+    }
+
+    summary.end_phase("reporting");
+
+    Ok(if interrupted {
+        EXIT_INTERRUPTED
+    } else if idle {
+        EXIT_IDLE_TIMEOUT
+    } else {
+        0
+    })
+}
+
+/// Parses a line of the form `label value` read in --labeled mode.
+fn parse_labeled_output(line: &str) -> Result<(&str, u32)> {
+    let mut words = line.split_whitespace();
+
+    match (words.next(), words.next(), words.next()) {
+        (Some(label), Some(value), None) => Ok((label, parse_output(value)?)),
+        _ => Err(Error::other(format!(
+            "expected `label value`, got `{}`",
+            line.trim()
+        ))),
+    }
+}
+
+/// Rejects the options a solver can't honour, rather than running without
+/// them: those racing interpretations or strides, tracking several labeled
+/// generators or resuming from saved candidates all build on the predictor
+/// of the table, so only solvers needing the table accept them.
+fn check_solver_flags(args: &Opt, solver: &SolverEntry) -> Result<()> {
+    if solver.needs_table {
+        return Ok(());
+    }
+
+    let unsupported = [
+        ("--race", args.race),
+        ("--max-stride", args.max_stride.is_some()),
+        ("--stride", args.stride > 1),
+        ("--labeled", args.labeled),
+        ("--seed-candidates", args.seed_candidates.is_some()),
+    ];
+
+    match unsupported.iter().find(|(_, given)| *given) {
+        Some((flag, _)) => Err(Error::other(format!(
+            "{} can't be used with the `{}` solver",
+            flag, solver.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Rejects the options needing candidate states, for solvers which don't
+/// track them and are only followed through `SolverBackend` (see
+/// `follow_backend`): those observing anything but outputs as they are,
+/// filtering or saving candidates, or recovering from inconsistent outputs.
+fn check_backend_flags(args: &Opt, reduction: bool, solver: &SolverEntry) -> Result<()> {
+    let unsupported = [
+        ("--derived", args.derived.is_some()),
+        ("--bits", args.bits.is_some()),
+        ("--float-scale", args.float_scale.is_some()),
+        ("--normal", args.normal.is_some()),
+        ("--discrete", args.discrete.is_some()),
+        ("--consumption", args.consumption.is_some()),
+        ("a final `mask` or `mod` transform", reduction),
+        ("--max-errors", args.max_errors > 0),
+        ("--resync", args.resync > 0),
+        ("--inc-in-file", args.inc_in_file.is_some()),
+        ("--inc-low-entropy", args.inc_low_entropy),
+        ("--state-mask", args.state_mask != 0),
+        ("--dump-candidates", args.dump_candidates.is_some()),
+        ("--result", args.result.is_some()),
+        ("--scan-memory", args.scan_memory.is_some()),
+        ("--gap-capture", args.gap_capture.is_some()),
+        ("--control", args.control.is_some()),
+        ("--oracle", args.oracle || args.oracle_command.is_some()),
+        ("--bounded", args.bounded.is_some()),
+        ("--entropy", args.entropy),
+    ];
+
+    match unsupported.iter().find(|(_, given)| *given) {
+        Some((flag, _)) => Err(Error::other(format!(
+            "{} needs candidate states, which the `{}` solver doesn't track",
+            flag, solver.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Follows the generator with a solver which doesn't track candidate states,
+/// through `SolverBackend` alone: each output is predicted, then submitted
+/// as it is read, and the state is reported once the solver recovers it.
+fn follow_backend(
+    mut backend: Box<dyn SolverBackend>,
+    solver: &SolverEntry,
+    mut input: Input,
+    args: &Opt,
+    seed: u64,
+    start_time: Instant,
+    summary: &mut Summary,
+) -> Result<i32> {
+    let mut outputs = input.last_index;
+
+    let mut console = Console {
+        label: None,
+        rng: Pcg32State::from_rand_pcg(seed, 0),
+        start_time,
+        outputs,
+        predictions: !args.no_predictions,
+        recovery: !args.no_recovery,
+        seed_search: args.seed_search,
+        rand_seed: args.rand_seed,
+        pcg_cpp: args.pcg_cpp,
+        unique: args.unique,
+        tokens: TokenFormat::from_args(args),
+        bounded: None,
+        sampler: args
+            .sample
+            .clone()
+            .map(|sampler| (sampler, args.sample_count)),
+        progress: !args.no_progress,
+        oracle: false,
+        entropy: false,
+        wide_scans: 0,
+        predictions_csv: args
+            .predictions_csv
+            .as_deref()
+            .map(PredictionLog::create)
+            .transpose()?,
+        control: None,
+    };
+
+    let mut recovered = None;
+    let mut idle = false;
+
+    loop {
+        summary.outputs = outputs;
+        summary.candidates = Some(backend.candidate_count());
+        input.rate.set_candidates(backend.candidate_count());
+
+        if recovered.is_none() {
+            recovered = backend.recovered_state();
+
+            if let Some(state) = &recovered {
+                console.on_recovered(state);
+
+                if args.then_predict && !console.predictions {
+                    report!("[+] Switching to predicting outputs as they keep coming.");
+                    console.predictions = true;
+                }
+
+                if !console.predictions {
+                    break;
+                }
+            }
+        }
+
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            break;
+        }
+
+        console.predict(outputs + 1, &backend.predict(), None);
+
+        let (index, output) = match input.next_output() {
+            Ok(observation) => observation,
+            Err(err) => {
+                if err.kind() == ErrorKind::InvalidData {
+                    report!("[!] Stopped reading outputs: {}.", err);
+                }
+
+                idle = err.kind() == ErrorKind::TimedOut;
+                break;
+            }
+        };
+
+        if index != outputs + 1 {
+            return Err(Error::other(format!(
+                "the `{}` solver can't skip to output #{}, it only follows consecutive outputs",
+                solver.name,
+                display_index(index)
+            )));
+        }
+
+        if console.predictions {
+            report!(
+                "[-] Reading output #{} (with value 0x{:08X})",
+                display_index(index),
+                output
+            );
+        }
+
+        console.outputs = index;
+        console.observe(index, output);
+
+        backend.submit(output).map_err(|err| {
+            Error::other(format!(
+                "{}: output #{} is inconsistent{}",
+                err,
+                display_index(index),
+                input.located(index)
+            ))
+        })?;
+
+        outputs = index;
+    }
+
+    if idle {
+        report!(
+            "[!] No output received for {} seconds, giving up with {} candidate states left.",
+            args.idle_timeout.unwrap_or_default(),
+            backend.candidate_count()
+        );
+    }
+
+    summary.outputs = outputs;
+    summary.candidates = Some(backend.candidate_count());
+    summary.recovered = backend
+        .recovered_state()
+        .map(|state| state.jump(1u64.wrapping_sub(outputs as u64)));
+    summary.input_rate = Some(input.rate.statistics());
+    summary.end_phase("tracking");
+
+    let interrupted = INTERRUPTED.load(Ordering::SeqCst);
+
+    if interrupted {
+        report!(
+            "\n[!] Interrupted after {} outputs with {} candidate states left.",
+            outputs,
+            backend.candidate_count()
+        );
+    }
+
+    if !args.no_recovery && summary.recovered.is_none() {
+        report!("[-] Not enough outputs available to complete state recovery.");
+    }
+
+    summary.end_phase("reporting");
+
+    Ok(if interrupted {
+        EXIT_INTERRUPTED
+    } else if idle {
+        EXIT_IDLE_TIMEOUT
+    } else {
+        0
+    })
+}
+
+/// The tables of a run, each for the stride it was made for.
+#[derive(Clone, Default)]
+struct TableSet {
+    tables: Vec<Arc<LookupTable>>,
+}
+
+impl TableSet {
+    /// Returns the table for a stride, if there is one.
+    fn get(&self, stride: usize) -> Option<Arc<LookupTable>> {
+        (self.tables.iter())
+            .find(|table| table.stride() == stride as u64)
+            .cloned()
+    }
+
+    /// Returns the table for a stride, generating it if there is none.
+    fn get_or_generate(&self, stride: usize) -> Arc<LookupTable> {
+        self.get(stride).unwrap_or_else(|| {
+            report!("[-] Generating the table for a stride of {}.", stride);
+            Arc::new(LookupTable::generate(stride as u64))
+        })
+    }
+}
+
+/// Loads the precomputed tables given, once for all the predictors of the
+/// run, or generates the one for the stride of the observations in memory if
+/// no path to one was given. Tables have no header, so each is identified
+/// among the strides the run needs by the sample entries it holds.
+fn load_tables(args: &Opt, summary: &mut Summary) -> Result<TableSet> {
+    let paths: Vec<&PathBuf> = args.table.iter().chain(&args.tables).collect();
+    let single = paths.len() == 1;
+
+    if paths.is_empty() {
+        report!(
+            "[-] Generating the table in memory, as no path to one was given (1GiB, which \
+             takes a few seconds)."
+        );
+
+        let table = LookupTable::generate(args.stride as u64);
+
+        report!("[+] Generated the table.");
+        summary.end_phase("table load");
+
+        return Ok(TableSet {
+            tables: vec![Arc::new(table)],
+        });
+    }
+
+    let strides = match args.max_stride {
+        Some(max_stride) => 1..=max_stride as u64,
+        None => args.stride as u64..=args.stride as u64,
+    };
+
+    let mut set = TableSet::default();
+
+    for path in paths {
+        let mut progress = Progress::new("Loading table", !args.no_progress);
+
+        let options = TableOptions {
+            prefault: args.prefault,
+            hugepages: args.hugepages,
+            mmap: args.mmap,
+            skip_checks: true,
+            ..TableOptions::default()
+        };
+
+        let mut table = LookupTable::open_with_options(path, options, |done, total| {
+            progress.update(done, total)
+        })
+        .inspect_err(|_| {
+            report!("[!] Failed to load precomputed table!");
+        })?;
+
+        progress.finish();
+
+        let Some(stride) = table.identify_stride(strides.clone()) else {
+            let wanted = match args.max_stride {
+                Some(max_stride) => format!("any stride up to {}", max_stride),
+                None => format!("a stride of {}", args.stride),
+            };
+
+            if !single {
+                report!(
+                    "[!] Ignoring {}, which wasn't made for {}.",
+                    path.display(),
+                    wanted
+                );
+
+                continue;
+            }
+
+            report!("[!] Failed to load precomputed table!");
+
+            return Err(Error::other(format!(
+                "{} lacks the sample entries of {}, is it really a table made by gen-table \
+                 (with the right --stride)?",
+                path.display(),
+                wanted
+            )));
+        };
+
+        let stride = stride as usize;
+
+        if set.get(stride).is_some() {
+            report!(
+                "[!] Ignoring {}, another table for a stride of {} was given before it.",
+                path.display(),
+                stride
+            );
+
+            continue;
+        }
+
+        match stride {
+            _ if single => report!("[+] Loaded precomputed table."),
+            1 => report!(
+                "[+] Loaded precomputed table {} for consecutive outputs.",
+                path.display()
+            ),
+            _ => report!(
+                "[+] Loaded precomputed table {} for a stride of {}.",
+                path.display(),
+                stride
+            ),
+        }
+
+        if stride == args.stride {
+            summary.table = Some(path.clone());
+        }
+
+        set.tables.push(Arc::new(table));
+    }
+
+    if set.get(args.stride).is_none() {
+        match cfg!(feature = "generate-table") {
+            true => set.tables.push(set.get_or_generate(args.stride)),
+            false => {
+                return Err(Error::other(format!(
+                    "none of the tables given was made for a stride of {} (see gen-table \
+                     --stride)",
+                    args.stride
+                )))
+            }
+        }
+    }
+
+    summary.end_phase("table load");
+
+    Ok(set)
+}
+
+/// Loads the table for the stride of the observations, like `load_tables`.
+fn load_table(args: &Opt, summary: &mut Summary) -> Result<Arc<LookupTable>> {
+    let tables = load_tables(args, summary)?;
+    Ok(tables
+        .get(args.stride)
+        .expect("the table set has the table"))
+}
+
+/// Prints statistics about a table, checking it without trusting it.
+fn display_table_info(path: &Path, stride: u64) -> Result<()> {
+    let options = TableOptions {
+        skip_checks: true,
+        stride,
+        ..TableOptions::default()
+    };
+
+    let mut progress = Progress::new("Loading table", true);
+    let table =
+        LookupTable::open_with_options(path, options, |done, total| progress.update(done, total))?;
+    progress.finish();
+
+    let stats = table.statistics();
+
+    report!("[+] Table {}:", path.display());
+    report!("    format:          raw sorted entries, without a header");
+    report!(
+        "    size:            {} bytes",
+        stats.entries * std::mem::size_of::<u64>()
+    );
+    report!("    entries:         {}", stats.entries);
+    report!(
+        "    multiplier:      0x{:016X}",
+        JumpTable::shared().constants(table.stride()).0 .0
+    );
+    report!(
+        "    sample entries:  {}",
+        match table.verify() {
+            Ok(()) => "all present".to_owned(),
+            Err(err) => format!("MISMATCH ({})", err),
+        }
+    );
+    report!(
+        "    buckets:         {} estimated positions ({:.1}% of entries), up to {} entries each",
+        stats.buckets,
+        100.0 * stats.buckets as f64 / stats.entries as f64,
+        stats.max_bucket
+    );
+    report!(
+        "    offsets:         {} to {} entries from the estimated position",
+        stats.min_offset,
+        stats.max_offset
+    );
+    report!(
+        "    scan length:     {:.2} entries on average, {} in the worst case",
+        stats.mean_scan,
+        stats.max_scan
+    );
+    report!("    outside window:  {} entries", stats.missed);
+
+    Ok(())
+}
+
+/// Prints generators drawn to be the slowest to initialize a predictor for,
+/// optionally writing their outputs to files to benchmark initialization on.
+fn write_worst_cases(
+    count: usize,
+    seed: u64,
+    output_dir: Option<&Path>,
+    outputs: usize,
+) -> Result<()> {
+    let mut rng = Pcg32State::from_rand_pcg(seed, 0);
+
+    if let Some(dir) = output_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    for index in 0..count {
+        let state = worst_case_state(&mut rng);
+
+        report!("0x{:016X} 0x{:016X}", state.state, state.inc);
+
+        if let Some(dir) = output_dir {
+            let mut generator = state;
+            let text: String = (0..outputs)
+                .map(|_| format!("0x{:08X}\n", generator.next_u32()))
+                .collect();
+
+            write(dir.join(format!("worst-{}.txt", index)), text)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns how much memory is resident for the process in bytes, where the
+/// system reports it.
+fn resident_memory() -> Option<u64> {
+    let status = read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+
+    Some(line.split_whitespace().nth(1)?.parse::<u64>().ok()? * 1024)
+}
+
+/// Runs a capture against the table set up in each way available, and
+/// prints how long loading the table, initializing the predictor and
+/// tracking each output took with it, and how much memory the table took up.
+fn compare_backends(path: &Path, capture: &Path, binary: bool, threshold: usize) -> Result<()> {
+    let outputs = read_capture(capture, binary)?;
+
+    if outputs.len() < 4 {
+        return Err(Error::other("need at least 4 outputs to compare backends"));
+    }
+
+    // each backend along with how to run the program with it
+    let backends = [
+        ("file", "by default", Some(TableOptions::default())),
+        (
+            "file, prefaulted",
+            "with --prefault",
+            Some(TableOptions {
+                prefault: true,
+                ..TableOptions::default()
+            }),
+        ),
+        (
+            "file, huge pages",
+            "with --hugepages",
+            Some(TableOptions {
+                hugepages: true,
+                ..TableOptions::default()
+            }),
+        ),
+        (
+            "file, mapped",
+            "with --mmap",
+            Some(TableOptions {
+                mmap: true,
+                ..TableOptions::default()
+            }),
+        ),
+        (
+            "generated",
+            "with strides past the first of --max-stride",
+            None,
+        ),
+    ];
+
+    report!(
+        "[-] Running {} outputs against {} table backends.",
+        outputs.len(),
+        backends.len()
+    );
+
+    let mut rows = vec![];
+
+    for (name, usage, options) in backends {
+        let baseline = resident_memory();
+        let start_time = Instant::now();
+
+        let table = Arc::new(match options {
+            Some(options) => LookupTable::open_with_options(path, options, |_, _| {})?,
+            None => LookupTable::generate(1),
+        });
+
+        let load = start_time.elapsed();
+        let memory = resident_memory()
+            .zip(baseline)
+            .map(|(after, before)| after.saturating_sub(before));
+
+        let start_time = Instant::now();
+        let initial = [outputs[0], outputs[1], outputs[2], outputs[3]];
+
+        let predictor = Predictor::try_new(table, initial)
+            .map_err(|_| Error::other("the first four outputs of the capture aren't consistent"))?;
+
+        let init = start_time.elapsed();
+
+        let mut tracker = Tracker::new(Candidates::Implicit(predictor), threshold, ());
+        let start_time = Instant::now();
+
+        for &output in &outputs[4..] {
+            if tracker.submit_next_output(output).is_err() {
+                tracker.skip_unknown_output()?;
+            }
+        }
+
+        let per_output = start_time.elapsed() / (outputs.len() - 4).max(1) as u32;
+
+        report!("[-] Ran the capture with the {} backend.", name);
+        rows.push((name, usage, load, init, per_output, memory));
+    }
+
+    report!(
+        "\n    {:<18}{:>12}{:>12}{:>16}{:>12}",
+        "backend",
+        "load",
+        "init",
+        "per output",
+        "memory"
+    );
+
+    for &(name, _, load, init, per_output, memory) in &rows {
+        report!(
+            "    {:<18}{:>10.2} s{:>10.4} s{:>13.2} us{:>12}",
+            name,
+            load.as_secs_f64(),
+            init.as_secs_f64(),
+            per_output.as_secs_f64() * 1e6,
+            memory.map_or("unknown".to_owned(), |bytes| format!("{} MiB", bytes >> 20))
+        );
+    }
+
+    report!();
+
+    let total = |&(_, _, load, init, per_output, _): &(_, _, Duration, Duration, Duration, _)| {
+        load + init + per_output * (outputs.len() - 4) as u32
+    };
+
+    let fastest = rows.iter().min_by_key(|row| total(row)).unwrap();
+
+    report!(
+        "[+] The {} backend runs this workload fastest, in {:.2} seconds (used {}).",
+        fastest.0,
+        total(fastest).as_secs_f64(),
+        fastest.1
+    );
+
+    Ok(())
+}
+
+fn describe_model(model: ObservationModel) -> String {
+    match model {
+        ObservationModel::Outputs => "outputs as they are".to_owned(),
+        ObservationModel::Derived(Derivation::Xor) => {
+            "outputs XORed with the one before them".to_owned()
+        }
+        ObservationModel::Derived(Derivation::Difference) => {
+            "outputs minus the one before them".to_owned()
+        }
+        ObservationModel::Bits(BitLeak::Parity) => "the lowest bit of outputs".to_owned(),
+        ObservationModel::Bits(BitLeak::TopBit) => "the highest bit of outputs".to_owned(),
+        ObservationModel::Bits(BitLeak::Bool) => "gen_bool(0.5) results".to_owned(),
+        ObservationModel::Scaled(range) => format!("outputs scaled to 0..{} as floats", range),
+        ObservationModel::LowBits(bits) => format!("the lowest {} bits of outputs", bits),
+        ObservationModel::Modulo(modulus) => format!("outputs modulo {}", modulus),
+    }
+}
+
+/// Returns how many bits about the generator an observation leaks at most.
+fn leaked_bits(model: ObservationModel) -> f64 {
+    match model {
+        ObservationModel::Outputs | ObservationModel::Derived(_) => 32.0,
+        ObservationModel::Bits(_) => 1.0,
+        ObservationModel::Scaled(range) => (range as f64).log2().min(32.0),
+        ObservationModel::LowBits(bits) => bits as f64,
+        ObservationModel::Modulo(modulus) => (modulus as f64).log2(),
+    }
+}
+
+/// Describes a size in bytes in MiB, or in GiB from 10 GiB on.
+fn describe_bytes(bytes: u64) -> String {
+    match bytes >> 30 {
+        0..=9 => format!("{} MiB", bytes >> 20),
+        _ => format!("{:.1} GiB", bytes as f64 / (1u64 << 30) as f64),
+    }
+}
+
+/// Describes a duration of up to many years in the most fitting unit.
+fn describe_seconds(seconds: f64) -> String {
+    const UNITS: [(&str, f64); 4] = [
+        ("minutes", 60.0),
+        ("hours", 3600.0),
+        ("days", 86400.0),
+        ("years", 31557600.0),
+    ];
+
+    (UNITS.iter().rev())
+        .find(|&&(_, unit)| seconds >= unit)
+        .map(|&(name, unit)| format!("{:.1} {}", seconds / unit, name))
+        .unwrap_or_else(|| format!("{:.2} seconds", seconds))
+}
+
+/// Attacks random generators (the same ones for the same seed) observed
+/// through a model after the four outputs initializing the predictor, and
+/// reports how many observations predicting their next output and
+/// recovering their state took, and how long on this machine, next to how
+/// many they take in theory.
+///
+/// Recovery usually takes far more observations than can be made here, so
+/// unless it happens it is extrapolated from the candidates left, whose
+/// number is roughly inversely proportional to the number of outputs
+/// observed (as with --oracle).
+fn estimate_cost(
+    path: &Path,
+    model: ObservationModel,
+    trials: usize,
+    max_observations: usize,
+    seed: u64,
+) -> Result<()> {
+    if trials == 0 || max_observations == 0 {
+        return Err(Error::other(
+            "estimating needs at least one trial and one observation",
+        ));
+    }
+
+    let table = Arc::new(LookupTable::open(path)?);
+    let mut rng = Pcg32State::from_rand_pcg(seed, 0);
+
+    report!(
+        "[-] Attacking {} generators observed as {}, for up to {} observations each.",
+        trials,
+        describe_model(model),
+        max_observations
+    );
+
+    let (mut inits, mut rates) = (vec![], vec![]);
+    let (mut predictions, mut recoveries, mut recovered) = (vec![], vec![], 0);
+
+    for trial in 1..=trials {
+        let mut next_u64 = || ((rng.next_u32() as u64) << 32) | rng.next_u32() as u64;
+        let mut target = Pcg32State::new(next_u64(), next_u64());
+        let generator = format!("0x{:016X} 0x{:016X}", target.state, target.inc);
+        let initial: Vec<u32> = (0..4).map(|_| target.next_u32()).collect();
+
+        let start_time = Instant::now();
+
+        let mut breaker = BreakerBuilder::new(Arc::clone(&table))
+            .observation_model(model)
+            .build(&initial)
+            .map_err(Error::other)?;
+
+        let init = start_time.elapsed().as_secs_f64();
+        let (mut last, mut prediction, mut observations) = (initial[3], None, 0);
+
+        loop {
+            if prediction.is_none() {
+                if let Prediction::Unique(_) = breaker.predict_future_output() {
+                    prediction = Some((observations as f64, start_time.elapsed().as_secs_f64()));
+                }
+            }
+
+            if breaker.recovered_state().is_some() || observations == max_observations {
+                break;
+            }
+
+            let (mut before, mut output) = (last, last);
+
+            for _ in 0..model.outputs() {
+                before = output;
+                output = target.next_u32();
+            }
+
+            breaker.submit(model.observe(before, output))?;
+            last = output;
+            observations += 1;
+        }
+
+        let rate = (start_time.elapsed().as_secs_f64() - init) / observations.max(1) as f64;
+        let count = breaker.tracker().candidates().count();
+
+        // extrapolate down to a single candidate, unless already there
+        let recovery = observations as f64 * count as f64;
+
+        report!(
+            "[-] Generator #{} ({}): {}, {}.",
+            trial,
+            generator,
+            match prediction {
+                Some((observations, seconds)) => format!(
+                    "predicted after {} observations in {}",
+                    observations,
+                    describe_seconds(seconds)
+                ),
+                None => "never predicted".to_owned(),
+            },
+            if count == 1 {
+                format!(
+                    "recovered after {} observations in {}",
+                    observations,
+                    describe_seconds(init + recovery * rate)
+                )
+            } else {
+                format!(
+                    "{} candidates left after {} observations",
+                    count, observations
+                )
+            }
+        );
+
+        recovered += (count == 1) as usize;
+        predictions.extend(prediction);
+        recoveries.push((recovery, init + recovery * rate));
+        inits.push(init);
+        rates.push(rate);
+    }
+
+    let median = |mut values: Vec<f64>| {
+        values.sort_by(|a, b| a.total_cmp(b));
+        values[values.len() / 2]
+    };
+
+    // four outputs initialize the predictor before any observation
+    let outputs = |observations: f64| 4.0 + observations * model.outputs() as f64;
+    let (init, rate) = (median(inits), median(rates));
+
+    let bits = leaked_bits(model);
+
+    if bits > 0.0 {
+        // a single bit tells apart the two outputs predicted after
+        // initialization, while the up to 2^27 candidates left take about
+        // as many outputs observed in full to narrow down to one
+        let prediction = (1.0 / bits).ceil();
+        let recovery = (1u64 << 27) as f64 * (32.0 / bits);
+
+        report!(
+            "[+] In theory, prediction takes {:.0} observations ({:.0} outputs), about {} on \
+             this machine, and recovery {:.0} observations ({:.0} outputs), about {}.",
+            prediction,
+            outputs(prediction),
+            describe_seconds(init + prediction * rate),
+            recovery,
+            outputs(recovery),
+            describe_seconds(init + recovery * rate)
+        );
+    } else {
+        report!("[!] In theory, these observations leak nothing, so neither is possible.");
+    }
+
+    if predictions.is_empty() {
+        report!(
+            "[!] Empirically, no generator was predicted within {} observations.",
+            max_observations
+        );
+    } else {
+        let reached = predictions.len();
+        let (observations, seconds): (Vec<f64>, Vec<f64>) = predictions.into_iter().unzip();
+        let observations = median(observations);
+
+        report!(
+            "[+] Empirically, prediction took {:.0} observations ({:.0} outputs) and {} in the \
+             median, for the {} of {} generators predicted.",
+            observations,
+            outputs(observations),
+            describe_seconds(median(seconds)),
+            reached,
+            trials
+        );
+    }
+
+    let (observations, seconds): (Vec<f64>, Vec<f64>) = recoveries.into_iter().unzip();
+    let observations = median(observations);
+
+    report!(
+        "[+] Empirically, recovery takes {:.0} observations ({:.0} outputs) and {} in the \
+         median{}.",
+        observations,
+        outputs(observations),
+        describe_seconds(median(seconds)),
+        if recovered < trials {
+            format!(
+                ", extrapolated for the {} of {} generators not recovered",
+                trials - recovered,
+                trials
+            )
+        } else {
+            String::new()
+        }
+    );
+
+    Ok(())
+}
+
+/// A generator tracked independently of the others in --labeled mode.
+struct Target {
+    label: String,
+    rng: Pcg32State,
+    outputs: usize,
+    window: Vec<u32>,
+    retries: usize,
+    tracker: Option<Tracker<Console>>,
+    /// Why this generator is no longer tracked, if it isn't.
+    failure: Option<String>,
+}
+
+impl Target {
+    pub fn new(label: &str, rng: Pcg32State) -> Self {
+        Self {
+            label: label.to_owned(),
+            rng,
+            outputs: 0,
+            window: Vec::with_capacity(4),
+            retries: 0,
+            tracker: None,
+            failure: None,
+        }
+    }
+
+    /// Submits the next output of this generator, initializing its predictor
+    /// once four consecutive outputs are available.
+    pub fn submit(
+        &mut self,
+        output: u32,
+        table: &Arc<LookupTable>,
+        args: &Opt,
+        filter: &CandidateFilter,
+        start_time: Instant,
+    ) -> Result<()> {
+        if self.failure.is_some() {
+            return Ok(());
+        }
+
+        self.outputs += 1;
+
+        if let Some(tracker) = &mut self.tracker {
+            tracker.observer_mut().outputs = self.outputs;
+
+            if !args.no_predictions {
+                report!(
+                    "[-] Reading output {} (with value 0x{:08X})",
+                    output_name(Some(&self.label), self.outputs),
+                    output
+                );
+            }
+
+            if tracker.submit_next_output(output).is_err() {
+                report!(
+                    "[!] Output {} is inconsistent, no longer tracking `{}`.",
+                    output_name(Some(&self.label), self.outputs),
+                    self.label
+                );
+
+                self.failure = Some(format!(
+                    "output #{} is inconsistent",
+                    display_index(self.outputs)
+                ));
+            }
+
+            return Ok(());
+        }
+
+        self.window.push(output);
+
+        if self.window.len() < 4 {
+            return Ok(());
+        }
+
+        let outputs = [
+            self.window[0],
+            self.window[1],
+            self.window[2],
+            self.window[3],
+        ];
+
+        let predictor = match Predictor::try_new(Arc::clone(table), outputs) {
+            Ok(predictor) => predictor,
+            Err(_) if self.retries == args.init_retries => {
+                report!(
+                    "[!] Outputs of `{}` not produced by PCG-XSH-RR, no longer tracking it.",
+                    self.label
+                );
+
+                self.failure = Some("not produced by PCG-XSH-RR".to_owned());
+                return Ok(());
+            }
+            Err(_) => {
+                self.retries += 1;
+                self.window.remove(0);
+                return Ok(());
+            }
+        };
+
+        report!(
+            "[+] Predictor for `{}` initialized on outputs #{} to #{} after {:.2} seconds.",
+            self.label,
+            display_index(self.outputs - 3),
+            display_index(self.outputs),
+            start_time.elapsed().as_secs_f64(),
+        );
+
+        let mut candidates = Candidates::Implicit(predictor);
+
+        if !args.no_recovery {
+            if let Err(err) = enumerate_candidates(&mut candidates, filter, self.outputs) {
+                report!("[!] {} for `{}`, no longer tracking it.", err, self.label);
+
+                self.failure = Some(err.to_string());
+                return Ok(());
+            }
+        }
+
+        let console = Console {
+            label: Some(self.label.clone()),
+            rng: self.rng,
+            start_time,
+            outputs: self.outputs,
+            predictions: !args.no_predictions,
+            recovery: !args.no_recovery,
+            seed_search: args.seed_search,
+            rand_seed: args.rand_seed,
+            pcg_cpp: args.pcg_cpp,
+            unique: args.unique,
+            tokens: TokenFormat::from_args(args),
+            bounded: args.bounded,
+            sampler: args
+                .sample
+                .clone()
+                .map(|sampler| (sampler, args.sample_count)),
+            progress: !args.no_progress,
+            oracle: false,
+            entropy: args.entropy,
+            wide_scans: 0,
+            predictions_csv: None,
+            control: None,
+        };
+
+        let threshold = if args.no_recovery { 0 } else { args.threshold };
+        self.tracker = Some(Tracker::new(candidates, threshold, console));
+        Ok(())
+    }
+}
+
+/// Tracks several generators whose outputs are interleaved on standard
+/// input, each line being of the form `label value`.
+fn run_labeled(
+    args: &Opt,
+    filter: &CandidateFilter,
+    seed: u64,
+    start_time: Instant,
+    summary: &mut Summary,
+) -> Result<i32> {
+    let table = load_table(args, summary)?;
+
+    report!("[-] Reading labeled outputs, tracking one generator per label.");
+
+    let mut stdin = BufReader::new(Interruptible(std::io::stdin()));
+    let mut targets: Vec<Target> = vec![];
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        match stdin.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(_) if INTERRUPTED.load(Ordering::SeqCst) => {
+                report!("\n[!] Interrupted, summarizing the generators tracked so far.");
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (label, mut output) = parse_labeled_output(&line)?;
+
+        if args.byteswap {
+            output = output.swap_bytes();
+        }
+
+        let output = args
+            .transform
+            .iter()
+            .fold(output, |value, step| step.apply(value));
+
+        let position = match targets.iter().position(|target| target.label == label) {
+            Some(position) => position,
+            None => {
+                let rng = Pcg32State::from_rand_pcg(seed, targets.len() as u64);
+                targets.push(Target::new(label, rng));
+                targets.len() - 1
+            }
+        };
+
+        targets[position].submit(output, &table, args, filter, start_time)?;
+        summary.outputs += 1;
+    }
+
+    summary.end_phase("tracking");
+
+    report!("[+] Tracked {} generators:", targets.len());
+
+    for target in &targets {
+        let status = match (&target.failure, &target.tracker) {
+            (Some(failure), _) => failure.clone(),
+            (None, None) => "not enough outputs to initialize".to_owned(),
+            (None, Some(tracker)) => match tracker.candidates().recovered_state() {
+                Some(state) if !args.no_recovery => {
+                    let initial = state.jump(1u64.wrapping_sub(target.outputs as u64));
+
+                    format!(
+                        "recovered with .state = 0x{:016X}, .inc = 0x{:016X}",
+                        initial.state, initial.inc
+                    )
+                }
+                _ => format!("{} candidate states left", tracker.candidates().count()),
+            },
+        };
+
+        report!(
+            "    `{}` ({} outputs): {}",
+            target.label,
+            target.outputs,
+            status
+        );
+    }
+
+    summary.wide_scans = table.wide_scans();
+
+    Ok(if INTERRUPTED.load(Ordering::SeqCst) {
+        EXIT_INTERRUPTED
+    } else {
+        0
+    })
+}
+
+/// Composite value built by the target from consecutive outputs.
+#[derive(Clone, Copy, Debug)]
+enum TokenKind {
+    /// A version 4 UUID, from four outputs.
+    Uuid,
+    /// A 16-byte nonce, from four outputs.
+    Nonce,
+    /// A 64-bit integer such as a session ID, from two outputs.
+    U64,
+}
+
+impl TokenKind {
+    /// Returns the number of outputs consumed by each token.
+    pub fn outputs(self) -> usize {
+        match self {
+            Self::Uuid | Self::Nonce => 4,
+            Self::U64 => 2,
+        }
+    }
+}
+
+impl FromStr for TokenKind {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "uuid" => Ok(Self::Uuid),
+            "nonce" => Ok(Self::Nonce),
+            "u64" => Ok(Self::U64),
+            _ => Err(Error::other("token must be `uuid`, `nonce` or `u64`")),
+        }
+    }
+}
+
+/// Byte order in which outputs are laid out within a token.
+#[derive(Clone, Copy, Debug)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl FromStr for ByteOrder {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "little" => Ok(Self::Little),
+            "big" => Ok(Self::Big),
+            _ => Err(Error::other("byte order must be `little` or `big`")),
+        }
+    }
+}
+
+/// How future outputs are assembled into the tokens printed by --tokens.
+struct TokenFormat {
+    kind: TokenKind,
+    order: ByteOrder,
+    count: usize,
+}
+
+impl TokenFormat {
+    pub fn from_args(args: &Opt) -> Option<Self> {
+        Some(Self {
+            kind: args.tokens?,
+            order: args.token_order,
+            count: args.token_count,
+        })
+    }
+
+    /// Assembles one token from as many outputs as it consumes.
+    pub fn format(&self, outputs: &[u32]) -> String {
+        let mut bytes: Vec<u8> = outputs
+            .iter()
+            .flat_map(|output| match self.order {
+                ByteOrder::Little => output.to_le_bytes(),
+                ByteOrder::Big => output.to_be_bytes(),
+            })
+            .collect();
+
+        match self.kind {
+            TokenKind::Uuid => {
+                bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+                bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+
+                let hex = hex_string(&bytes);
+
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..]
+                )
+            }
+            TokenKind::Nonce => hex_string(&bytes),
+            TokenKind::U64 => {
+                let mut word = [0; 8];
+                word.copy_from_slice(&bytes);
+
+                let value = match self.order {
+                    ByteOrder::Little => u64::from_le_bytes(word),
+                    ByteOrder::Big => u64::from_be_bytes(word),
+                };
+
+                format!("0x{:016X} ({})", value, value)
+            }
+        }
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Prints the tokens the target will build from its future outputs, given
+/// the state which produced the last output consumed.
+fn display_tokens(state: Pcg32State, label: Option<&str>, index: usize, format: &TokenFormat) {
+    let mut generator = state;
+    generator.advance();
+
+    report!(
+        "[+] Next {} tokens built from output {} onwards:\n",
+        format.count,
+        output_name(label, index)
+    );
+
+    for _ in 0..format.count {
+        let outputs: Vec<u32> = (0..format.kind.outputs())
+            .map(|_| generator.next_u32())
+            .collect();
+
+        report!("    {}", format.format(&outputs));
+    }
+
+    report!();
+}
+
+/// A sampling procedure the target applies to its outputs, modelled on rand.
+#[derive(Clone, Debug)]
+enum Sampler {
+    /// `Uniform::new(low, high)` over `u32`, consuming one output per try.
+    Uniform(u32, u32),
+    /// `WeightedIndex::new(weights)` over `u32` weights, each optionally
+    /// named (e.g. after a loot table entry).
+    Weighted(Vec<(String, u32)>),
+    /// A normal distribution with some mean and standard deviation, sampled
+    /// with Box-Muller from two outputs.
+    Normal(f64, f64),
+}
+
+/// Samples `low + hi` where `(hi, lo)` is the widening product of an output
+/// and `range`, rejecting outputs whose `lo` exceeds the zone like rand's
+/// `UniformInt`.
+fn sample_uniform(generator: &mut Pcg32State, low: u32, range: u32) -> u32 {
+    let zone = u32::MAX - (u32::MAX - range + 1) % range;
+
+    loop {
+        let product = generator.next_u32() as u64 * range as u64;
+
+        if product as u32 <= zone {
+            return low + (product >> 32) as u32;
+        }
+    }
+}
+
+/// A discrete distribution the target samples from a single output, turned
+/// into a uniform in [0, 1) as `output / 2^32`.
+#[derive(Clone, Copy, Debug)]
+enum Discrete {
+    /// 1 with probability `p` (when the uniform is below `p`), else 0.
+    Bernoulli(f64),
+    /// The number of failures before the first success of probability `p`,
+    /// by inverting its CDF as `floor(ln(1 - u) / ln(1 - p))`.
+    Geometric(f64),
+}
+
+impl Discrete {
+    /// Returns the sample drawn from an output.
+    pub fn sample(self, output: u32) -> u32 {
+        let uniform = output as f64 / 4_294_967_296.0;
+
+        match self {
+            Self::Bernoulli(p) => (uniform < p) as u32,
+            Self::Geometric(p) => ((1.0 - uniform).ln() / (1.0 - p).ln()) as u32,
+        }
+    }
+}
+
+/// Parses a discrete distribution, `bernoulli(p)` or `geometric(p)`.
+fn parse_discrete(text: &str) -> Result<Discrete> {
+    let invalid = || {
+        Error::other(format!(
+            "invalid distribution `{}`, expected `bernoulli(p)` or `geometric(p)` with 0 < p < 1",
+            text
+        ))
+    };
+
+    let (name, p) = text.trim().split_once('(').ok_or_else(invalid)?;
+    let p: f64 = p
+        .strip_suffix(')')
+        .and_then(|p| p.trim().parse().ok())
+        .filter(|&p| p > 0.0 && p < 1.0)
+        .ok_or_else(invalid)?;
+
+    match name {
+        "bernoulli" => Ok(Discrete::Bernoulli(p)),
+        "geometric" => Ok(Discrete::Geometric(p)),
+        _ => Err(invalid()),
+    }
+}
+
+/// A quantity the target draws from its outputs, as part of a consumption
+/// model.
+#[derive(Clone, Copy, Debug)]
+enum Draw {
+    /// A raw output.
+    Output,
+    /// A uniform in [0, 1) as `output / 2^32`.
+    Uniform,
+    /// rand's `f64`, from the top 53 bits of a `u64` made of two outputs
+    /// (the second being its high half).
+    Double,
+    /// `pcg32_boundedrand(bound)`, assumed to never reject its output.
+    Bounded(u32),
+    /// A normal sample drawn with Box-Muller from two outputs.
+    Normal(f64, f64),
+    /// A sample of a discrete distribution.
+    Discrete(Discrete),
+    /// An output consumed without being observed.
+    Skip,
+}
+
+impl Draw {
+    /// Returns how many outputs the target consumes for this draw.
+    pub fn outputs(self) -> usize {
+        match self {
+            Self::Double | Self::Normal(..) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Submits the observed value of this draw, which only depends on the
+    /// last two outputs consumed.
+    pub fn submit(self, tracker: &mut Tracker<Console>, text: &str) -> Result<()> {
+        let outputs = self.outputs();
+
+        match self {
+            Self::Output => tracker.submit_next_output(parse_output(text)?),
+            Self::Bounded(bound) => {
+                let value = parse_output(text)?;
+                tracker.submit_next_where(outputs, |_, next| next % bound == value)
+            }
+            Self::Discrete(distribution) => {
+                let value = parse_output(text)?;
+                tracker.submit_next_where(outputs, |_, next| distribution.sample(next) == value)
+            }
+            Self::Uniform | Self::Double | Self::Normal(..) => {
+                let (value, tolerance) = parse_sample(text)?;
+
+                tracker.submit_next_where(outputs, |previous, next| {
+                    let drawn = match self {
+                        Self::Uniform => next as f64 / 4_294_967_296.0,
+                        Self::Double => {
+                            let bits = (next as u64) << 32 | previous as u64;
+                            (bits >> 11) as f64 / 9_007_199_254_740_992.0
+                        }
+                        Self::Normal(mean, std_dev) => mean + std_dev * box_muller(previous, next),
+                        _ => unreachable!(),
+                    };
+
+                    (drawn - value).abs() <= tolerance
+                })
+            }
+            Self::Skip => tracker.skip_unknown_output(),
+        }
+    }
+}
+
+/// The quantities the target draws from its outputs in each iteration, in
+/// the order it draws them.
+#[derive(Clone, Debug)]
+struct Consumption {
+    draws: Vec<Draw>,
+}
+
+/// Parses a consumption model written as a list such as `2 uniform, 1
+/// bounded(52), skip, 1 f64`, each draw optionally preceded by how many
+/// times it is drawn.
+fn parse_consumption(text: &str) -> Result<Consumption> {
+    let mut draws = vec![];
+    let (mut depth, mut start) = (0, 0);
+
+    // split on commas, except those separating arguments
+    let items = text
+        .char_indices()
+        .chain([(text.len(), ',')])
+        .filter_map(|(index, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    let item = &text[start..index];
+                    start = index + 1;
+                    return Some(item.trim());
+                }
+                _ => {}
+            }
+
+            None
+        });
+
+    for item in items.collect::<Vec<_>>() {
+        let invalid = || Error::other(format!("invalid draw `{}` in consumption model", item));
+
+        let (count, draw) = match item.split_once(' ') {
+            Some((count, draw)) if count.parse::<usize>().is_ok() => {
+                (count.parse::<usize>().unwrap(), draw.trim())
+            }
+            _ => (1, item),
+        };
+
+        let draw = match draw {
+            "u32" => Draw::Output,
+            "uniform" => Draw::Uniform,
+            "f64" => Draw::Double,
+            "skip" => Draw::Skip,
+            _ if draw.starts_with("bounded(") => {
+                let bound = draw["bounded(".len()..]
+                    .strip_suffix(')')
+                    .ok_or_else(invalid)?;
+                Draw::Bounded(parse_bound(bound.trim()).map_err(|_| invalid())?)
+            }
+            _ if draw.starts_with("normal(") => {
+                let arguments = draw["normal(".len()..]
+                    .strip_suffix(')')
+                    .ok_or_else(invalid)?;
+                let (mean, std_dev) = parse_normal(arguments).map_err(|_| invalid())?;
+                Draw::Normal(mean, std_dev)
+            }
+            _ => Draw::Discrete(parse_discrete(draw).map_err(|_| invalid())?),
+        };
+
+        draws.extend(std::iter::repeat_n(draw, count));
+    }
+
+    if !draws.iter().any(|draw| !matches!(draw, Draw::Skip)) {
+        return Err(Error::other(
+            "consumption model must draw something observed",
+        ));
+    }
+
+    Ok(Consumption { draws })
+}
+
+/// Draws a standard normal sample from two outputs with Box-Muller.
+fn box_muller(first: u32, second: u32) -> f64 {
+    // (0, 1] for the logarithm and [0, 1) for the angle
+    let u1 = (first as f64 + 1.0) / 4_294_967_296.0;
+    let u2 = second as f64 / 4_294_967_296.0;
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+impl Sampler {
+    /// Draws one sample, returning it as it should be displayed.
+    pub fn sample(&self, generator: &mut Pcg32State) -> String {
+        match self {
+            Self::Uniform(low, high) => sample_uniform(generator, *low, high - low).to_string(),
+            Self::Weighted(weights) => {
+                let total = weights.iter().map(|(_, weight)| weight).sum();
+                let chosen = sample_uniform(generator, 0, total);
+
+                let mut cumulative = 0;
+
+                for (name, weight) in weights {
+                    cumulative += weight;
+
+                    if chosen < cumulative {
+                        return name.clone();
+                    }
+                }
+
+                unreachable!()
+            }
+            Self::Normal(mean, std_dev) => {
+                let first = generator.next_u32();
+                let z = box_muller(first, generator.next_u32());
+
+                format!("{:.6}", mean + std_dev * z)
+            }
+        }
+    }
+}
+
+/// Parses a sampler written as `name(arguments)`, e.g. `uniform(1,7)`,
+/// `weighted(common=70,rare=25,epic=5)` or `normal(100,15)`.
+fn parse_sampler(text: &str) -> Result<Sampler> {
+    let invalid = || Error::other(format!("invalid sampler `{}`", text));
+
+    let (name, arguments) = text.trim().split_once('(').ok_or_else(invalid)?;
+    let arguments: Vec<&str> = arguments
+        .strip_suffix(')')
+        .ok_or_else(invalid)?
+        .split(',')
+        .map(str::trim)
+        .collect();
+
+    match (name, arguments.as_slice()) {
+        ("uniform", [low, high]) => {
+            let low = parse::<u32>(low).map_err(|_| invalid())?;
+            let high = parse::<u32>(high).map_err(|_| invalid())?;
+
+            if low >= high {
+                return Err(invalid());
+            }
+
+            Ok(Sampler::Uniform(low, high))
+        }
+        ("weighted", weights) => {
+            let weights = weights
+                .iter()
+                .enumerate()
+                .map(|(index, weight)| {
+                    let (name, weight) = match weight.split_once('=') {
+                        Some((name, weight)) => (name.trim().to_owned(), weight),
+                        None => (format!("#{}", index), *weight),
+                    };
+
+                    Ok((name, parse::<u32>(weight).map_err(|_| invalid())?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let total = weights
+                .iter()
+                .try_fold(0u32, |total, (_, weight)| total.checked_add(*weight));
+
+            if total.is_none_or(|total| total == 0) {
+                return Err(invalid());
+            }
+
+            Ok(Sampler::Weighted(weights))
+        }
+        ("normal", [mean, std_dev]) => Ok(Sampler::Normal(
+            mean.parse().map_err(|_| invalid())?,
+            std_dev.parse().map_err(|_| invalid())?,
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+/// Prints the samples the target will draw from its future outputs, given
+/// the state which produced the last output consumed.
+fn display_samples(
+    state: Pcg32State,
+    label: Option<&str>,
+    index: usize,
+    sampler: &Sampler,
+    count: usize,
+) {
+    let mut generator = state;
+    generator.advance();
+
+    report!(
+        "[+] Next {} samples drawn from output {} onwards:\n",
+        count,
+        output_name(label, index)
+    );
+
+    for _ in 0..count {
+        report!("    {}", sampler.sample(&mut generator));
+    }
+
+    report!();
+}
+
+/// Order of the fields of a `pcg32_random_t` in a memory dump.
+#[derive(Clone, Copy, Debug)]
+enum StructLayout {
+    StateInc,
+    IncState,
+}
+
+impl FromStr for StructLayout {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "state,inc" => Ok(Self::StateInc),
+            "inc,state" => Ok(Self::IncState),
+            _ => Err(Error::other("layout must be `state,inc` or `inc,state`")),
+        }
+    }
+}
+
+/// Scans a raw memory dump for generator states, at every aligned offset,
+/// which lie on the same sequence as one of the candidates.
+fn scan_memory<P: AsRef<Path>>(
+    path: P,
+    layout: StructLayout,
+    align: usize,
+    states: &[Pcg32State],
+    outputs: usize,
+) -> Result<()> {
+    let dump = read(path)?;
+
+    let candidates: HashMap<u64, &Pcg32State> =
+        states.iter().map(|state| (state.inc.0, state)).collect();
+
+    report!(
+        "[-] Scanning {} bytes of memory for {} candidate states.",
+        dump.len(),
+        candidates.len()
+    );
+
+    let mut found = 0;
+
+    for offset in (0..dump.len().saturating_sub(15)).step_by(align.max(1)) {
+        let mut words = [0u64; 2];
+        cast_slice_mut(&mut words).copy_from_slice(&dump[offset..offset + 16]);
+
+        let (state, inc) = match layout {
+            StructLayout::StateInc => (words[0], words[1]),
+            StructLayout::IncState => (words[1], words[0]),
+        };
+
+        if let Some(candidate) = candidates.get(&(inc | 1)) {
+            let distance = candidate.distance_to(W(state)) as i64;
+
+            report!(
+                "[+] Found state 0x{:016X} with inc 0x{:016X} at offset 0x{:X}, \
+                 about to produce output #{}.",
+                state,
+                inc,
+                offset,
+                display_index(outputs) as i64 + distance
+            );
+
+            found += 1;
+        }
+    }
+
+    if found == 0 {
+        report!("[-] No matching generator state found in memory.");
+    }
+
+    Ok(())
+}
+
+/// Locates a later capture of consecutive outputs of the same generator by
+/// recovering the state it starts at, using the now known increment, then
+/// solving for the number of steps from the recovered state to that state.
+fn locate_capture<P: AsRef<Path>>(path: P, state: Pcg32State, outputs: usize) -> Result<()> {
+    let capture = read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_output)
+        .collect::<Result<Vec<_>>>()?;
+
+    if capture.len() < 3 {
+        return Err(Error::other("need at least 3 outputs in the later capture"));
+    }
+
+    report!(
+        "[-] Searching for the state producing the {} outputs of the later capture...",
+        capture.len()
+    );
+
+    match recover_state_with_inc(state.inc, &capture) {
+        Some(start) => {
+            let distance = state.distance_to(start.state);
+
+            report!(
+                "[+] Later capture starts at output #{}, {} outputs after output #{}.",
+                display_index(outputs) as u128 + distance as u128,
+                distance,
+                display_index(outputs)
+            );
+        }
+        None => report!("[-] Later capture was not produced by the same generator."),
+    }
+
+    Ok(())
+}
+
+/// Number of raw outputs kept to diagnose initialization failures.
+const DIAGNOSIS_OUTPUTS: usize = 64;
+
+/// Another way the raw outputs could have been meant to be read.
+struct Interpretation {
+    description: String,
+    /// The flags which would read the outputs this way.
+    flags: String,
+    outputs: Vec<u32>,
+}
+
+/// Lists plausible interpretations of the raw outputs other than the one
+/// configured, undoing the most common input mistakes.
+fn interpretations(input: &Input, args: &Opt) -> Vec<Interpretation> {
+    let raw = &input.first_outputs;
+    let mut interpretations = vec![];
+
+    if input.byteswap || !input.transforms.is_empty() || input.every_nth > 1 {
+        interpretations.push(Interpretation {
+            description: "as given, without input transforms".to_owned(),
+            flags: "no --byteswap, --transform or --every-nth".to_owned(),
+            outputs: raw.clone(),
+        });
+    }
+
+    if !input.byteswap {
+        interpretations.push(Interpretation {
+            description: "in the opposite byte order".to_owned(),
+            flags: "--byteswap".to_owned(),
+            outputs: raw.iter().map(|output| output.swap_bytes()).collect(),
+        });
+    }
+
+    if input.binary {
+        let bytes: Vec<u8> = raw.iter().flat_map(|output| output.to_ne_bytes()).collect();
+
+        for offset in 1..4 {
+            interpretations.push(Interpretation {
+                description: format!("shifted by {} bytes", offset),
+                flags: format!("discarding the first {} bytes of input", offset),
+                outputs: bytes[offset..]
+                    .chunks_exact(4)
+                    .map(|word| u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+                    .collect(),
+            });
+        }
+    }
+
+    if args.every_nth <= 1 {
+        for nth in 2..=4 {
+            interpretations.push(Interpretation {
+                description: format!("keeping one output in {}", nth),
+                flags: format!("--every-nth {}", nth),
+                outputs: raw.iter().copied().step_by(nth).collect(),
+            });
+        }
+    }
+
+    interpretations
+}
+
+/// Describes properties of the raw outputs which hint at why they can't have
+/// been produced by PCG-XSH-RR as given.
+fn output_hints(outputs: &[u32]) -> Vec<String> {
+    let mut hints = vec![];
+
+    let bits = outputs
+        .iter()
+        .map(|output| 32 - output.leading_zeros())
+        .max()
+        .unwrap_or_default();
+
+    if bits == 31 {
+        hints.push(
+            "values fit in 31 bits, maybe they are signed Java-style outputs or shifted \
+             right by one bit"
+                .to_owned(),
+        );
+    } else if bits < 31 {
+        hints.push(format!(
+            "values fit in {} bits, maybe they are truncated or bounded outputs, which \
+             can't be predicted directly",
+            bits
+        ));
+    }
+
+    if outputs.iter().all(|output| output & 1 == 0) {
+        hints.push("values are all even, maybe they are shifted left or masked".to_owned());
+    }
+
+    if outputs.windows(2).any(|pair| pair[0] == pair[1]) {
+        hints.push("some values repeat consecutively, try --dedupe-consecutive".to_owned());
+    }
+
+    let low_bytes_zero = outputs.iter().filter(|output| *output & 0xff == 0).count();
+
+    if outputs.len() >= 4 && low_bytes_zero * 2 > outputs.len() {
+        hints.push("most values end with a zero byte, maybe they are big-endian".to_owned());
+    }
+
+    hints
+}
+
+/// Explains why the first outputs read may have failed to initialize the
+/// predictor, by trying other interpretations of them and looking for
+/// telltale properties.
+fn diagnose_initialization(table: Arc<LookupTable>, input: &Input, args: &Opt) {
+    report!(
+        "[!] Initialization failed, diagnosing the first {} outputs read.",
+        input.first_outputs.len()
+    );
+
+    if input.byteswap
+        || input.dedupe_consecutive
+        || input.every_nth > 1
+        || !input.transforms.is_empty()
+    {
+        report!(
+            "[-] Outputs were read with:{}{}{}{}",
+            if input.byteswap { " --byteswap" } else { "" },
+            if input.dedupe_consecutive {
+                " --dedupe-consecutive"
+            } else {
+                ""
+            },
+            if input.every_nth > 1 {
+                format!(" --every-nth {}", input.every_nth)
+            } else {
+                String::new()
+            },
+            if input.transforms.is_empty() {
+                String::new()
+            } else {
+                format!(" --transform {:?}", input.transforms)
+            }
+        );
+    }
+
+    let mut table = table;
+    let mut found = false;
+
+    let interpretations = interpretations(input, args)
+        .into_iter()
+        .filter(|interpretation| interpretation.outputs.len() >= 4);
+
+    for interpretation in interpretations {
+        // allow for a few unrelated values at the start of the stream
+        let windows = interpretation.outputs.windows(4).take(4);
+        let mut works = false;
+
+        for window in windows {
+            match Predictor::try_new(table, [window[0], window[1], window[2], window[3]]) {
+                Ok(predictor) => {
+                    table = predictor.into_table();
+                    works = true;
+                    break;
+                }
+                Err(returned) => table = returned,
+            }
+        }
+
+        report!(
+            "    {} {} ({})",
+            if works { "[+]" } else { "[ ]" },
+            interpretation.description,
+            interpretation.flags
+        );
+
+        found |= works;
+    }
+
+    for hint in output_hints(&input.first_outputs) {
+        report!("[-] Hint: {}.", hint);
+    }
+
+    if found {
+        report!("[+] Outputs read as marked above would initialize the predictor.");
+    }
+}
+
+/// Submits a bit leaked by the generator, accounting for the outputs it was
+/// drawn from.
+fn submit_bit(
+    tracker: &mut Tracker<Console>,
+    input: &mut Input,
+    outputs: &mut usize,
+    bit: u32,
+    leak: BitLeak,
+) -> Result<()> {
+    if bit > 1 {
+        return Err(Error::other(format!(
+            "leaked bit must be 0 or 1, not {}",
+            bit
+        )));
+    }
+
+    // the observer sees the index of the last output the bit was drawn from
+    *outputs += leak.outputs() - 1;
+    input.last_index = *outputs;
+    tracker.observer_mut().outputs = *outputs;
+
+    if tracker.observer_mut().predictions {
+        report!(
+            "[-] Reading bit #{} (with value {})",
+            display_index(*outputs),
+            bit
+        );
+    }
+
+    tracker.submit_next_bit(bit == 1, leak)
+}
+
+/// Submits a sample of a normal distribution, drawn with Box-Muller from two
+/// outputs, accounting for both of them.
+fn submit_normal(
+    tracker: &mut Tracker<Console>,
+    input: &mut Input,
+    outputs: &mut usize,
+    (sample, tolerance): (f64, f64),
+    (mean, std_dev): (f64, f64),
+) -> Result<()> {
+    // the observer sees the index of the second output the sample was drawn from
+    *outputs += 2;
+    input.last_index = *outputs;
+    tracker.observer_mut().outputs = *outputs;
+
+    if tracker.observer_mut().predictions {
+        report!(
+            "[-] Reading sample #{} (with value {})",
+            display_index(*outputs),
+            sample
+        );
+    }
+
+    tracker.submit_next_where(2, |first, second| {
+        (mean + std_dev * box_muller(first, second) - sample).abs() <= tolerance
+    })
+}
+
+/// Submits a sample of a discrete distribution, drawn from the last of the
+/// `drawn` outputs the target consumes for each sample.
+fn submit_discrete(
+    tracker: &mut Tracker<Console>,
+    input: &mut Input,
+    outputs: &mut usize,
+    sample: u32,
+    distribution: Discrete,
+    drawn: usize,
+) -> Result<()> {
+    if let Discrete::Bernoulli(_) = distribution {
+        if sample > 1 {
+            return Err(Error::other(format!(
+                "Bernoulli sample must be 0 or 1, not {}",
+                sample
+            )));
+        }
+    }
+
+    // the observer sees the index of the output the sample was drawn from
+    *outputs += drawn - 1;
+    input.last_index = *outputs;
+    tracker.observer_mut().outputs = *outputs;
+
+    if tracker.observer_mut().predictions {
+        report!(
+            "[-] Reading sample #{} (with value {})",
+            display_index(*outputs),
+            sample
+        );
+    }
+
+    tracker.submit_next_where(drawn, |_, next| distribution.sample(next) == sample)
+}
+
+/// Submits an output reduced to a range by float scaling.
+fn submit_scaled(
+    tracker: &mut Tracker<Console>,
+    outputs: usize,
+    value: u32,
+    range: u32,
+) -> Result<()> {
+    if value >= range {
+        return Err(Error::other(format!(
+            "scaled output must be below {}, not {}",
+            range, value
+        )));
+    }
+
+    if tracker.observer_mut().predictions {
+        report!(
+            "[-] Reading scaled output #{} (with value {})",
+            display_index(outputs),
+            value
+        );
+    }
+
+    tracker.submit_next_scaled(value, range)
+}
+
+/// Submits an output observed reduced as the transforms end with, stepping
+/// past any outputs missing before it.
+fn submit_reduced(
+    tracker: &mut Tracker<Console>,
+    outputs: usize,
+    value: u32,
+    model: ObservationModel,
+    missing: usize,
+) -> Result<()> {
+    if model.observe(0, value) != value {
+        return Err(Error::other(format!(
+            "value {} can't be an output reduced by the final transform",
+            value
+        )));
+    }
+
+    if tracker.observer_mut().predictions {
+        report!(
+            "[-] Reading reduced output #{} (with value {})",
+            display_index(outputs),
+            value
+        );
+    }
+
+    tracker.submit_next_where(missing + 1, |previous, next| {
+        model.observe(previous, next) == value
+    })
+}
+
+/// Number of observations which must all be consistent with a stride or any
+/// other interpretation of the outputs for it to be inferred, past the four
+/// initializing the predictor.
+const CONFIRMATIONS: usize = 4;
+
+/// Infers how many outputs separate the observations by trying every stride
+/// up to `max_stride` in turn, with the table given for each or else one
+/// generated for it, until one
+/// initializes a predictor which the observations after the first four are
+/// consistent with. The observations are then numbered by the outputs they
+/// are, in `window` and from then on by the input.
+fn infer_stride(
+    tables: &TableSet,
+    input: &mut Input,
+    max_stride: usize,
+    window: &mut Vec<(usize, u32)>,
+) -> Result<Predictor> {
+    report!(
+        "[-] Reading {} more outputs to infer the stride.",
+        CONFIRMATIONS
+    );
+
+    let mut observations = vec![];
+
+    while observations.len() < 4 + CONFIRMATIONS {
+        observations.push(input.next_output()?.1);
+    }
+
+    let initial = [
+        observations[0],
+        observations[1],
+        observations[2],
+        observations[3],
+    ];
+    for stride in 1..=max_stride {
+        let table = tables.get_or_generate(stride);
+
+        let Ok(mut predictor) = Predictor::try_new(table, initial) else {
+            continue;
+        };
+
+        let consistent = observations[4..]
+            .iter()
+            .all(|&output| predictor.submit_next_output(output).is_ok());
+
+        if consistent {
+            match stride {
+                1 => report!("[+] Inferred that the outputs are consecutive."),
+                _ => report!(
+                    "[+] Inferred a stride of {} (pass --stride {} with a table made by \
+                     gen-table --stride {} to skip this next time).",
+                    stride,
+                    stride,
+                    stride
+                ),
+            }
+
+            *window = (observations.iter().enumerate())
+                .map(|(offset, &output)| (1 + offset * stride, output))
+                .collect();
+
+            input.stride = stride;
+            input.last_index = window.last().unwrap().0;
+
+            return Ok(predictor);
+        }
+    }
+
+    Err(Error::other(format!(
+        "no stride up to {} makes the outputs consistent",
+        max_stride
+    )))
+}
+
+/// An interpretation of the observations raced by `race_interpretations`
+/// (or compared by `compare_interpretations`), ordered by stride and then as
+/// read before byteswapped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Hypothesis {
+    stride: usize,
+    /// Whether the outputs are in the opposite byte order to the one read.
+    byteswap: bool,
+}
+
+impl Hypothesis {
+    fn describe(self) -> String {
+        format!(
+            "{} with a stride of {}",
+            if self.byteswap {
+                "byteswapped outputs"
+            } else {
+                "outputs as read"
+            },
+            self.stride
+        )
+    }
+}
+
+/// Settles raced interpretations in the order of `Hypothesis` as they finish,
+/// in whatever order that is, along with their result if consistent.
+struct RaceOrder<T> {
+    pending: VecDeque<Hypothesis>,
+    finished: HashMap<Hypothesis, Option<T>>,
+}
+
+impl<T> RaceOrder<T> {
+    /// Expects both byte orders of every stride.
+    fn new(strides: std::ops::RangeInclusive<usize>) -> Self {
+        Self {
+            pending: strides
+                .flat_map(|stride| [false, true].map(|byteswap| Hypothesis { stride, byteswap }))
+                .collect(),
+            finished: HashMap::new(),
+        }
+    }
+
+    /// Records that an interpretation finished, and returns the winner once
+    /// it and every interpretation before it have, calling `ruled_out` for
+    /// each inconsistent one settled on the way.
+    fn finish<F>(
+        &mut self,
+        hypothesis: Hypothesis,
+        result: Option<T>,
+        mut ruled_out: F,
+    ) -> Option<(Hypothesis, T)>
+    where
+        F: FnMut(Hypothesis),
+    {
+        let Self { pending, finished } = self;
+        finished.insert(hypothesis, result);
+
+        while let Some(result) = pending.front().and_then(|h| finished.remove(h)) {
+            let hypothesis = pending.pop_front().unwrap();
+
+            match result {
+                Some(result) => return Some((hypothesis, result)),
+                None => ruled_out(hypothesis),
+            }
+        }
+
+        None
+    }
+}
+
+/// Races a predictor for each stride the observations could be in, each on
+/// its own thread trying both byte orders in turn with the table of its
+/// stride, and carries on with the first interpretation in the order of
+/// `Hypothesis` which the observations after the first four are consistent
+/// with, once every one before it is ruled out; whichever thread finishes
+/// first, the same interpretation wins. The others are abandoned, cancelling
+/// those already busy initializing.
+fn race_interpretations(
+    tables: &TableSet,
+    input: &mut Input,
+    args: &Opt,
+    window: &mut Vec<(usize, u32)>,
+) -> Result<Predictor> {
+    let strides = match args.max_stride {
+        Some(max_stride) => 1..=max_stride,
+        None => args.stride..=args.stride,
+    };
+
+    report!(
+        "[-] Reading {} more outputs to race {} interpretations of them.",
+        CONFIRMATIONS,
+        strides.clone().count() * 2
+    );
+
+    let mut observations = vec![];
+
+    while observations.len() < 4 + CONFIRMATIONS {
+        observations.push(input.next_output()?.1);
+    }
+
+    let mut order = RaceOrder::new(strides.clone());
+    let observations = Arc::new(observations);
+    let abandoned = CancellationToken::new();
+    let (sender, receiver) = mpsc::channel();
+    for stride in strides {
+        let table = tables.get(stride);
+        let (observations, abandoned, sender) =
+            (observations.clone(), abandoned.clone(), sender.clone());
+
+        thread::spawn(move || {
+            let table = match table {
+                Some(table) => table,
+                None if abandoned.is_cancelled() => return,
+                None => Arc::new(LookupTable::generate(stride as u64)),
+            };
+
+            // one byte order at a time, so that only the first consistent one
+            // queries the table, which counts every query (see `wide_scans`)
+            for byteswap in [false, true] {
+                if abandoned.is_cancelled() {
+                    return;
+                }
+
+                let outputs: Vec<u32> = (observations.iter())
+                    .map(|&output| match byteswap {
+                        true => output.swap_bytes(),
+                        false => output,
+                    })
+                    .collect();
+
+                let initial = [outputs[0], outputs[1], outputs[2], outputs[3]];
+
+                let predictor = Predictor::try_new_with_cancellation(
+                    table.clone(),
+                    initial,
+                    &abandoned,
+                    |_, _| {},
+                )
+                .ok()
+                .and_then(|mut predictor| {
+                    outputs[4..]
+                        .iter()
+                        .all(|&output| predictor.submit_next_output(output).is_ok())
+                        .then_some(predictor)
+                });
+
+                let consistent = predictor.is_some();
+                let result = predictor.map(|predictor| (outputs, predictor));
+                let _ = sender.send((Hypothesis { stride, byteswap }, result));
+
+                if consistent {
+                    return;
+                }
+            }
+        });
+    }
+
+    drop(sender);
+
+    for (hypothesis, result) in receiver {
+        let settled = order.finish(hypothesis, result, |hypothesis| {
+            report!("[-] Ruled out {}.", hypothesis.describe());
+        });
+
+        if let Some((hypothesis, (outputs, mut predictor))) = settled {
+            abandoned.cancel();
+            predictor.set_cancellation(CancellationToken::new());
+            report!("[+] Race won by {}.", hypothesis.describe());
+
+            let byteswap = input.byteswap != hypothesis.byteswap;
+            let mut flags = vec![];
+
+            if byteswap {
+                flags.push("--byteswap".to_owned());
+            }
+
+            if hypothesis.stride > 1 {
+                flags.push(format!(
+                    "--stride {} with a table made by gen-table --stride {}",
+                    hypothesis.stride, hypothesis.stride
+                ));
+            }
+
+            if !flags.is_empty() {
+                report!("[-] Pass {} to skip the race next time.", flags.join(" "));
+            }
+
+            *window = (outputs.iter().enumerate())
+                .map(|(offset, &output)| (1 + offset * hypothesis.stride, output))
+                .collect();
+
+            input.byteswap = byteswap;
+            input.detect_byte_order = false;
+            input.stride = hypothesis.stride;
+            input.last_index = window.last().unwrap().0;
+
+            return Ok(predictor);
+        }
+    }
+
+    Err(Error::other(
+        "no interpretation raced makes the outputs consistent",
+    ))
+}
+
+/// Reads the values of a capture, one per line or as raw native-endian u32s.
+fn read_capture(path: &Path, binary: bool) -> Result<Vec<u32>> {
+    if binary {
+        Ok((read(path)?.chunks_exact(4))
+            .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect())
+    } else {
+        (read_to_string(path)?.lines())
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_output)
+            .collect()
+    }
+}
+
+/// Number of stretches of a capture explained by only one interpretation
+/// reported by `compare_interpretations`.
+const DIVERGENCES: usize = 16;
+
+/// An interpretation compared by `compare_interpretations`, along with the
+/// table made for its stride (generated in memory if not given).
+#[derive(Clone)]
+struct Contender {
+    hypothesis: Hypothesis,
+    table: Option<PathBuf>,
+}
+
+impl Contender {
+    fn describe(&self) -> String {
+        match &self.table {
+            Some(path) => format!("{} (table {})", self.hypothesis.describe(), path.display()),
+            None => self.hypothesis.describe(),
+        }
+    }
+}
+
+/// Parses an interpretation for `compare`, as comma-separated settings.
+fn parse_contender(text: &str) -> Result<Contender> {
+    let mut contender = Contender {
+        hypothesis: Hypothesis {
+            stride: 1,
+            byteswap: false,
+        },
+        table: None,
+    };
+
+    for setting in text.split(',').map(str::trim) {
+        match setting.split_once(':') {
+            Some(("stride", stride)) => contender.hypothesis.stride = parse_stride(stride)?,
+            Some(("table", path)) => contender.table = Some(PathBuf::from(path)),
+            None if setting == "byteswap" => contender.hypothesis.byteswap = true,
+            _ => {
+                return Err(Error::other(format!(
+                    "unknown setting `{}`, expected `stride:K`, `byteswap` or `table:<path>`",
+                    setting
+                )))
+            }
+        }
+    }
+
+    Ok(contender)
+}
+
+/// Returns which of the outputs a predictor initialized from the first four
+/// finds consistent, stepping past the others as unknown (none are if it
+/// fails to initialize).
+fn explain_outputs(table: Arc<LookupTable>, outputs: &[u32]) -> Vec<bool> {
+    let initial = [outputs[0], outputs[1], outputs[2], outputs[3]];
+
+    let Ok(predictor) = Predictor::try_new(table, initial) else {
+        return vec![false; outputs.len()];
+    };
+
+    let mut candidates = Candidates::Implicit(predictor);
+    let mut explained = vec![true; 4];
+
+    for &output in &outputs[4..] {
+        let consistent = candidates.submit_next_output(output).is_ok();
+
+        explained.push(consistent);
+
+        if !consistent && candidates.skip_unknown_output().is_err() {
+            // the outputs after it can't be lined up with the candidates
+            explained.resize(outputs.len(), false);
+            break;
+        }
+    }
+
+    explained
+}
+
+/// Runs two interpretations of a capture through a predictor each, and
+/// reports which explains more of its values and the stretches of values
+/// which only one of them explains.
+fn compare_interpretations(
+    capture: &Path,
+    binary: bool,
+    contenders: [&Contender; 2],
+) -> Result<()> {
+    let values = read_capture(capture, binary)?;
+
+    if values.len() < 5 {
+        return Err(Error::other(
+            "need at least 5 values to compare interpretations",
+        ));
+    }
+
+    let names = ["first", "second"];
+
+    for (name, contender) in names.iter().zip(contenders) {
+        report!(
+            "[-] The {} interpretation takes {}.",
+            name,
+            contender.describe()
+        );
+    }
+
+    let mut tables: HashMap<(usize, Option<PathBuf>), Arc<LookupTable>> = HashMap::new();
+    let mut explained = vec![];
+
+    for (name, contender) in names.iter().zip(contenders) {
+        let Hypothesis { stride, byteswap } = contender.hypothesis;
+        let key = (stride, contender.table.clone());
+
+        let table = match tables.get(&key) {
+            Some(table) => table.clone(),
+            None => {
+                let table = match &contender.table {
+                    Some(path) => {
+                        let options = TableOptions {
+                            stride: stride as u64,
+                            ..TableOptions::default()
+                        };
+
+                        LookupTable::open_with_options(path, options, |_, _| {})?
+                    }
+                    None => {
+                        report!(
+                            "[-] Generating the table for a stride of {} in memory...",
+                            stride
+                        );
+
+                        LookupTable::generate(stride as u64)
+                    }
+                };
+
+                tables.entry(key).or_insert(Arc::new(table)).clone()
+            }
+        };
+
+        let outputs: Vec<u32> = (values.iter())
+            .map(|&value| match byteswap {
+                true => value.swap_bytes(),
+                false => value,
+            })
+            .collect();
+
+        let consistent = explain_outputs(table, &outputs);
+        let count = consistent.iter().filter(|&&consistent| consistent).count();
+
+        report!(
+            "[-] The {} explains {} of the {} values ({:.2}%){}.",
+            name,
+            count,
+            values.len(),
+            100.0 * count as f64 / values.len() as f64,
+            match consistent.iter().position(|&consistent| !consistent) {
+                Some(0) => ", failing to initialize".to_owned(),
+                Some(index) => format!(", the first it doesn't being #{}", index + 1),
+                None => String::new(),
+            }
+        );
+
+        explained.push((count, consistent));
+    }
+
+    // stretches of values explained by one interpretation and not the other
+    let mut stretches: Vec<(usize, usize, usize)> = vec![];
+
+    for index in 0..values.len() {
+        if explained[0].1[index] == explained[1].1[index] {
+            continue;
+        }
+
+        let winner = if explained[0].1[index] { 0 } else { 1 };
+
+        match stretches.last_mut() {
+            Some((_, last, by)) if *last + 1 == index && *by == winner => *last = index,
+            _ => stretches.push((index, index, winner)),
+        }
+    }
+
+    for &(first, last, by) in stretches.iter().take(DIVERGENCES) {
+        let values = match first == last {
+            true => format!("Value #{}", first + 1),
+            false => format!("Values #{} to #{}", first + 1, last + 1),
+        };
+
+        report!(
+            "[-] {}{} only explained by the {}.",
+            values,
+            match binary {
+                true => format!(" (bytes {} to {})", 4 * first, 4 * last + 3),
+                false => String::new(),
+            },
+            names[by]
+        );
+    }
+
+    if stretches.len() > DIVERGENCES {
+        report!(
+            "[-] ...and {} more stretches explained by only one of them.",
+            stretches.len() - DIVERGENCES
+        );
+    }
+
+    match explained[0].0.cmp(&explained[1].0) {
+        std::cmp::Ordering::Equal if stretches.is_empty() => report!(
+            "[+] Both interpretations explain the same values, so the capture can't tell them apart."
+        ),
+        std::cmp::Ordering::Equal => report!(
+            "[+] Both interpretations explain as many values, though not the same ones."
+        ),
+        ordering => {
+            let (winner, loser) = match ordering {
+                std::cmp::Ordering::Greater => (0, 1),
+                _ => (1, 0),
+            };
+
+            report!(
+                "[+] The {} interpretation explains the capture better, by {} values.",
+                names[winner],
+                explained[winner].0 - explained[loser].0
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Switches to explicitly enumerating candidates straight away if known
+/// properties of the generator can be used to discard some.
+fn enumerate_candidates(
+    candidates: &mut Candidates,
+    filter: &CandidateFilter,
+    outputs: usize,
+) -> Result<()> {
+    if let Candidates::Implicit(predictor) = candidates {
+        if filter.is_active() {
+            let states =
+                predictor.remaining_candidates_where(|state| filter.matches(state, outputs));
+
+            if states.is_empty() {
+                return Err(Error::other(
+                    "no candidate state matches the known constraints",
+                ));
+            }
+
+            report!(
+                "[+] Narrowed to {} states matching the known constraints.",
+                states.len()
+            );
+
+            *candidates = Candidates::Explicit(states);
+        }
+    }
+
+    Ok(())
+}
+
+fn display_recovered_state(
+    recovered: Pcg32State,
+    outputs: usize,
+    start_time: Instant,
+) -> Pcg32State {
+    report!("[-] State recovery complete, rewinding state...");
+
+    let recovered = recovered.jump(1u64.wrapping_sub(outputs as u64));
+
+    report!(
+        "[+] Generator internal state fully recovered after {:.2} seconds:",
+        start_time.elapsed().as_secs_f64()
+    );
+
+    report!("\n    pcg32_random_t state = {{");
+    report!("        .state = 0x{:016X}", recovered.state);
+    report!("        .inc   = 0x{:016X}", recovered.inc);
+    report!("    }};\n");
+
+    recovered
+}
+
+/// Classifies plausible `initstate` arguments to `pcg32_srandom`, from the
+/// least to the most likely to occur by chance.
+fn classify_seed(initstate: u64) -> Option<(usize, &'static str)> {
+    match initstate {
+        0..=0xffff => Some((0, "small integer")),
+        0x1_0000..=0x40_0000 => Some((1, "process ID")),
+        946_684_800..=4_102_444_800 => Some((2, "UNIX timestamp")),
+        0x40_0001..=0xffff_ffff => Some((3, "32-bit seed")),
+        _ => None,
+    }
+}
+
+/// The state of `PCG32_INITIALIZER`, for generators which are never seeded.
+const PCG32_INITIALIZER_STATE: u64 = 0x853C_49E6_748F_EA9B;
+
+/// The increment of `PCG32_INITIALIZER`, which `pcg32_srandom` keeps when
+/// seeding the global generator with the same stream.
+const PCG32_INITIALIZER_INC: u64 = 0xDA3E_39CB_94B9_5BDB;
+
+/// How many outputs back to look for a seeding point without --seed-search,
+/// when the increment is a default one.
+const DEFAULT_SEED_SEARCH: u64 = 1 << 24;
+
+/// Names the generator whose default increment this is, if any.
+fn default_increment_name(inc: u64) -> Option<&'static str> {
+    match inc {
+        PCG32_INITIALIZER_INC => Some("PCG32_INITIALIZER"),
+        PCG_CPP_DEFAULT_INC => Some("pcg32_oneseq and pcg-cpp's pcg32"),
+        _ => None,
+    }
+}
+
+/// Walks the generator backwards from the first output, looking for a state
+/// that `pcg32_srandom` could have produced from a plausible seed.
+fn search_seed(initial: Pcg32State, max_steps: u64) {
+    report!(
+        "[-] Searching up to {} outputs back for a seeding point...",
+        max_steps
+    );
+
+    let mut state = initial;
+    let mut best = None;
+
+    for steps in 0..=max_steps {
+        if (state.state.0, state.inc.0) == (PCG32_INITIALIZER_STATE, PCG32_INITIALIZER_INC) {
+            report!(
+                "[+] Generator was initialized with PCG32_INITIALIZER {} outputs before the first output, and never seeded.",
+                steps
+            );
+
+            return;
+        }
+
+        // pcg32_srandom leaves the state at (initstate + inc) * A + inc
+        let initstate = A_INV * (state.state - initial.inc) - initial.inc;
+
+        if let Some((rank, kind)) = classify_seed(initstate.0) {
+            report!(
+                "[+] Candidate seed 0x{:016X} ({}) found {} outputs back.",
+                initstate,
+                kind,
+                steps
+            );
+
+            if best.is_none_or(|(best_rank, _, _)| rank < best_rank) {
+                best = Some((rank, steps, initstate));
+            }
+        }
+
+        state.rewind();
+    }
+
+    if let Some((_, steps, initstate)) = best {
+        report!(
+            "[+] Generator most likely seeded {} outputs before the first output with:",
+            steps
+        );
+
+        report!(
+            "\n    pcg32_srandom_r(&state, 0x{:016X}, 0x{:016X});\n",
+            initstate,
+            initial.inc >> 1
+        );
+    } else {
+        report!("[-] No plausible seeding point found.");
+    }
+}
+
+/// Reports the seed `rand_pcg::Lcg64Xsh32::from_seed` (or `SmallRng` back
+/// when it was PCG-based) was given to start in the initial state, and the
+/// `u64` passed to `seed_from_u64` if that is where the seed came from.
+fn display_rand_seed(initial: Pcg32State) {
+    let seed = initial.to_rand_seed();
+
+    report!("[+] Generator would be created by rand with:");
+
+    report!(
+        "\n    Lcg64Xsh32::from_seed([{}]);\n",
+        seed.iter()
+            .map(|byte| format!("0x{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    report!("[-] Checking whether the seed was expanded by seed_from_u64...");
+
+    match invert_seed_from_u64(seed) {
+        Some(state) => {
+            report!("[+] Seed was expanded from a single u64:");
+            report!("\n    SmallRng::seed_from_u64(0x{:016X});\n", state);
+        }
+        None => report!("[-] Seed was not produced by seed_from_u64."),
+    }
+}
+
+/// The increment of pcg-cpp's `pcg32` when no stream is given.
+const PCG_CPP_DEFAULT_INC: u64 = 0x1405_7B7E_F767_814F;
+
+/// The seed of a default-constructed pcg-cpp `pcg32`.
+const PCG_CPP_DEFAULT_SEED: u64 = 0xCAFE_F00D_D15E_A5E5;
+
+/// Reports the arguments pcg-cpp's `pcg32` constructors were given to start
+/// in the initial state, which are seeded exactly like `rand_pcg`.
+fn display_pcg_cpp_seed(initial: Pcg32State) {
+    let (seed, stream) = initial.to_rand_pcg();
+
+    if initial.inc.0 != PCG_CPP_DEFAULT_INC {
+        report!("[+] Generator would be created by pcg-cpp with a custom stream:");
+        report!("\n    pcg32 rng(0x{:016X}, 0x{:016X});\n", seed, stream);
+        return;
+    }
+
+    let kind = match seed {
+        PCG_CPP_DEFAULT_SEED => "default-constructed",
+        0..=0xffff => "small literal seed",
+        0x1_0000..=0xffff_ffff => "32-bit seed, e.g. from std::random_device",
+        _ => "64-bit seed",
+    };
+
+    report!("[+] Generator would be created by pcg-cpp with a {}:", kind);
+    report!("\n    pcg32 rng(0x{:016X});\n", seed);
+}
+
+/// Returns the address of a pcg-c `pcg32_unique` generator, whose increment
+/// is its own address with the lowest bit set, if the increment can be one.
+fn unique_address(inc: u64) -> Option<u64> {
+    let address = inc & !1;
+
+    // generators hold 64-bit words, so their address has its low bits clear
+    match address.is_multiple_of(8) && (0x1_0000..0x8000_0000_0000).contains(&address) {
+        true => Some(address),
+        false => None,
+    }
+}
+
+/// Reports where the recovered generator lives in memory, assuming it is a
+/// `pcg32_unique`, and which part of the address space layout that gives away
+/// on a typical x86-64 Linux target.
+fn display_unique_address(initial: Pcg32State) {
+    let address = match unique_address(initial.inc.0) {
+        Some(address) => address,
+        None => {
+            report!(
+                "[!] The increment 0x{:016X} isn't the address of a pcg32_unique generator.",
+                initial.inc
+            );
+            return;
+        }
+    };
+
+    let (region, reveals) = match address {
+        0x7ff0_0000_0000.. => (
+            "the stack of the main thread",
+            "the randomized stack base, defeating stack ASLR",
+        ),
+        0x7f00_0000_0000.. => (
+            "the mmap area (shared libraries, thread stacks or large heap blocks)",
+            "the randomized mmap base, and with it where libc and other libraries are",
+        ),
+        0x5500_0000_0000..0x5700_0000_0000 => (
+            "a PIE executable or the heap just after it",
+            "the randomized executable base (up to the heap offset), defeating PIE",
+        ),
+        ..0x1_0000_0000 => (
+            "a non-PIE executable or the heap just after it",
+            "the heap offset, as non-PIE executables are always at the same address",
+        ),
+        _ => ("an unusual mapping", "part of the address space layout"),
+    };
+
+    report!(
+        "[+] As a pcg32_unique, the generator would be located at 0x{:016X}:",
+        address
+    );
+    report!("\n    region:   {}", region);
+    report!(
+        "    page:     0x{:016X} (offset 0x{:03X})",
+        address & !0xfff,
+        address & 0xfff
+    );
+    report!("    reveals:  {}\n", reveals);
+}
+
+/// Derives a seed from the system clock when none was given.
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Name of the configuration file looked up in the working directory.
+const CONFIG_FILE: &str = "pcg-breaker.toml";
+
+/// A value in the subset of TOML accepted in configuration files.
+#[derive(Debug, PartialEq)]
+enum ConfigValue {
+    Bool(bool),
+    /// A string or number, passed on as the argument of its flag.
+    Text(String),
+    Array(Vec<String>),
+}
+
+/// Parses a scalar value, unquoting strings.
+fn parse_config_scalar(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    if let Some(literal) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        return Some(literal.to_owned());
+    }
+
+    if let Some(basic) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Some(basic.replace("\\\"", "\"").replace("\\\\", "\\"));
+    }
+
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(text.to_owned())
+}
+
+/// Splits text at every `separator` found outside of quoted strings, where
+/// basic strings may hold escaped quotes (and literal strings may not).
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let (mut pieces, mut start) = (vec![], 0);
+    let (mut quote, mut escaped) = (None, false);
+
+    for (index, c) in text.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == separator => {
+                pieces.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            None => {}
+        }
+    }
+
+    pieces.push(&text[start..]);
+    pieces
+}
+
+/// Parses a configuration file made of `key = value` lines, optionally
+/// grouped into `[profile]` sections, into the keys of each section (the
+/// keys before the first section being listed under the empty name).
+fn parse_config(text: &str) -> Result<HashMap<String, Vec<(String, ConfigValue)>>> {
+    let mut sections: HashMap<String, Vec<(String, ConfigValue)>> = HashMap::new();
+    let mut section = String::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let invalid = || Error::other(format!("invalid line {} in configuration", number + 1));
+
+        let line = split_unquoted(line, '#')[0].trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_owned();
+            sections.entry(section.clone()).or_default();
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim();
+
+        let value = match value {
+            "true" => ConfigValue::Bool(true),
+            "false" => ConfigValue::Bool(false),
+            _ => match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                Some(items) => ConfigValue::Array(
+                    split_unquoted(items, ',')
+                        .into_iter()
+                        .filter(|item| !item.trim().is_empty())
+                        .map(parse_config_scalar)
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?,
+                ),
+                None => ConfigValue::Text(parse_config_scalar(value).ok_or_else(invalid)?),
+            },
+        };
+
+        sections
+            .entry(section.clone())
+            .or_default()
+            .push((key.trim().to_owned(), value));
+    }
+
+    Ok(sections)
+}
+
+/// Returns whether a `--name` flag is given, with or without a value.
+fn flag_given(argv: &[String], name: &str) -> bool {
+    let flag = format!("--{}", name);
+
+    (argv.iter()).any(|arg| *arg == flag || arg.starts_with(&format!("{}=", flag)))
+}
+
+/// Returns the value of a `--name value` or `--name=value` flag.
+fn flag_value(argv: &[String], name: &str) -> Option<String> {
+    let flag = format!("--{}", name);
+
+    argv.iter().enumerate().find_map(|(index, arg)| {
+        if *arg == flag {
+            argv.get(index + 1).cloned()
+        } else {
+            arg.strip_prefix(&format!("{}=", flag)).map(str::to_owned)
+        }
+    })
+}
+
+/// Parses the command line, filling in flags from the configuration file's
+/// top-level keys and from the selected profile, with flags given on the
+/// command line taking precedence. Keys are named after the long flags,
+/// except for `table` which gives the table path.
+fn parse_args() -> Result<Opt> {
+    let argv: Vec<String> = std::env::args().collect();
+
+    let config = flag_value(&argv, "config");
+    let profile = flag_value(&argv, "profile");
+
+    let path = PathBuf::from(config.as_deref().unwrap_or(CONFIG_FILE));
+
+    if config.is_none() && profile.is_none() && !path.exists() {
+        let mut args = Opt::from_iter(&argv);
+        args.threshold_given = flag_given(&argv, "threshold");
+        return Ok(args);
+    }
+
+    let mut sections = parse_config(
+        &read_to_string(&path)
+            .map_err(|err| Error::other(format!("failed to read {}: {}", path.display(), err)))?,
+    )?;
+
+    let mut keys = sections.remove("").unwrap_or_default();
+
+    if let Some(profile) = &profile {
+        keys.extend(sections.remove(profile).ok_or_else(|| {
+            Error::other(format!("no profile `{}` in {}", profile, path.display()))
+        })?);
+    }
+
+    let mut flags = vec![];
+    let mut table = None;
+
+    for (key, value) in keys {
+        let flag = format!("--{}", key);
+
+        match value {
+            _ if flag_given(&argv, &key) => {}
+            ConfigValue::Text(path) if key == "table" => table = Some(PathBuf::from(path)),
+            ConfigValue::Bool(true) => flags.push(flag),
+            ConfigValue::Bool(false) => {}
+            ConfigValue::Text(text) => flags.extend([flag, text]),
+            ConfigValue::Array(items) => {
+                for item in items {
+                    flags.extend([flag.clone(), item]);
+                }
+            }
+        }
+    }
+
+    let mut args = Opt::from_iter(argv.iter().take(1).chain(&flags).chain(argv.iter().skip(1)));
+    args.threshold_given = flag_given(&argv, "threshold") || flag_given(&flags, "threshold");
+
+    if args.table.is_none() {
+        args.table = table;
+    }
+
+    Ok(args)
+}
+
+/// Output format of the run summary.
+#[derive(Clone, Copy, Debug)]
+enum SummaryFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for SummaryFormat {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::other("format must be `text` or `json`")),
+        }
+    }
+}
+
+/// What happened during a run, reported when it ends whether it succeeded
+/// or not.
+struct Summary {
+    status: String,
+    outputs: usize,
+    phases: Vec<(&'static str, Duration)>,
+    candidates: Option<usize>,
+    recovered: Option<Pcg32State>,
+    table: Option<PathBuf>,
+    /// How many table queries found their entry outside the scan window.
+    wide_scans: usize,
+    /// How many outputs arrived per second, and the longest wait for one.
+    input_rate: Option<(Option<f64>, Duration)>,
+    phase_start: Instant,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self {
+            status: String::new(),
+            outputs: 0,
+            phases: vec![],
+            candidates: None,
+            recovered: None,
+            table: None,
+            wide_scans: 0,
+            input_rate: None,
+            phase_start: Instant::now(),
+        }
+    }
+
+    /// Records how long the phase ending now took.
+    pub fn end_phase(&mut self, name: &'static str) {
+        self.phases.push((name, self.phase_start.elapsed()));
+        self.phase_start = Instant::now();
+    }
+
+    pub fn display(&self, format: SummaryFormat) {
+        let table_size = self
+            .table
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len());
+
+        let wide_scans = self.wide_scans;
+
+        match format {
+            SummaryFormat::Text => {
+                report!("[-] Run summary:\n");
+                report!("    algorithm:       PCG-XSH-RR");
+                report!("    status:          {}", self.status);
+                report!("    outputs:         {}", self.outputs);
+
+                if let Some(count) = self.candidates {
+                    report!(
+                        "    candidates left: {} ({:.1} bits)",
+                        count,
+                        (count.max(1) as f64).log2()
+                    );
+                }
+
+                if let Some(state) = self.recovered {
+                    report!(
+                        "    recovered:       .state = 0x{:016X}, .inc = 0x{:016X}",
+                        state.state,
+                        state.inc
+                    );
+                }
+
+                if let Some(path) = &self.table {
+                    report!(
+                        "    table:           {} ({} bytes, {} entries)",
+                        path.display(),
+                        table_size.unwrap_or_default(),
+                        table_size.unwrap_or_default() / 8
+                    );
+                }
+
+                if wide_scans != 0 {
+                    report!(
+                        "    wide scans:      {} (entries outside their scan window)",
+                        wide_scans
+                    );
+                }
+
+                if let Some((Some(rate), longest_wait)) = self.input_rate {
+                    report!(
+                        "    input rate:      {:.1} outputs per second (longest wait {:.2} seconds)",
+                        rate,
+                        longest_wait.as_secs_f64()
+                    );
+                }
+
+                for (name, duration) in &self.phases {
+                    report!(
+                        "    {:<17}{:.2} seconds",
+                        format!("{}:", name),
+                        duration.as_secs_f64()
+                    );
+                }
+
+                report!();
+            }
+            SummaryFormat::Json => {
+                let phases: Vec<String> = self
+                    .phases
+                    .iter()
+                    .map(|(name, duration)| {
+                        format!("{}:{:.6}", json_string(name), duration.as_secs_f64())
+                    })
+                    .collect();
+
+                let mut fields = vec![
+                    format!("\"algorithm\":{}", json_string("PCG-XSH-RR")),
+                    format!("\"status\":{}", json_string(&self.status)),
+                    format!("\"outputs\":{}", self.outputs),
+                    format!("\"phases\":{{{}}}", phases.join(",")),
+                ];
+
+                if let Some(count) = self.candidates {
+                    fields.push(format!("\"candidates\":{}", count));
+                    fields.push(format!("\"entropy\":{:.3}", (count.max(1) as f64).log2()));
+                }
+
+                if let Some(state) = self.recovered {
+                    fields.push(format!(
+                        "\"recovered\":{{\"state\":\"0x{:016X}\",\"inc\":\"0x{:016X}\"}}",
+                        state.state, state.inc
+                    ));
+                }
+
+                if let Some((Some(rate), longest_wait)) = self.input_rate {
+                    fields.push(format!(
+                        "\"input\":{{\"rate\":{:.3},\"longest_wait\":{:.6}}}",
+                        rate,
+                        longest_wait.as_secs_f64()
+                    ));
+                }
+
+                if let Some(path) = &self.table {
+                    fields.push(format!(
+                        "\"table\":{{\"path\":{},\"bytes\":{},\"wide_scans\":{}}}",
+                        json_string(&path.display().to_string()),
+                        table_size.unwrap_or_default(),
+                        wide_scans
+                    ));
+                }
+
+                report!("{{{}}}", fields.join(","));
+            }
+        }
+    }
+}
+
+/// Lists outputs one per line as `index:value`, indexed as displayed; this is
+/// the text hashed in results.
+fn observation_lines(observations: &[(usize, u32)]) -> String {
+    observations
+        .iter()
+        .map(|&(index, output)| format!("{}:0x{:08X}\n", display_index(index), output))
+        .collect()
+}
+
+/// Writes a result for --result: the recovered state (the one producing the
+/// first output), the outputs it was recovered from and when.
+fn write_result(path: &Path, state: Pcg32State, observations: &[(usize, u32)]) -> Result<()> {
+    let lines = observation_lines(observations);
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    let outputs: Vec<String> = lines.lines().map(json_string).collect();
+
+    let text = format!(
+        "{{\"algorithm\":{},\"recovered\":{{\"state\":\"0x{:016X}\",\"inc\":\"0x{:016X}\"}},\
+         \"first_index\":{},\"timestamp\":{},\"sha256\":\"{}\",\"outputs\":[{}]}}\n",
+        json_string("PCG-XSH-RR"),
+        state.state,
+        state.inc,
+        display_index(1),
+        timestamp,
+        hex_string(&sha256(lines.as_bytes())),
+        outputs.join(",")
+    );
+
+    write(path, text)
+}
+
+/// Returns the value of a field of the (flat) JSON written by write_result,
+/// without its quotes if it is a string.
+fn result_field<'a>(text: &'a str, name: &str) -> Result<&'a str> {
+    let missing = || Error::other(format!("result has no `{}` field", name));
+
+    let start = text.find(&format!("\"{}\":", name)).ok_or_else(missing)? + name.len() + 3;
+    let value = &text[start..];
+
+    match value.strip_prefix('"') {
+        Some(value) => Ok(&value[..value.find('"').ok_or_else(missing)?]),
+        None => Ok(&value[..value.find([',', '}']).ok_or_else(missing)?]),
+    }
+}
+
+/// Recovers the state of a generator with 64-bit outputs and an invertible
+/// output function from the outputs on standard input, then predicts the
+/// outputs following them.
+fn recover_invertible(
+    function: Invertible64,
+    predict: usize,
+    doubles: bool,
+    cache: Option<usize>,
+) -> Result<()> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+
+    let words = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty());
+
+    let name = match function {
+        Invertible64::XslRrRr => "XSL-RR-RR",
+        Invertible64::RxsMXs => "RXS-M-XS",
+    };
+
+    if doubles {
+        let doubles = words
+            .map(|word| {
+                word.parse::<f64>()
+                    .map_err(|_| Error::other(format!("invalid double `{}`", word)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        return recover_invertible_doubles(function, name, &doubles, predict, cache);
+    }
+
+    let outputs = words
+        .map(|word| {
+            parse::<u64>(word).map_err(|_| Error::other(format!("invalid output `{}`", word)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if outputs.len() < 2 {
+        return Err(Error::other("at least two consecutive outputs are needed"));
+    }
+
+    let state = recover_invertible_64(function, &outputs)
+        .ok_or_else(|| Error::other(format!("output sequence not produced by {} 64/64", name)))?;
+
+    report!(
+        "[+] Recovered the {} 64/64 generator from {} outputs:\n",
+        name,
+        outputs.len()
+    );
+
+    report!("    pcg_state_setseq_64 state = {{");
+    report!("        .state = 0x{:016X}", state.state);
+    report!("        .inc   = 0x{:016X}", state.inc);
+    report!("    }};\n");
+
+    for index in outputs.len()..outputs.len() + predict {
+        report!(
+            "[+] Output #{} will be 0x{:016X}",
+            display_index(index + 1),
+            function.output(state.jump(index as u64).state)
+        );
+    }
+
+    Ok(())
+}
+
+/// Recovers the state of a generator with 64-bit outputs and an invertible
+/// output function from doubles drawn from its outputs, in the order they were
+/// served, then predicts the doubles served after them. With a cache, the
+/// doubles are drawn a whole cache at a time and served last first, as V8
+/// does for `Math.random`, so they are only consecutive within each refill.
+fn recover_invertible_doubles(
+    function: Invertible64,
+    name: &str,
+    served: &[f64],
+    predict: usize,
+    cache: Option<usize>,
+) -> Result<()> {
+    let size = cache.unwrap_or(1);
+
+    if !served.len().is_multiple_of(size) {
+        return Err(Error::other(format!(
+            "{} doubles don't make whole refills of a cache of {}",
+            served.len(),
+            size
+        )));
+    }
+
+    if let Some(index) = served
+        .iter()
+        .position(|&value| double_output_bits(value).is_none())
+    {
+        return Err(Error::other(format!(
+            "double #{} can't be drawn from the top 53 bits of an output",
+            display_index(index + 1)
+        )));
+    }
+
+    // the position in the order the doubles were drawn of each served double
+    let drawn = |index: usize| index / size * size + size - 1 - index % size;
+
+    let mut doubles = vec![0.0; served.len()];
+
+    for (index, &value) in served.iter().enumerate() {
+        doubles[drawn(index)] = value;
+    }
+
+    if doubles.len() < 3 {
+        return Err(Error::other(
+            "at least three consecutive doubles are needed",
+        ));
+    }
+
+    let state = recover_invertible_64_from_doubles(function, &doubles).ok_or_else(|| {
+        Error::other(format!(
+            "double sequence not drawn from a unique {} 64/64 generator",
+            name
+        ))
+    })?;
+
+    report!(
+        "[+] Recovered the {} 64/64 generator from {} doubles:\n",
+        name,
+        doubles.len()
+    );
+
+    report!("    pcg_state_setseq_64 state = {{");
+    report!("        .state = 0x{:016X}", state.state);
+    report!("        .inc   = 0x{:016X}", state.inc);
+    report!("    }};\n");
+
+    for index in served.len()..served.len() + predict {
+        let output = function.output(state.jump(drawn(index) as u64).state);
+
+        report!(
+            "[+] Double #{} will be {}",
+            display_index(index + 1),
+            (output >> 11) as f64 / 9_007_199_254_740_992.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the recovered state of a result written with --result, which
+/// produced the output numbered with the returned index.
+fn result_state(text: &str) -> Result<(Pcg32State, usize)> {
+    let invalid = |what: &str| Error::other(format!("invalid {} in result", what));
+
+    let state = parse::<u64>(result_field(text, "state")?).map_err(|_| invalid("state"))?;
+    let inc = parse::<u64>(result_field(text, "inc")?).map_err(|_| invalid("inc"))?;
+    let first_index: usize = result_field(text, "first_index")?
+        .parse()
+        .map_err(|_| invalid("first index"))?;
+
+    Ok((Pcg32State::new(state, inc), first_index))
+}
+
+/// Checks a result written with --result, exiting with an error unless its
+/// outputs match their hash and are all produced by its recovered state.
+fn verify_result(path: &Path) -> Result<()> {
+    let text = read_to_string(path)?;
+    let invalid = |what: &str| Error::other(format!("invalid {} in result", what));
+
+    let (state, first_index) = result_state(&text)?;
+    let timestamp: u64 = result_field(&text, "timestamp")?
+        .parse()
+        .map_err(|_| invalid("timestamp"))?;
+
+    let outputs = text
+        .split_once("\"outputs\":[")
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .ok_or_else(|| invalid("outputs"))?
+        .0;
+
+    let observations = outputs
+        .split(',')
+        .filter(|output| !output.is_empty())
+        .map(|output| match parse_observation(output.trim_matches('"')) {
+            Ok((Some(index), output)) if index >= first_index => Ok((index, output.value())),
+            _ => Err(invalid("outputs")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let lines: String = observations
+        .iter()
+        .map(|&(index, output)| format!("{}:0x{:08X}\n", index, output))
+        .collect();
+
+    report!(
+        "[-] Result recovered .state = 0x{:016X}, .inc = 0x{:016X} at {} seconds after the epoch.",
+        state.state,
+        state.inc,
+        timestamp
+    );
+
+    if hex_string(&sha256(lines.as_bytes())) != result_field(&text, "sha256")? {
+        return Err(Error::other("outputs don't match their hash"));
+    }
+
+    report!("[+] The {} outputs match their hash.", observations.len());
+
+    for &(index, output) in &observations {
+        if state.jump((index - first_index) as u64).output() != output {
+            return Err(Error::other(format!(
+                "output #{} is not produced by the recovered state",
+                index
+            )));
+        }
+    }
+
+    report!("[+] Every output is produced by the recovered state.");
+
+    Ok(())
+}
+
+/// Renders a binary capture as a hex dump, labeling each of its words with
+/// the output of the recovered generator it is: the one predicted next, or
+/// one up to `resync` outputs later after a gap of unobserved outputs. Words
+/// which are neither are left unlabeled, as data the outputs are embedded in.
+fn annotate_capture(
+    capture: &Path,
+    mut generator: Pcg32State,
+    mut index: usize,
+    resync: usize,
+    byteswap: bool,
+) -> Result<()> {
+    let bytes = read(capture)?;
+    let (mut outputs, mut gaps, mut skipped) = (0, 0, 0);
+
+    for (offset, chunk) in (0..).step_by(4).zip(bytes.chunks(4)) {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        let text: String = (chunk.iter())
+            .map(|&byte| match byte.is_ascii_graphic() {
+                true => byte as char,
+                false => '.',
+            })
+            .collect();
+
+        let dump = format!("{:08x}  {:<11}  |{:<4}|", offset, hex.join(" "), text);
+
+        if chunk.len() < 4 {
+            report!("{}  trailing bytes", dump);
+            continue;
+        }
+
+        let word = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let word = if byteswap { word.swap_bytes() } else { word };
+
+        let mut lookahead = generator;
+
+        let Some(gap) = (0..=resync).find(|_| lookahead.next_u32() == word) else {
+            report!(
+                "{}  not an output (output #{} would be 0x{:08X})",
+                dump,
+                index,
+                generator.output()
+            );
+
+            continue;
+        };
+
+        let status = match gap {
+            0 => "predicted".to_owned(),
+            1 => format!("after output #{} went unobserved", index),
+            gap => format!(
+                "after outputs #{} to #{} went unobserved",
+                index,
+                index + gap - 1
+            ),
+        };
+
+        generator = lookahead;
+        index += gap + 1;
+        outputs += 1;
+
+        if gap > 0 {
+            gaps += 1;
+            skipped += gap;
+        }
+
+        report!("{}  output #{:<8} {}", dump, index - 1, status);
+    }
+
+    report!(
+        "\n[+] {} of the {} words are outputs, with {} gaps of {} unobserved outputs in all.",
+        outputs,
+        bytes.len() / 4,
+        gaps,
+        skipped
+    );
+
+    Ok(())
+}
+
+/// Memory set aside by --memory-budget for everything besides the tables and
+/// the enumerated candidates: the program itself, buffers and the like.
+const MEMORY_RESERVE: u64 = 64 << 20;
+
+/// Fits the run in a memory budget. Tables read into memory or generated are
+/// held as a whole, so the budget must fit every such table the run loads at
+/// once (every one given, and one per stride raced with --race), and those
+/// given are mapped instead (see --mmap) when they don't fit; whatever is
+/// left after them and the reserve decides the threshold, unless --threshold
+/// was given, as the candidates are enumerated while the table is still
+/// loaded.
+fn plan_memory(args: &mut Opt, solver: &SolverEntry, budget: u64) -> Result<()> {
+    let given = args.table.iter().chain(&args.tables).count() as u64;
+
+    let tables = match args.race {
+        _ if args.seed_candidates.is_some() => 0,
+        _ if !solver.needs_table && given == 0 => 0,
+        true => (args.max_stride.unwrap_or(1) as u64).max(given),
+        false => given.max(1),
+    };
+
+    // enumerating threads gather their candidates into a single list at the
+    // end, which briefly holds every candidate twice
+    #[allow(unused_mut)]
+    let mut candidate_bytes = 2 * std::mem::size_of::<Pcg32State>() as u64;
+
+    // pruning on the GPU copies the candidates and gets back their index
+    #[cfg(feature = "gpu")]
+    if args.gpu {
+        candidate_bytes = candidate_bytes.max(2 * 16 + 4);
+    }
+
+    // mapped tables are paged in and out by the kernel as they are queried,
+    // so they don't need to fit
+    let mappable = match cfg!(unix) {
+        true => given.min(tables),
+        false => 0,
+    };
+
+    if !args.mmap && mappable > 0 && budget < tables * TABLE_BYTES + MEMORY_RESERVE {
+        report!("[-] Mapping the tables rather than reading them, as they don't fit the budget.");
+        args.mmap = true;
+    }
+
+    let held = match args.mmap {
+        true => tables - mappable,
+        false => tables,
+    };
+
+    let fixed = held * TABLE_BYTES + MEMORY_RESERVE;
+
+    if budget < fixed {
+        return Err(Error::other(format!(
+            "a memory budget of {} can't fit {} of tables generated in memory, which are held \
+             as a whole, and {} for the rest of the run",
+            describe_bytes(budget),
+            describe_bytes(held * TABLE_BYTES),
+            describe_bytes(MEMORY_RESERVE)
+        )));
+    }
+
+    let fitting = ((budget - fixed) / candidate_bytes) as usize;
+
+    report!(
+        "[-] Planning for a memory budget of {}: {} of tables{} and room for {} enumerated \
+         candidates.",
+        describe_bytes(budget),
+        describe_bytes(held * TABLE_BYTES),
+        match args.mmap && mappable > 0 {
+            true => format!(
+                " (besides {} mapped)",
+                describe_bytes(mappable * TABLE_BYTES)
+            ),
+            false => String::new(),
+        },
+        fitting
+    );
+
+    if args.threshold_given {
+        if args.threshold > fitting {
+            report!(
+                "[!] --threshold {} may enumerate more candidates than the budget fits.",
+                args.threshold
+            );
+        }
+    } else if !args.no_recovery {
+        args.threshold = fitting;
+        report!("[-] Enumerating the candidates from {} of them.", fitting);
+    }
+
+    Ok(())
+}
+
+/// Size in bytes of a table made by gen-table (or generated in memory), which
+/// the wizard checks rather than loading the table twice.
+const TABLE_BYTES: u64 = 1 << 30;
+
+/// Asks the questions of `wizard` on standard input.
+struct Wizard {
+    lines: std::io::Lines<std::io::StdinLock<'static>>,
+}
+
+impl Wizard {
+    /// Asks a question, returning the answer or the default if left empty.
+    fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        match default {
+            "" => print!("{} ", question),
+            _ => print!("{} [{}] ", question, default),
+        }
+
+        std::io::stdout().flush()?;
+
+        let answer = (self.lines.next()).ok_or_else(|| Error::other("wizard aborted"))??;
+
+        match answer.trim() {
+            "" => Ok(default.to_owned()),
+            answer => Ok(answer.to_owned()),
+        }
+    }
+
+    /// Asks for a number until one is given.
+    fn ask_number<T: FromStr>(&mut self, question: &str, default: &str) -> Result<T> {
+        loop {
+            match self.ask(question, default)?.parse() {
+                Ok(number) => return Ok(number),
+                Err(_) => report!("[!] That isn't a number."),
+            }
+        }
+    }
+
+    /// Asks to pick one of some choices, returning its index.
+    fn choose(&mut self, question: &str, choices: &[&str]) -> Result<usize> {
+        report!("{}", question);
+
+        for (index, choice) in choices.iter().enumerate() {
+            report!("  {}) {}", index + 1, choice);
+        }
+
+        loop {
+            match self.ask_number::<usize>(">", "1")? {
+                choice @ 1.. if choice <= choices.len() => return Ok(choice - 1),
+                _ => report!("[!] Pick a number from 1 to {}.", choices.len()),
+            }
+        }
+    }
+
+    /// Asks a yes or no question, yes being the default.
+    fn confirm(&mut self, question: &str) -> Result<bool> {
+        loop {
+            match self
+                .ask(&format!("{} [Y/n]", question), "")?
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => report!("[!] Answer yes or no."),
+            }
+        }
+    }
+}
+
+/// Quotes an argument for a POSIX shell, unless it doesn't need it.
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Asks what was observed of the target and how, checks for a table
+/// (offering to build one with gen-table), then runs the command line this
+/// calls for and prints it, so that it can be run again without the wizard.
+fn run_wizard() -> Result<()> {
+    let mut wizard = Wizard {
+        lines: std::io::stdin().lines(),
+    };
+
+    let mut args: Vec<String> = vec![];
+    let mut capture = None;
+
+    report!("[-] A few questions about what you observed will make up the command to run.\n");
+
+    let source = wizard.choose(
+        "How will the outputs be given?",
+        &[
+            "in a file holding the whole capture",
+            "in a log file still being written to",
+            "typed in here",
+        ],
+    )?;
+
+    if source == 2 {
+        args.push("--inline".to_owned());
+        args.push(wizard.ask("Outputs, separated by spaces or commas:", "")?);
+    } else {
+        let path = wizard.ask("Path to the file:", "")?;
+
+        if !Path::new(&path).is_file() {
+            return Err(Error::other(format!("no file at `{}`", path)));
+        }
+
+        if source == 1 {
+            args.extend(["--follow".to_owned(), path]);
+        } else {
+            match wizard.choose(
+                "How are the outputs written?",
+                &[
+                    "one number per line, in decimal or hexadecimal",
+                    "as raw 32-bit words",
+                    "not sure, detect it",
+                ],
+            )? {
+                1 => {
+                    args.push("--binary".to_owned());
+
+                    if !wizard.confirm("Are the words in the byte order of this machine?")? {
+                        args.push("--byteswap".to_owned());
+                    }
+                }
+                2 => args.extend(["--input-format".to_owned(), "auto".to_owned()]),
+                _ => {}
+            }
+
+            capture = Some(path);
+        }
+    }
+
+    match wizard.choose(
+        "What do the values observed look like?",
+        &[
+            "whole 32-bit outputs",
+            "each output XORed with, or minus, the one before it",
+            "four whole outputs, then outputs scaled to 0..N as floats",
+            "four whole outputs, then single bits leaked by outputs",
+        ],
+    )? {
+        1 => {
+            let derivation = ["xor", "sub"][wizard.choose(
+                "How is each value derived?",
+                &["output XOR previous output", "output minus previous output"],
+            )?];
+
+            args.extend(["--derived".to_owned(), derivation.to_owned()]);
+        }
+        2 => {
+            let range: u32 = wizard.ask_number("What is N?", "")?;
+            args.extend(["--float-scale".to_owned(), range.to_string()]);
+        }
+        3 => {
+            let leak = ["parity", "top", "bool"][wizard.choose(
+                "Which bit is leaked?",
+                &[
+                    "the lowest bit of each output",
+                    "the highest bit of each output",
+                    "the result of rand's gen_bool(0.5)",
+                ],
+            )?];
+
+            args.extend(["--bits".to_owned(), leak.to_owned()]);
+        }
+        _ => {}
+    }
+
+    let count: usize = wizard.ask_number("Roughly how many values do you have?", "")?;
+
+    if count < 4 {
+        return Err(Error::other(
+            "at least four outputs are needed to predict anything",
+        ));
+    }
+
+    match wizard.choose(
+        "What are you after?",
+        &[
+            "predicting the next outputs, and recovering the state if possible",
+            "recovering the state",
+            "predicting the next outputs only",
+        ],
+    )? {
+        1 => args.push("--no-predictions".to_owned()),
+        2 => args.push("--no-recovery".to_owned()),
+        _ => {}
+    }
+
+    if count < 10_000_000 && !args.iter().any(|arg| arg == "--no-recovery") {
+        report!(
+            "[!] Recovering the state usually takes several dozen million outputs, so expect \
+             the run to only narrow the candidates down."
+        );
+    }
+
+    let bound = wizard.ask(
+        "If the target draws bounded values (pcg32_boundedrand_r), their bound:",
+        "none",
+    )?;
+
+    if bound != "none" {
+        args.extend(["--bounded".to_owned(), parse_bound(&bound)?.to_string()]);
+    }
+
+    let table = wizard.ask("Path to the table:", "table.bin")?;
+
+    match std::fs::metadata(&table) {
+        Ok(metadata) if metadata.len() == TABLE_BYTES => {}
+        Ok(_) => {
+            return Err(Error::other(format!(
+                "`{}` isn't a table made by gen-table",
+                table
+            )))
+        }
+        Err(_) => {
+            let gen_table = std::env::current_exe()?.with_file_name("gen-table");
+
+            if !wizard.confirm("There is no table there. Build it now with gen-table (1GiB)?")? {
+                return Err(Error::other(format!(
+                    "a table is needed, build one with `gen-table --output {}`",
+                    shell_quote(&table)
+                )));
+            }
+
+            if !Command::new(gen_table)
+                .arg("--output")
+                .arg(&table)
+                .status()?
+                .success()
+            {
+                return Err(Error::other("gen-table failed to build the table"));
+            }
+        }
+    }
+
+    args.insert(0, table);
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command.args(&args);
+
+    if let Some(path) = &capture {
+        command.stdin(File::open(path)?);
+    }
+
+    report!("[-] Running the command.\n");
+
+    let status = command.status()?;
+
+    let quoted: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+
+    report!(
+        "[+] To run the same again without the wizard:\n\n    pcg-breaker {}{}\n",
+        quoted.join(" "),
+        capture
+            .map(|path| format!(" < {}", shell_quote(&path)))
+            .unwrap_or_default()
+    );
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(Error::other("the command failed")),
+    }
+}
+
+/// Computes the SHA-256 hash of some data.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut hash: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // pad with a one bit, zeroes and the length in bits to a whole block
+    let mut message = data.to_vec();
+    message.push(0x80);
+    message.resize((data.len() + 9).next_multiple_of(64) - 8, 0);
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+
+        for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hash;
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (word, value) in hash.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0; 32];
+
+    for (bytes, word) in digest.chunks_mut(4).zip(hash) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+
+    digest
+}
+
+/// Quotes a string for JSON.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("\nfatal error: {}", err);
+            process::exit(1); // report failure
+        }
+    };
+
+    if let Some(shell) = args.completions {
+        Opt::clap().gen_completions_to("pcg-breaker", shell, &mut std::io::stdout());
+        return;
+    }
+
+    // applies to the subcommands pruning candidates as well
+    set_force_scalar(args.force_scalar);
+
+    if let Some(command) = &args.command {
+        let result = match command {
+            Subcommand::Table(TableCommand::Info { path, stride }) => {
+                display_table_info(path, *stride)
+            }
+            Subcommand::Bench(BenchCommand::Corpus {
+                count,
+                seed,
+                output_dir,
+                outputs,
+            }) => write_worst_cases(*count, *seed, output_dir.as_deref(), *outputs),
+            Subcommand::Bench(BenchCommand::Compare {
+                path,
+                capture,
+                binary,
+                threshold,
+            }) => compare_backends(path, capture, *binary, *threshold),
+            Subcommand::Estimate {
+                path,
+                model,
+                trials,
+                max_observations,
+                seed,
+            } => estimate_cost(path, *model, *trials, *max_observations, *seed),
+            Subcommand::Compare {
+                capture,
+                first,
+                second,
+                binary,
+            } => compare_interpretations(capture, *binary, [first, second]),
+            Subcommand::Annotate {
+                capture,
+                result,
+                state,
+                inc,
+                resync,
+                byteswap,
+            } => (match (result, state, inc) {
+                (Some(path), _, _) => read_to_string(path).and_then(|text| result_state(&text)),
+                (None, Some(state), Some(inc)) => {
+                    Ok((Pcg32State::new(*state, *inc), display_index(1)))
+                }
+                _ => Err(Error::other(
+                    "either --result or --state and --inc are needed",
+                )),
+            })
+            .and_then(|(generator, index)| {
+                annotate_capture(capture, generator, index, *resync, *byteswap)
+            }),
+            Subcommand::Wizard => run_wizard(),
+            Subcommand::Solvers => {
+                for entry in SolverRegistry::with_builtins().entries() {
+                    report!("{:<12}{}", entry.name, entry.description);
+                }
+
+                Ok(())
+            }
+            Subcommand::VerifyResult { path } => verify_result(path),
+            Subcommand::Invertible {
+                function,
+                predict,
+                doubles,
+                cache,
+            } => recover_invertible(*function, *predict, *doubles, *cache),
+        };
+
+        if let Err(err) = result {
+            eprintln!("\nfatal error: {}", err);
+            process::exit(1); // report failure
+        }
+
+        return;
+    }
+
+    EMIT_BINARY.store(args.emit_binary, Ordering::Relaxed);
+    TOLERANT_NUMBERS.store(args.tolerant_numbers, Ordering::Relaxed);
+    INDEX_OFFSET.store(args.first_index - 1, Ordering::Relaxed);
+    install_interrupt_handler();
+
+    let format = args.format;
+    let mut summary = Summary::new();
+
+    let status = match run(args, &mut summary) {
+        Ok(status) => {
+            summary.status = match status {
+                EXIT_IDLE_TIMEOUT => "idle timeout".to_owned(),
+                EXIT_INTERRUPTED => "interrupted".to_owned(),
+                _ => "success".to_owned(),
+            };
+
+            status
+        }
+        Err(err) => {
+            eprintln!("\nfatal error: {}", err);
+            summary.status = format!("failed: {}", err);
+            1 // report failure
+        }
+    };
+
+    summary.display(format);
+    process::exit(status);
+}
+
+const EXAMPLES: &str = "EXAMPLES:
+    Generate the table once, then predict outputs typed one per line, in
+    decimal or 0x-prefixed hexadecimal:
+        cargo run --release --bin gen-table
+        pcg-breaker table.bin
+
+    Recover the state from the outputs printed by util/pcg.c:
+        ./pcg | pcg-breaker --no-predictions table.bin
+
+    Predict outputs captured as raw native-endian 32-bit words:
+        pcg-breaker --binary table.bin < capture.bin
+
+    Recover the state of a generator using the default increment, which
+    takes only a handful of outputs:
+        pcg-breaker --inc-equals 0x14057B7EF767814F table.bin < outputs.txt
+
+    Resume from candidates saved by an earlier run, without the table:
+        pcg-breaker --dump-candidates saved.bin table.bin < part1.txt
+        pcg-breaker --seed-candidates saved.bin < part2.txt
+
+    Install completions for bash:
+        pcg-breaker --completions bash > /etc/bash_completion.d/pcg-breaker";
+
+#[derive(StructOpt)]
+#[structopt(about, after_help = EXAMPLES)]
+struct Opt {
+    /// Only perform state recovery, without predicting outputs
+    #[structopt(
+        long = "no-predictions",
+        alias = "recovery",
+        conflicts_with = "no-recovery"
+    )]
+    no_predictions: bool,
+
+    /// With --no-predictions, start predicting outputs once the state is
+    /// recovered instead of exiting
+    #[structopt(
+        long = "then-predict",
+        requires = "no-predictions",
+        conflicts_with_all = &["oracle", "oracle-command"]
+    )]
+    then_predict: bool,
+
+    /// Only predict outputs, without attempting state recovery
+    #[structopt(long = "no-recovery")]
+    no_recovery: bool,
+
+    /// Number of candidates from which they are explicitly enumerated
+    #[structopt(long = "threshold", default_value = "1000")]
+    threshold: usize,
+
+    /// Whether --threshold was given, on the command line or in the
+    /// configuration file, rather than left to its default
+    #[structopt(skip)]
+    threshold_given: bool,
+
+    /// Fit the run in this much memory (e.g. 512M or 2G), mapping the tables
+    /// as with --mmap if they don't fit (failing straight away if generated
+    /// tables can't fit) and otherwise enumerating candidates as early as
+    /// the rest of the budget allows, unless --threshold is given
+    #[structopt(long = "memory-budget", parse(try_from_str = parse_memory_size))]
+    memory_budget: Option<u64>,
+
+    /// Read raw native-endian u32 outputs instead of one number per line
+    #[structopt(long = "binary", conflicts_with = "input-format")]
+    binary: bool,
+
+    /// Index of the first output read in the target's own numbering, from
+    /// which outputs are numbered (and which their `index:` prefixes use)
+    #[structopt(long = "first-index", default_value = "1", parse(try_from_str = parse_first_index))]
+    first_index: usize,
+
+    /// Format of the outputs on stdin (`text`, `binary` or `auto` to detect
+    /// it, along with the byte order of binary outputs)
+    #[structopt(long = "input-format", default_value = "text")]
+    input_format: InputFormat,
+
+    /// Reverse the byte order of each output after reading it
+    #[structopt(long = "byteswap")]
+    byteswap: bool,
+
+    /// Accept numbers with digit group separators, such as "4 294 967 295"
+    /// or "4.294.967.295", as copied from documents
+    #[structopt(long = "tolerant-numbers")]
+    tolerant_numbers: bool,
+
+    /// Stop after waiting this many seconds for an output
+    #[structopt(long = "idle-timeout")]
+    idle_timeout: Option<u64>,
+
+    /// Warn when no output has been received for this many seconds, and
+    /// again every time as long passes (0 never warns)
+    #[structopt(long = "stall-alert", default_value = "30")]
+    stall_alert: u64,
+
+    /// Read outputs ahead from the start, keeping up to this many and
+    /// dropping the oldest rather than ever blocking the producer
+    #[structopt(long = "buffer", conflicts_with_all = &["oracle-command", "inline", "labeled"])]
+    buffer: Option<usize>,
+
+    /// Discard outputs equal to the output just before them
+    #[structopt(long = "dedupe-consecutive")]
+    dedupe_consecutive: bool,
+
+    /// Only keep every N-th output, starting with the first one
+    #[structopt(long = "every-nth", default_value = "1")]
+    every_nth: usize,
+
+    /// Transforms applied in order to each output, e.g. 'sub(1),xor(0xff)', ending
+    /// with 'mask(2^K-1)' or 'mod(N)' for outputs observed reduced past the first four
+    #[structopt(long = "transform", use_delimiter = true, parse(try_from_str = parse_transform))]
+    transform: Vec<Transform>,
+
+    /// Write the remaining candidate states to this file on exit
+    #[structopt(long = "dump-candidates", parse(from_os_str))]
+    dump_candidates: Option<PathBuf>,
+
+    /// Write the recovered state to this file as JSON, along with the outputs
+    /// consumed, their SHA-256 hash and a timestamp, for verify-result
+    #[structopt(long = "result", conflicts_with_all = &["bits", "float-scale", "labeled"], parse(from_os_str))]
+    result: Option<PathBuf>,
+
+    /// Write every prediction made, along with the output it turned out to
+    /// be, to this CSV file
+    #[structopt(
+        long = "predictions-csv",
+        conflicts_with = "labeled",
+        parse(from_os_str)
+    )]
+    predictions_csv: Option<PathBuf>,
+
+    /// Accept commands (status, predict, state, checkpoint) on a Unix socket
+    /// at this path while outputs are being read
+    #[structopt(long = "control", conflicts_with = "labeled", parse(from_os_str))]
+    control: Option<PathBuf>,
+
+    /// Start from candidate states written by --dump-candidates
+    #[structopt(long = "seed-candidates", parse(from_os_str))]
+    seed_candidates: Option<PathBuf>,
+
+    /// Only keep candidates with this increment
+    #[structopt(long = "inc-equals", parse(try_from_str = parse::<u64>))]
+    inc_equals: Option<u64>,
+
+    /// Only keep candidates with an increment listed in this file
+    #[structopt(long = "inc-in-file", parse(from_os_str))]
+    inc_in_file: Option<PathBuf>,
+
+    /// Only keep candidates whose increment has a 32-bit stream selector
+    #[structopt(long = "inc-low-entropy")]
+    inc_low_entropy: bool,
+
+    /// Only keep candidates whose state has these bits equal to --state-value
+    #[structopt(long = "state-mask", default_value = "0", parse(try_from_str = parse::<u64>))]
+    state_mask: u64,
+
+    /// Known value of the state bits selected by --state-mask
+    #[structopt(long = "state-value", default_value = "0", parse(try_from_str = parse::<u64>))]
+    state_value: u64,
+
+    /// Index of the output produced by the state known through --state-mask
+    #[structopt(long = "state-index", default_value = "1")]
+    state_index: usize,
+
+    /// Read observations derived from each output and the one before it,
+    /// `xor` for out[i] ^ out[i - 1] or `sub` for out[i] - out[i - 1]
+    #[structopt(
+        long = "derived",
+        parse(try_from_str = parse_derivation),
+        conflicts_with_all = &["labeled", "resync"]
+    )]
+    derived: Option<Derivation>,
+
+    /// Known value of the output preceding the first derived observation
+    #[structopt(long = "first-output", requires = "derived", parse(try_from_str = parse_output))]
+    first_output: Option<u32>,
+
+    /// After the four outputs initializing the predictor, read single bits
+    /// (0 or 1) leaked by later outputs: `parity` for out[i] & 1, `top` for
+    /// out[i] >> 31 or `bool` for rand's gen_bool(0.5), drawn from 2 outputs
+    #[structopt(
+        long = "bits",
+        parse(try_from_str = parse_bit_leak),
+        conflicts_with_all = &["derived", "labeled", "resync"]
+    )]
+    bits: Option<BitLeak>,
+
+    /// After the four outputs initializing the predictor, read outputs
+    /// reduced to 0..N by float scaling, as in (out[i] as f64 / 2^32 * N) as
+    /// u32, which leaks their highest bits rather than their lowest
+    #[structopt(
+        long = "float-scale",
+        parse(try_from_str = parse_bound),
+        conflicts_with_all = &["bits", "derived", "labeled", "resync"]
+    )]
+    float_scale: Option<u32>,
+
+    /// After the four outputs initializing the predictor, read samples of a
+    /// normal distribution with this `mean,std_dev`, each drawn with
+    /// Box-Muller from 2 outputs (as with --sample normal), one per line
+    #[structopt(
+        long = "normal",
+        parse(try_from_str = parse_normal),
+        conflicts_with_all = &["bits", "float-scale", "derived", "labeled", "resync", "result"]
+    )]
+    normal: Option<(f64, f64)>,
+
+    /// After the four outputs initializing the predictor, read samples of
+    /// `bernoulli(p)` or `geometric(p)`, each drawn from a single output as a
+    /// uniform in [0, 1)
+    #[structopt(
+        long = "discrete",
+        parse(try_from_str = parse_discrete),
+        conflicts_with_all = &["bits", "float-scale", "normal", "derived", "labeled", "resync", "result"]
+    )]
+    discrete: Option<Discrete>,
+
+    /// Number of outputs the target consumes for each sample given with
+    /// --discrete, the sample being drawn from the last of them
+    #[structopt(long = "sample-outputs", default_value = "1", parse(try_from_str = parse_sample_outputs))]
+    sample_outputs: usize,
+
+    /// Only every k-th output is observed (outputs 1, k + 1, 2k + 1, ...),
+    /// which needs a table made with gen-table --stride k
+    #[structopt(long = "stride", default_value = "1", parse(try_from_str = parse_stride))]
+    stride: usize,
+
+    /// Infer how many outputs separate the observations, trying every stride
+    /// up to this one in turn (generating the table for each in memory)
+    #[structopt(
+        long = "max-stride",
+        parse(try_from_str = parse_stride),
+        conflicts_with = "seed-candidates"
+    )]
+    max_stride: Option<usize>,
+
+    /// Race a predictor for each byte order the outputs may be in, and each
+    /// stride up to --max-stride if given, on their own threads, carrying on
+    /// with whichever interpretation the first outputs turn out consistent
+    /// with first
+    #[structopt(long = "race", conflicts_with_all = &["seed-candidates", "transform"])]
+    race: bool,
+
+    /// After the four outputs initializing the predictor, read one line of
+    /// values per iteration of the target, which draws these quantities from
+    /// its outputs in order, e.g. '2 uniform, 1 bounded(52), skip, 1 f64'
+    /// (also u32, normal(mean,std_dev), bernoulli(p) and geometric(p))
+    #[structopt(
+        long = "consumption",
+        parse(try_from_str = parse_consumption),
+        conflicts_with_all = &["bits", "float-scale", "normal", "discrete", "derived", "labeled", "resync", "result"]
+    )]
+    consumption: Option<Consumption>,
+
+    /// Number of outputs submitted at once to enumerated candidates while
+    /// not predicting outputs (1 to submit them one at a time)
+    #[structopt(long = "chunk-size", default_value = "64")]
+    chunk_size: usize,
+
+    /// Slide the initialization window forward up to this many times
+    #[structopt(long = "init-retries", default_value = "16")]
+    init_retries: usize,
+
+    /// Tolerate up to this many inconsistent outputs after initialization
+    #[structopt(long = "max-errors", default_value = "0")]
+    max_errors: usize,
+
+    /// After recovery, skip up to this many unobserved outputs to resync
+    #[structopt(long = "resync", default_value = "0")]
+    resync: usize,
+
+    /// Scan this memory dump for states matching the candidates on exit
+    #[structopt(long = "scan-memory", parse(from_os_str))]
+    scan_memory: Option<PathBuf>,
+
+    /// After recovery, locate this later capture of outputs of the generator
+    #[structopt(long = "gap-capture", parse(from_os_str))]
+    gap_capture: Option<PathBuf>,
+
+    /// Field order of the generator struct in the memory dump
+    #[structopt(long = "struct-layout", default_value = "state,inc")]
+    struct_layout: StructLayout,
+
+    /// Alignment of the generator struct in the memory dump
+    #[structopt(long = "scan-align", default_value = "8")]
+    scan_align: usize,
+
+    /// After recovery, look this many outputs back for a plausible seed
+    #[structopt(long = "seed-search")]
+    seed_search: Option<u64>,
+
+    /// Read lines of the form `label value`, tracking one generator per label
+    #[structopt(long = "labeled", conflicts_with_all = &["binary", "seed-candidates"])]
+    labeled: bool,
+
+    /// Stop reading outputs as soon as the state is recovered, estimating how
+    /// many more are needed
+    #[structopt(long = "oracle", conflicts_with_all = &["no-recovery", "labeled"])]
+    oracle: bool,
+
+    /// Solver tracking the generator, `table` for the table (see `pcg-breaker
+    /// solvers` for the others, with which only the options reading outputs,
+    /// --inc-equals, --threshold and --no-predictions apply)
+    #[structopt(long = "solver", default_value = "table")]
+    solver: String,
+
+    /// Prune enumerated candidates on a GPU through OpenCL whenever there are
+    /// at least 2^20 of them, which makes a threshold in the tens of millions
+    /// practical
+    #[cfg(feature = "gpu")]
+    #[structopt(long = "gpu", conflicts_with = "labeled")]
+    gpu: bool,
+
+    /// Prune enumerated candidates one at a time, even if the CPU supports
+    /// the AVX2 or AVX-512 paths, e.g. to rule them out while debugging
+    #[structopt(long = "force-scalar")]
+    force_scalar: bool,
+
+    /// Report after each output how many bits of uncertainty about the state
+    /// are left, from the number of candidates
+    #[structopt(long = "entropy")]
+    entropy: bool,
+
+    /// Read the outputs from this list, separated by whitespace or commas,
+    /// instead of standard input
+    #[structopt(
+        long = "inline",
+        conflicts_with_all = &["oracle-command", "labeled", "binary", "tolerant-numbers"],
+        parse(try_from_str = parse_inline)
+    )]
+    inline: Option<InlineOutputs>,
+
+    /// Read the outputs from this log file instead of standard input, waiting
+    /// for more to be appended to it like `tail -F`
+    #[structopt(
+        long = "follow",
+        conflicts_with_all = &["oracle-command", "inline", "labeled", "binary", "buffer"],
+        parse(from_os_str)
+    )]
+    follow: Option<PathBuf>,
+
+    /// Replay outputs recorded in this file as JSON lines along with when
+    /// they were observed, such as `{"time": 1700000000.25, "output": 42}`,
+    /// waiting as long between them as they originally took
+    #[structopt(
+        long = "replay-timing",
+        conflicts_with_all = &["oracle-command", "inline", "labeled", "binary", "buffer", "follow"],
+        parse(from_os_str)
+    )]
+    replay_timing: Option<PathBuf>,
+
+    /// How many times faster than recorded to replay outputs with
+    /// --replay-timing
+    #[structopt(long = "replay-speed", default_value = "1", parse(try_from_str = parse_replay_speed))]
+    replay_speed: f64,
+
+    /// Subscribe to outputs published to a NATS subject instead of reading
+    /// standard input, given as `host:port/subject`
+    #[cfg(feature = "nats")]
+    #[structopt(
+        long = "nats",
+        conflicts_with_all = &["oracle-command", "inline", "labeled", "binary", "buffer", "follow"]
+    )]
+    nats: Option<String>,
+
+    /// Receive outputs sent by a WebSocket server at this ws:// or wss://
+    /// URL instead of reading standard input
+    #[cfg(feature = "websocket")]
+    #[structopt(
+        long = "ws",
+        conflicts_with_all = &["oracle-command", "inline", "labeled", "binary", "buffer", "follow"]
+    )]
+    ws: Option<String>,
+
+    /// Path to the outputs in each JSON message received with --ws, such as
+    /// `data.rolls[0]`
+    #[cfg(feature = "websocket")]
+    #[structopt(long = "ws-field", requires = "ws")]
+    ws_field: Option<String>,
+
+    /// Poll an HTTP endpoint returning outputs instead of reading standard
+    /// input, in which `{n}` is replaced by the number of the request
+    #[cfg(feature = "http")]
+    #[structopt(
+        long = "http-poll",
+        conflicts_with_all = &["oracle-command", "inline", "labeled", "binary", "buffer", "follow"]
+    )]
+    http_poll: Option<String>,
+
+    /// Minimum number of milliseconds between requests made with --http-poll
+    #[cfg(feature = "http")]
+    #[structopt(long = "interval", default_value = "1000")]
+    interval: u64,
+
+    /// Path to the outputs in each JSON response polled with --http-poll,
+    /// such as `data.rolls[0]`
+    #[cfg(feature = "http")]
+    #[structopt(long = "extract", requires = "http-poll")]
+    extract: Option<String>,
+
+    /// Query each output by running this shell command (implies --oracle)
+    #[structopt(long = "oracle-command", conflicts_with_all = &["no-recovery", "labeled"])]
+    oracle_command: Option<String>,
+
+    /// After recovery, report the seed rand's PCG-based SmallRng was created with
+    #[structopt(long = "rand-seed")]
+    rand_seed: bool,
+
+    /// After recovery, report the seed pcg-cpp's pcg32 was constructed with
+    #[structopt(long = "pcg-cpp")]
+    pcg_cpp: bool,
+
+    /// Keep only increments which can be the address of a pcg-c pcg32_unique
+    /// generator, and report where it is in memory after recovery
+    #[structopt(long = "unique")]
+    unique: bool,
+
+    /// After recovery, print the future tokens (`uuid`, `nonce` or `u64`) the
+    /// target will build from its outputs
+    #[structopt(long = "tokens")]
+    tokens: Option<TokenKind>,
+
+    /// Byte order of outputs within tokens (`little` or `big`)
+    #[structopt(long = "token-order", default_value = "little")]
+    token_order: ByteOrder,
+
+    /// Number of future tokens to print
+    #[structopt(long = "token-count", default_value = "8")]
+    token_count: usize,
+
+    /// Also predict what pcg32_boundedrand will return for this bound
+    #[structopt(long = "bounded", parse(try_from_str = parse_bound))]
+    bounded: Option<u32>,
+
+    /// After recovery, predict samples drawn by this procedure, e.g.
+    /// 'weighted(common=70,rare=25,epic=5)', 'uniform(1,7)' or 'normal(100,15)'
+    #[structopt(long = "sample", parse(try_from_str = parse_sampler))]
+    sample: Option<Sampler>,
+
+    /// Number of future samples to predict
+    #[structopt(long = "sample-count", default_value = "8")]
+    sample_count: usize,
+
+    /// Write each predicted output to stdout as a raw little-endian u32,
+    /// printing everything else to stderr
+    #[structopt(long = "emit-binary", conflicts_with_all = &["no-predictions", "labeled"])]
+    emit_binary: bool,
+
+    /// Fault in all of the table's memory in one pass before loading it
+    #[structopt(long = "prefault")]
+    prefault: bool,
+
+    /// Ask for the table to be backed by transparent huge pages (Linux only)
+    #[structopt(long = "hugepages")]
+    hugepages: bool,
+
+    /// Map the table files into memory rather than reading them, so that
+    /// only the entries queried are paged in, and can be paged out again
+    /// under memory pressure (Unix only)
+    #[structopt(long = "mmap")]
+    mmap: bool,
+
+    /// Don't report progress while loading the table and initializing
+    #[structopt(long = "no-progress")]
+    no_progress: bool,
+
+    /// Seed for internal randomness, to make runs reproducible
+    #[structopt(long = "seed", parse(try_from_str = parse::<u64>))]
+    seed: Option<u64>,
+
+    /// Print completions for this shell (bash, zsh, fish, powershell or
+    /// elvish) and exit
+    #[structopt(long = "completions", possible_values = &Shell::variants())]
+    completions: Option<Shell>,
+
+    /// Format of the summary printed at exit (`text` or `json`)
+    #[structopt(long = "format", default_value = "text")]
+    format: SummaryFormat,
+
+    /// Read defaults for any flag from this file instead of pcg-breaker.toml
+    #[structopt(long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    /// Also read defaults from this section of the configuration file
+    #[structopt(long = "profile")]
+    profile: Option<String>,
+
+    /// Path to the precomputed table (required without --seed-candidates,
+    /// unless built with the generate-table feature)
+    #[structopt(parse(from_os_str))]
+    table: Option<PathBuf>,
+
+    /// Another precomputed table, made for any stride (may be given several
+    /// times): the table of each stride the run needs, including those tried
+    /// by --max-stride and --race, is the one given holding its sample
+    /// entries, and is only generated if none does
+    #[structopt(long = "table", number_of_values = 1, parse(from_os_str))]
+    tables: Vec<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Subcommand>,
+}
+
+/// Subcommands run instead of following a generator.
+#[derive(StructOpt)]
+enum Subcommand {
+    /// Inspect precomputed tables
+    Table(TableCommand),
+    /// Generate inputs for measuring performance
+    Bench(BenchCommand),
+    /// Estimate how many outputs predicting outputs and recovering the state
+    /// take when they are observed through a model, and how long on this
+    /// machine, by attacking random generators
+    Estimate {
+        /// Path to the table
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// How outputs are observed after the four initializing the
+        /// predictor: `u32` as they are, `low:K` for their lowest K bits,
+        /// `mod:N`, `float:N` (see --float-scale), `parity`, `top` or `bool`
+        /// (see --bits), or `xor` or `sub` (see --derived)
+        #[structopt(long = "model", default_value = "u32", parse(try_from_str = parse_model))]
+        model: ObservationModel,
+
+        /// Number of generators attacked
+        #[structopt(long = "trials", default_value = "8")]
+        trials: usize,
+
+        /// Number of observations after which a generator is given up on
+        #[structopt(long = "max-observations", default_value = "100000")]
+        max_observations: usize,
+
+        /// Seed from which the generators are drawn
+        #[structopt(long = "seed", default_value = "0", parse(try_from_str = parse::<u64>))]
+        seed: u64,
+    },
+    /// Compare two interpretations of a capture, reporting which explains
+    /// more of it and where they diverge
+    Compare {
+        /// Capture to compare the interpretations on, one value per line
+        #[structopt(parse(from_os_str))]
+        capture: PathBuf,
+
+        /// First interpretation, as comma-separated settings: `stride:K`,
+        /// `byteswap` and `table:<path>` for the table made for its stride
+        /// (generated in memory if not given)
+        #[structopt(long = "first", default_value = "stride:1", parse(try_from_str = parse_contender))]
+        first: Contender,
+
+        /// Second interpretation, as for --first
+        #[structopt(long = "second", parse(try_from_str = parse_contender))]
+        second: Contender,
+
+        /// Read raw native-endian u32 values instead of one number per line
+        #[structopt(long = "binary")]
+        binary: bool,
+    },
+    /// Render a binary capture as a hex dump, labeling each word with the
+    /// output of a recovered generator it is, if any
+    Annotate {
+        /// Binary capture of raw native-endian u32 words
+        #[structopt(parse(from_os_str))]
+        capture: PathBuf,
+
+        /// Result written with --result, whose recovered state produced the
+        /// first output of the capture
+        #[structopt(long = "result", parse(from_os_str), conflicts_with_all = &["state", "inc"])]
+        result: Option<PathBuf>,
+
+        /// State which produced the first output of the capture
+        #[structopt(long = "state", requires = "inc", parse(try_from_str = parse::<u64>))]
+        state: Option<u64>,
+
+        /// Increment of the generator
+        #[structopt(long = "inc", requires = "state", parse(try_from_str = parse::<u64>))]
+        inc: Option<u64>,
+
+        /// Number of outputs looked ahead for a word which isn't the output
+        /// predicted, to label it after a gap of unobserved outputs
+        #[structopt(long = "resync", default_value = "64")]
+        resync: usize,
+
+        /// Words are in the opposite byte order to the native one
+        #[structopt(long = "byteswap")]
+        byteswap: bool,
+    },
+    /// Ask what was observed of the target, then build and run the matching
+    /// command line, printing it for later runs
+    Wizard,
+    /// List the solvers which --solver can select
+    Solvers,
+    /// Check a result written with --result: that its outputs match their
+    /// hash and are all produced by the recovered state
+    VerifyResult {
+        /// Path to the result
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Recover the state of a PCG member with 64-bit state and 64-bit
+    /// outputs, which leak their whole state, from two or more consecutive
+    /// outputs on standard input
+    Invertible {
+        /// Output function of the generator (`xsl-rr-rr` or `rxs-m-xs`)
+        #[structopt(long = "output-function", parse(try_from_str = parse_invertible_64))]
+        function: Invertible64,
+        /// Number of outputs to predict after the last one read
+        #[structopt(long = "predict", default_value = "8")]
+        predict: usize,
+        /// Read doubles in [0, 1) drawn from the top 53 bits of each output,
+        /// as in (out[i] >> 11) / 2^53, instead of the outputs (at least 3)
+        #[structopt(long = "doubles")]
+        doubles: bool,
+        /// The doubles are drawn this many at a time into a cache and served
+        /// from it last first, as V8 does for Math.random
+        #[structopt(long = "cache", requires = "doubles", parse(try_from_str = parse_cache_size))]
+        cache: Option<usize>,
+    },
+}
+
+#[derive(StructOpt)]
+enum TableCommand {
+    /// Print statistics about a table, e.g. to check a table made elsewhere
+    Info {
+        /// Path to the table
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Stride the table was made for (see gen-table --stride)
+        #[structopt(long = "stride", default_value = "1")]
+        stride: u64,
+    },
+}
+
+#[derive(StructOpt)]
+enum BenchCommand {
+    /// Print generators which are the slowest to initialize a predictor for,
+    /// the same ones for the same seed
+    Corpus {
+        /// Number of generators to print
+        #[structopt(long = "count", default_value = "8")]
+        count: usize,
+
+        /// Seed from which the generators are drawn
+        #[structopt(long = "seed", default_value = "0", parse(try_from_str = parse::<u64>))]
+        seed: u64,
+
+        /// Also write this many outputs of each generator to a file in this
+        /// directory, one per line
+        #[structopt(long = "output-dir", parse(from_os_str))]
+        output_dir: Option<PathBuf>,
+
+        /// Number of outputs written for each generator
+        #[structopt(long = "outputs", default_value = "1000")]
+        outputs: usize,
+    },
+    /// Run a capture against the table set up in each way available, and
+    /// compare the time and memory each takes
+    Compare {
+        /// Path to the table
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Capture to run, one output per line
+        #[structopt(parse(from_os_str))]
+        capture: PathBuf,
+
+        /// Read raw native-endian u32 outputs instead of one number per line
+        #[structopt(long = "binary")]
+        binary: bool,
+
+        /// Number of candidates from which they are explicitly enumerated
+        #[structopt(long = "threshold", default_value = "1000")]
+        threshold: usize,
+    },
+}
+
+const ASCII_HEADER: &str = r#"
+  ____   ___  ___    ____  ____  ____   __   __ _  ____  ____
+ (  _ \ / __)/ __)  (  _ \(  _ \(  __) / _\ (  / )(  __)(  _ \
+  ) __/( (__( (_ \   ) _ ( )   / ) _) /    \ )  (  ) _)  )   /
+ (__)   \___)\___/  (____/(__\_)(____)\_/\_/(__\_)(____)(__\_)
+
+         PCG-XSH-RR Output Prediction & State Recovery
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns every order the items can come in.
+    fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+        if items.is_empty() {
+            return vec![vec![]];
+        }
+
+        let mut orders = vec![];
+
+        for (index, item) in items.iter().enumerate() {
+            let mut rest = items.to_vec();
+            rest.remove(index);
+
+            for mut order in permutations(&rest) {
+                order.insert(0, item.clone());
+                orders.push(order);
+            }
+        }
+
+        orders
+    }
+
+    /// Settles interpretations finishing in the given order, returning the
+    /// winner and those ruled out before it.
+    fn settle(
+        finishing: &[Hypothesis],
+        consistent: &[Hypothesis],
+    ) -> (Option<Hypothesis>, Vec<Hypothesis>) {
+        let mut order = RaceOrder::new(1..=3);
+        let mut ruled_out = vec![];
+
+        for &hypothesis in finishing {
+            let result = consistent.contains(&hypothesis).then_some(hypothesis);
+
+            if let Some((winner, result)) = order.finish(hypothesis, result, |h| ruled_out.push(h))
+            {
+                assert_eq!(winner, result);
+                return (Some(winner), ruled_out);
+            }
+        }
+
+        (None, ruled_out)
+    }
+
+    fn hypothesis(stride: usize, byteswap: bool) -> Hypothesis {
+        Hypothesis { stride, byteswap }
+    }
+
+    #[test]
+    fn race_winner_is_independent_of_completion_order() {
+        let all: Vec<Hypothesis> = (1..=3)
+            .flat_map(|stride| [false, true].map(|byteswap| hypothesis(stride, byteswap)))
+            .collect();
+
+        let cases = [
+            (vec![hypothesis(1, false)], Some(hypothesis(1, false))),
+            (
+                vec![hypothesis(1, true), hypothesis(2, false)],
+                Some(hypothesis(1, true)),
+            ),
+            (
+                vec![
+                    hypothesis(3, false),
+                    hypothesis(2, true),
+                    hypothesis(3, true),
+                ],
+                Some(hypothesis(2, true)),
+            ),
+            (vec![hypothesis(3, true)], Some(hypothesis(3, true))),
+            (all.clone(), Some(hypothesis(1, false))),
+            (vec![], None),
+        ];
+
+        for (consistent, expected) in cases {
+            let ruled_out: Vec<Hypothesis> = (all.iter().copied())
+                .take_while(|&h| Some(h) != expected)
+                .collect();
+
+            for finishing in permutations(&all) {
+                assert_eq!(
+                    settle(&finishing, &consistent),
+                    (expected, ruled_out.clone()),
+                    "finishing in the order {:?}",
+                    finishing
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn race_waits_for_earlier_interpretations() {
+        let mut order = RaceOrder::new(1..=2);
+        let mut ruled_out = vec![];
+
+        // a later consistent interpretation can't win while an earlier one
+        // is still running, however long it takes
+        assert_eq!(
+            order.finish(hypothesis(2, false), Some(()), |h| ruled_out.push(h)),
+            None
+        );
+        assert_eq!(
+            order.finish(hypothesis(1, true), None, |h| ruled_out.push(h)),
+            None
+        );
+        assert_eq!(
+            order.finish(hypothesis(1, false), None, |h| ruled_out.push(h)),
+            Some((hypothesis(2, false), ()))
+        );
+        assert_eq!(ruled_out, [hypothesis(1, false), hypothesis(1, true)]);
+    }
+
+    #[test]
+    fn config_comments_stop_at_quotes() {
+        let sections = parse_config(concat!(
+            "# a comment\n",
+            "ws-field = \"$.data#rolls\" # the field\n",
+            "extract = 'a#b'\n",
+            "http-poll = \"http://host/say \\\"#hi\\\"\"#\n",
+            "\n",
+            "[fast] # a profile\n",
+            "no-predictions = true\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            sections[""],
+            [
+                ("ws-field".into(), ConfigValue::Text("$.data#rolls".into())),
+                ("extract".into(), ConfigValue::Text("a#b".into())),
+                (
+                    "http-poll".into(),
+                    ConfigValue::Text("http://host/say \"#hi\"".into())
+                ),
+            ]
+        );
+        assert_eq!(
+            sections["fast"],
+            [("no-predictions".into(), ConfigValue::Bool(true))]
+        );
+    }
+
+    #[test]
+    fn config_arrays_split_outside_quotes() {
+        let sections =
+            parse_config("labels = [\"a,b\", 'c, d', e, \"f\\\",\"] # g, h\nempty = []\n").unwrap();
+
+        assert_eq!(
+            sections[""],
+            [
+                (
+                    "labels".into(),
+                    ConfigValue::Array(vec![
+                        "a,b".into(),
+                        "c, d".into(),
+                        "e".into(),
+                        "f\",".into()
+                    ])
+                ),
+                ("empty".into(), ConfigValue::Array(vec![])),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_config_lines_are_rejected() {
+        for text in ["threshold\n", "labels = [a b]\n", "extract = two words\n"] {
+            assert!(parse_config(text).is_err(), "{:?} was accepted", text);
+        }
+    }
+
+    #[test]
+    fn final_reductions_are_taken_off_transforms() {
+        let parse = |text: &str| -> Vec<Transform> {
+            text.split(',')
+                .map(|step| parse_transform(step).unwrap())
+                .collect()
+        };
+
+        let mut transforms = parse("sub(1),mod(1000)");
+        let reduction = take_reduction(&mut transforms).unwrap();
+        assert!(matches!(reduction, Some(ObservationModel::Modulo(1000))));
+        assert!(matches!(transforms[..], [Transform::Sub(1)]));
+
+        let mut transforms = parse("xor(0xff),mask(0xffff)");
+        let reduction = take_reduction(&mut transforms).unwrap();
+        assert!(matches!(reduction, Some(ObservationModel::LowBits(16))));
+        assert_eq!(transforms.len(), 1);
+
+        // steps which don't lose bits are left alone
+        let mut transforms = parse("mul(3),rotl(7),shl(32),mask(0xffffffff)");
+        assert!(take_reduction(&mut transforms).unwrap().is_none());
+        assert_eq!(transforms.len(), 4);
+
+        for text in [
+            "mask(0xff00)",
+            "shr(16)",
+            "mod(10),add(1)",
+            "mul(2)",
+            "div(3)",
+        ] {
+            let mut transforms = parse(text);
+            assert!(
+                take_reduction(&mut transforms).is_err(),
+                "{} was accepted",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn decimal_digits_are_read_both_ways() {
+        assert_eq!(parse_reading("0x1234").unwrap(), Reading::Exact(0x1234));
+        assert_eq!(parse_reading("5").unwrap(), Reading::Exact(5));
+        assert_eq!(
+            parse_reading("2468a5eb").unwrap(),
+            Reading::PlainHex(0x2468_a5eb)
+        );
+
+        let digits = |decimal, hex, fixed_width| Reading::Digits {
+            decimal,
+            hex,
+            fixed_width,
+        };
+
+        assert_eq!(
+            parse_reading("67539161").unwrap(),
+            digits(67_539_161, 0x6753_9161, true)
+        );
+        assert_eq!(
+            parse_reading("00012345").unwrap(),
+            digits(12345, 0x12345, true)
+        );
+        assert_eq!(parse_reading("1234").unwrap(), digits(1234, 0x1234, false));
+
+        // too many digits for hexadecimal
+        assert_eq!(
+            parse_reading("4294967295").unwrap(),
+            Reading::Exact(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn sha256_matches_fips_180_4() {
+        // the examples of FIPS 180-4, and the 55 and 56 byte messages whose
+        // length only fits in the first or second padding block
+        let vectors: [(Vec<u8>, &str); 7] = [
+            (
+                b"abc".to_vec(),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"".to_vec(),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+            (
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu".to_vec(),
+                "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+            ),
+            (
+                vec![b'a'; 1_000_000],
+                "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+            ),
+            (
+                vec![b'a'; 55],
+                "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318",
+            ),
+            (
+                vec![b'a'; 56],
+                "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a",
+            ),
+        ];
+
+        for (message, digest) in &vectors {
+            assert_eq!(
+                hex_string(&sha256(message)),
+                *digest,
+                "{} bytes",
+                message.len()
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! The arithmetic of PCG-XSH-RR itself: its constants, its output function
+//! and the inverse of it, and stepping the underlying LCG forwards, backwards
+//! or by any number of steps. None of it needs a lookup table, so it can be
+//! used on its own to check or replay a generator.
+//!
+//! The LCG steps a 64-bit state to `A * state + inc`, with an odd increment
+//! `inc` selecting one of 2^63 streams, and every output is computed from
+//! the state before the step.
+
+use crate::Pcg32State;
+use std::num::Wrapping as W;
+
+pub use crate::jump::JumpTable;
+
+/// The multiplicative constant from PCG-XSH-RR
+pub const A: W<u64> = W(6_364_136_223_846_793_005);
+
+/// The multiplicative inverse of A (modulo 2^64)
+///
+/// ```
+/// use pcg_breaker::pcg_math::{A, A_INV};
+/// use std::num::Wrapping as W;
+///
+/// assert_eq!(A * A_INV, W(1));
+/// ```
+pub const A_INV: W<u64> = W(13_877_824_140_714_322_085);
+
+/// XSH-RR output transformation from PCG: the top 5 bits of the state give a
+/// rotation, applied to bits 27 to 58 of the state xorshifted by 18 bits.
+///
+/// The first output of the reference `pcg32` demo, seeded with 42 on stream
+/// 54, and outputs of states whose rotation is 0 (none) or 31:
+///
+/// ```
+/// use pcg_breaker::pcg_math::{advance, compute_xsh_rr};
+/// use std::num::Wrapping as W;
+///
+/// let inc = W((54 << 1) | 1);
+/// let state = advance(W(42) + inc, inc);
+///
+/// assert_eq!(compute_xsh_rr(state), 0xa15c_02b7);
+///
+/// assert_eq!(compute_xsh_rr(W(0)), 0);
+/// assert_eq!(compute_xsh_rr(W(0x07ff_ffff)), 0);
+/// assert_eq!(compute_xsh_rr(W(1 << 27)), 1);
+/// assert_eq!(compute_xsh_rr(W(31 << 59 | 1 << 27)), 0x000f_8002);
+/// assert_eq!(compute_xsh_rr(W(u64::MAX)), 0xfff0_0001);
+/// ```
+pub fn compute_xsh_rr(state: W<u64>) -> u32 {
+    let xorshifted = ((state >> 18) ^ state) >> 27;
+    let rotation = (state >> 59).0 as u32;
+
+    (xorshifted.0 as u32).rotate_right(rotation)
+}
+
+/// Inverts XSH-RR given one particular rotation guess, returning the top 37
+/// bits of the only states with that rotation producing `output`; their low
+/// 27 bits are left clear, as the output doesn't depend on them.
+///
+/// ```
+/// use pcg_breaker::pcg_math::{compute_xsh_rr, invert_xsh_rr};
+/// use std::num::Wrapping as W;
+///
+/// for rotation in 0..32 {
+///     for output in [0, 1, 0x8000_0000, 0xdead_beef, u32::MAX] {
+///         let state = invert_xsh_rr(rotation, output);
+///
+///         assert_eq!(state >> 59, W(rotation as u64));
+///         assert_eq!(state & W(0x07ff_ffff), W(0));
+///
+///         for low in [0, 1, 0x07ff_ffff] {
+///             assert_eq!(compute_xsh_rr(state | W(low)), output);
+///         }
+///     }
+/// }
+/// ```
+pub fn invert_xsh_rr(rotation: u32, output: u32) -> W<u64> {
+    let mut state = W(rotation as u64) << 59;
+
+    let recovered = W(output.rotate_left(rotation) as u64);
+
+    state |= (recovered >> 19) << 46;
+
+    state |= (((recovered >> 1) ^ (state >> 46)) & W(0x3ffff)) << 28;
+
+    state |= ((recovered ^ (state >> 45)) & W(1)) << 27;
+
+    state
+}
+
+/// Steps a state forwards once.
+///
+/// ```
+/// use pcg_breaker::pcg_math::{advance, A};
+/// use std::num::Wrapping as W;
+///
+/// assert_eq!(advance(W(0), W(1)), W(1));
+/// assert_eq!(advance(W(1), W(1)), A + W(1));
+/// assert_eq!(advance(W(u64::MAX), W(1)), W(1) - A);
+/// ```
+pub fn advance(state: W<u64>, inc: W<u64>) -> W<u64> {
+    A * state + inc
+}
+
+/// Steps a state backwards once, undoing `advance`.
+///
+/// ```
+/// use pcg_breaker::pcg_math::{advance, rewind};
+/// use std::num::Wrapping as W;
+///
+/// for state in [0, 1, 1 << 63, u64::MAX] {
+///     for inc in [1, 0x1234_5679, u64::MAX] {
+///         let (state, inc) = (W(state), W(inc));
+///
+///         assert_eq!(rewind(advance(state, inc), inc), state);
+///         assert_eq!(advance(rewind(state, inc), inc), state);
+///     }
+/// }
+/// ```
+pub fn rewind(state: W<u64>, inc: W<u64>) -> W<u64> {
+    A_INV * (state - inc)
+}
+
+/// Advances a state by `delta` steps, in at most 64 multiplications (see
+/// `JumpTable`). As the LCG has a period of 2^64, jumping by `u64::MAX`
+/// steps rewinds it by one step.
+///
+/// ```
+/// use pcg_breaker::pcg_math::{advance, jump, rewind};
+/// use std::num::Wrapping as W;
+///
+/// let (state, inc) = (W(0x1234_5678_90ab_cdef), W(0x1234_5679));
+///
+/// assert_eq!(jump(state, inc, 0), state);
+/// assert_eq!(jump(state, inc, 1), advance(state, inc));
+/// assert_eq!(jump(state, inc, 3), advance(advance(advance(state, inc), inc), inc));
+/// assert_eq!(jump(state, inc, u64::MAX), rewind(state, inc));
+/// assert_eq!(jump(jump(state, inc, 1 << 63), inc, 1 << 63), state);
+/// ```
+pub fn jump(state: W<u64>, inc: W<u64>, delta: u64) -> W<u64> {
+    let (mult, plus) = JumpTable::shared().constants(delta);
+
+    mult * state + plus * inc
+}
+
+/// Returns the number of steps needed to advance a state to `target`, which
+/// every state reaches as the LCG has a period of 2^64 for an odd increment.
+///
+/// ```
+/// use pcg_breaker::pcg_math::{distance, jump};
+/// use std::num::Wrapping as W;
+///
+/// let (state, inc) = (W(0x1234_5678_90ab_cdef), W(0x1234_5679));
+///
+/// for delta in [0, 1, 2, 1000, 1 << 63, u64::MAX] {
+///     assert_eq!(distance(state, inc, jump(state, inc, delta)), delta);
+/// }
+/// ```
+pub fn distance(state: W<u64>, inc: W<u64>, target: W<u64>) -> u64 {
+    JumpTable::shared().distance(&Pcg32State { state, inc }, target)
+}
@@ -0,0 +1,271 @@
+//! Vectorized pruning of enumerated candidates, dispatched at run time to
+//! the widest instruction set the CPU supports, with a scalar fallback.
+
+use crate::Pcg32State;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set to prune with the scalar path whatever the CPU supports.
+static FORCE_SCALAR: AtomicBool = AtomicBool::new(false);
+
+/// A way of testing candidates against an output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimdPath {
+    /// One candidate at a time, on any CPU.
+    Scalar,
+    /// Four candidates at a time, on x86-64 CPUs with AVX2.
+    Avx2,
+    /// Eight candidates at a time, on x86-64 CPUs with AVX-512F and DQ.
+    Avx512,
+}
+
+impl SimdPath {
+    /// Returns the path candidates are pruned with: the widest supported by
+    /// the CPU, unless `set_force_scalar` forced the scalar one.
+    pub fn current() -> Self {
+        match FORCE_SCALAR.load(Ordering::Relaxed) {
+            true => Self::Scalar,
+            false => Self::supported()[0],
+        }
+    }
+
+    /// Returns the paths the CPU supports, widest first (the scalar path is
+    /// always supported, and comes last).
+    pub fn supported() -> Vec<Self> {
+        let mut paths = vec![];
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512dq") {
+                paths.push(Self::Avx512);
+            }
+
+            if is_x86_feature_detected!("avx2") {
+                paths.push(Self::Avx2);
+            }
+        }
+
+        paths.push(Self::Scalar);
+        paths
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scalar => "scalar",
+            Self::Avx2 => "AVX2",
+            Self::Avx512 => "AVX-512",
+        }
+    }
+
+    /// Returns the index of the candidates whose next output is `output`, in
+    /// order, falling back to the scalar path if the CPU doesn't support this
+    /// one. Every path gives the same indices:
+    ///
+    /// ```
+    /// use pcg_breaker::{Pcg32State, SimdPath};
+    ///
+    /// let mut generator = Pcg32State::new(0x1234_5678_90ab_cdef, 0x1234_5679);
+    ///
+    /// // candidates sharing their next output, among random ones, at every
+    /// // position in a vector and in its scalar tail
+    /// let target = Pcg32State::new(42, 7);
+    /// let mut states: Vec<Pcg32State> = (0..1003)
+    ///     .map(|_| Pcg32State::new(generator.next_u32() as u64, generator.next_u32() as u64))
+    ///     .collect();
+    ///
+    /// for index in [0, 3, 4, 7, 8, 15, 500, 1001, 1002] {
+    ///     states[index] = target;
+    /// }
+    ///
+    /// let scalar = SimdPath::Scalar.find_matches(&states, target.next_output());
+    /// assert_eq!(scalar, [0, 3, 4, 7, 8, 15, 500, 1001, 1002]);
+    ///
+    /// for path in SimdPath::supported() {
+    ///     for output in [target.next_output(), states[1].next_output(), 0, u32::MAX] {
+    ///         assert_eq!(
+    ///             path.find_matches(&states, output),
+    ///             SimdPath::Scalar.find_matches(&states, output),
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    pub fn find_matches(self, states: &[Pcg32State], output: u32) -> Vec<usize> {
+        let mut matches = vec![];
+
+        // the vectorized paths leave the candidates not filling a vector
+        let start = match self {
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2 if is_x86_feature_detected!("avx2") => unsafe {
+                x86::find_matches_avx2(states, output, &mut matches)
+            },
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx512
+                if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512dq") =>
+            unsafe { x86::find_matches_avx512(states, output, &mut matches) },
+            _ => 0,
+        };
+
+        matches
+            .extend((start..states.len()).filter(|&index| states[index].next_output() == output));
+
+        matches
+    }
+}
+
+/// Makes candidates be pruned with the scalar path whatever the CPU supports
+/// (or with the widest path again), e.g. to rule out the vectorized paths
+/// while debugging.
+pub fn set_force_scalar(force: bool) {
+    FORCE_SCALAR.store(force, Ordering::Relaxed);
+}
+
+/// Keeps the candidates whose next output is `output`, advanced past it, or
+/// returns false and leaves them as they were if there are none.
+pub(crate) fn retain_next_output(states: &mut Vec<Pcg32State>, output: u32) -> bool {
+    let matches = SimdPath::current().find_matches(states, output);
+
+    if matches.is_empty() {
+        return false;
+    }
+
+    *states = matches
+        .into_iter()
+        .map(|index| {
+            let mut state = states[index];
+            state.advance();
+            state
+        })
+        .collect();
+
+    true
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use crate::{Pcg32State, A};
+    use std::arch::x86_64::*;
+
+    /// Pushes the index of the candidates whose next output is `output`, in
+    /// order, four at a time, returning the index of the first candidate left
+    /// untested.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn find_matches_avx2(
+        states: &[Pcg32State],
+        output: u32,
+        matches: &mut Vec<usize>,
+    ) -> usize {
+        // the AVX2 multiplication is only 32 by 32 bits, so the 64-bit
+        // product is put together from the low and high halves of each side
+        let (mult_low, mult_high) = (
+            _mm256_set1_epi64x(A.0 as u32 as i64),
+            _mm256_set1_epi64x((A.0 >> 32) as i64),
+        );
+
+        let low_half = _mm256_set1_epi64x(u32::MAX as i64);
+        let bits = _mm256_set1_epi64x(32);
+        let expected = _mm256_set1_epi64x(output as i64);
+
+        let chunks = states.chunks_exact(4);
+        let tested = states.len() - chunks.remainder().len();
+
+        for (chunk, lanes) in chunks.enumerate() {
+            let mut words = [[0i64; 4]; 2];
+
+            for (k, state) in lanes.iter().enumerate() {
+                words[0][k] = state.state.0 as i64;
+                words[1][k] = state.inc.0 as i64;
+            }
+
+            let state = _mm256_loadu_si256(words[0].as_ptr() as *const __m256i);
+            let inc = _mm256_loadu_si256(words[1].as_ptr() as *const __m256i);
+
+            let cross = _mm256_add_epi64(
+                _mm256_mul_epu32(_mm256_srli_epi64(state, 32), mult_low),
+                _mm256_mul_epu32(state, mult_high),
+            );
+
+            let next = _mm256_add_epi64(
+                _mm256_add_epi64(
+                    _mm256_mul_epu32(state, mult_low),
+                    _mm256_slli_epi64(cross, 32),
+                ),
+                inc,
+            );
+
+            let xorshifted = _mm256_and_si256(
+                _mm256_srli_epi64(_mm256_xor_si256(_mm256_srli_epi64(next, 18), next), 27),
+                low_half,
+            );
+
+            let rotation = _mm256_srli_epi64(next, 59);
+
+            let rotated = _mm256_or_si256(
+                _mm256_srlv_epi64(xorshifted, rotation),
+                _mm256_and_si256(
+                    _mm256_sllv_epi64(xorshifted, _mm256_sub_epi64(bits, rotation)),
+                    low_half,
+                ),
+            );
+
+            let mask =
+                _mm256_movemask_pd(_mm256_castsi256_pd(_mm256_cmpeq_epi64(rotated, expected)));
+
+            push_lanes(matches, chunk * 4, mask as u32);
+        }
+
+        tested
+    }
+
+    /// Pushes the index of the candidates whose next output is `output`, in
+    /// order, eight at a time, returning the index of the first candidate
+    /// left untested.
+    #[target_feature(enable = "avx512f,avx512dq")]
+    pub unsafe fn find_matches_avx512(
+        states: &[Pcg32State],
+        output: u32,
+        matches: &mut Vec<usize>,
+    ) -> usize {
+        let mult = _mm512_set1_epi64(A.0 as i64);
+        let low_half = _mm512_set1_epi64(u32::MAX as i64);
+        let expected = _mm512_set1_epi64(output as i64);
+
+        let chunks = states.chunks_exact(8);
+        let tested = states.len() - chunks.remainder().len();
+
+        for (chunk, lanes) in chunks.enumerate() {
+            let mut words = [[0i64; 8]; 2];
+
+            for (k, state) in lanes.iter().enumerate() {
+                words[0][k] = state.state.0 as i64;
+                words[1][k] = state.inc.0 as i64;
+            }
+
+            let state = _mm512_loadu_epi64(words[0].as_ptr());
+            let inc = _mm512_loadu_epi64(words[1].as_ptr());
+
+            let next = _mm512_add_epi64(_mm512_mullo_epi64(state, mult), inc);
+
+            let xorshifted = _mm512_and_si512(
+                _mm512_srli_epi64(_mm512_xor_si512(_mm512_srli_epi64(next, 18), next), 27),
+                low_half,
+            );
+
+            // the high half of every 64-bit lane is zero on both sides, so
+            // rotating the 32-bit halves only rotates the outputs
+            let rotated = _mm512_rorv_epi32(xorshifted, _mm512_srli_epi64(next, 59));
+
+            let mask = _mm512_cmpeq_epi64_mask(rotated, expected);
+
+            push_lanes(matches, chunk * 8, mask as u32);
+        }
+
+        tested
+    }
+
+    /// Pushes the index of each lane set in a comparison mask.
+    fn push_lanes(matches: &mut Vec<usize>, first: usize, mut mask: u32) {
+        while mask != 0 {
+            matches.push(first + mask.trailing_zeros() as usize);
+            mask &= mask - 1;
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! A uniform interface over the ways of tracking a generator, along with a
+//! registry of them, so that any of them can be selected by name from the
+//! command line or by embedders.
+
+use crate::{
+    recover_state_with_inc, CancellationToken, Candidates, LookupTable, Observer, Pcg32State,
+    Prediction, Predictor, Tracker,
+};
+use std::io::{Error, ErrorKind, Result};
+use std::num::Wrapping as W;
+use std::sync::Arc;
+
+/// Tracks a generator from its outputs, however it goes about it.
+pub trait SolverBackend: Send {
+    /// Submits the next output produced by the generator, failing if it is
+    /// inconsistent with the outputs submitted before.
+    fn submit(&mut self, output: u32) -> Result<()>;
+
+    /// Predicts the next output.
+    fn predict(&self) -> Prediction;
+
+    /// Returns the number of candidate states left.
+    fn candidate_count(&self) -> usize;
+
+    /// Returns the state which produced the last output submitted, once it
+    /// has been uniquely determined.
+    fn recovered_state(&self) -> Option<Pcg32State>;
+
+    /// Returns the candidate states, for solvers which track them.
+    fn candidates(&self) -> Option<&Candidates> {
+        None
+    }
+
+    /// Hands back the candidate states, for solvers which track them, so
+    /// that they can be tracked further with a `Tracker` of one's own.
+    fn into_candidates(self: Box<Self>) -> Option<Candidates> {
+        None
+    }
+}
+
+impl<O: Observer + Send> SolverBackend for Tracker<O> {
+    fn submit(&mut self, output: u32) -> Result<()> {
+        self.submit_next_output(output)
+    }
+
+    fn predict(&self) -> Prediction {
+        self.candidates().predict_future_output()
+    }
+
+    fn candidate_count(&self) -> usize {
+        self.candidates().count()
+    }
+
+    fn recovered_state(&self) -> Option<Pcg32State> {
+        self.candidates().recovered_state()
+    }
+
+    fn candidates(&self) -> Option<&Candidates> {
+        Some(Tracker::candidates(self))
+    }
+
+    fn into_candidates(self: Box<Self>) -> Option<Candidates> {
+        Some(Tracker::into_candidates(*self))
+    }
+}
+
+/// Called with how many of the steps of starting a solver are done, and how
+/// many there are.
+pub type SolverProgress = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// What solvers may need to get started, besides the first outputs.
+#[derive(Clone, Default)]
+pub struct SolverConfig {
+    /// The table, for solvers which query one.
+    pub table: Option<Arc<LookupTable>>,
+    /// The increment of the generator, if known.
+    pub inc: Option<u64>,
+    /// Number of candidates from which they are explicitly enumerated.
+    pub threshold: usize,
+    /// Number of threads used to enumerate candidates (one per core if zero).
+    pub threads: usize,
+    /// Where solvers which take a while to start report their progress.
+    pub progress: Option<SolverProgress>,
+    /// The token cancelling the searches of solvers which take a while,
+    /// which then fail with `ErrorKind::Interrupted`.
+    pub cancellation: CancellationToken,
+}
+
+/// Starts a solver from the first outputs of the generator, failing with
+/// `ErrorKind::InvalidData` if they are inconsistent, or with
+/// `ErrorKind::InvalidInput` if the configuration lacks what it needs.
+pub type SolverFactory = fn(&SolverConfig, &[u32]) -> Result<Box<dyn SolverBackend>>;
+
+/// A solver known to a registry.
+#[derive(Clone, Copy)]
+pub struct SolverEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Number of outputs the solver needs to get started.
+    pub outputs: usize,
+    /// Whether the solver can't get started without the table.
+    pub needs_table: bool,
+    pub factory: SolverFactory,
+}
+
+/// Solvers selectable by name. New ones are added with `register`, after
+/// which they can be selected like the built-in ones.
+#[derive(Clone, Default)]
+pub struct SolverRegistry {
+    entries: Vec<SolverEntry>,
+}
+
+impl SolverRegistry {
+    /// Creates a registry without any solver.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry of the solvers built into the library: `table`,
+    /// which tracks the generator with the table, and `known-inc`, which
+    /// searches for the state of a generator whose increment is known.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+
+        registry.register(SolverEntry {
+            name: "table",
+            description: "tracks the generator with the table, from 4 outputs",
+            outputs: 4,
+            needs_table: true,
+            factory: table_solver,
+        });
+
+        registry.register(SolverEntry {
+            name: "known-inc",
+            description: "searches the 2^32 states a known increment leaves, from 3 outputs",
+            outputs: 3,
+            needs_table: false,
+            factory: known_inc_solver,
+        });
+
+        registry
+    }
+
+    /// Adds a solver, replacing any of the same name.
+    pub fn register(&mut self, entry: SolverEntry) {
+        self.entries.retain(|existing| existing.name != entry.name);
+        self.entries.push(entry);
+    }
+
+    /// Returns the solver of some name.
+    pub fn get(&self, name: &str) -> Option<&SolverEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Returns the solvers in the order they were registered.
+    pub fn entries(&self) -> &[SolverEntry] {
+        &self.entries
+    }
+
+    /// Starts the solver of some name from the first outputs of the
+    /// generator, which must be at least as many as it needs (see the
+    /// `SolverFactory` for how it fails).
+    pub fn start(
+        &self,
+        name: &str,
+        config: &SolverConfig,
+        outputs: &[u32],
+    ) -> Result<Box<dyn SolverBackend>> {
+        let entry = self.get(name).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("no solver named `{}`", name),
+            )
+        })?;
+
+        if outputs.len() < entry.outputs {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "the `{}` solver needs at least {} outputs",
+                    name, entry.outputs
+                ),
+            ));
+        }
+
+        (entry.factory)(config, outputs)
+    }
+}
+
+fn table_solver(config: &SolverConfig, outputs: &[u32]) -> Result<Box<dyn SolverBackend>> {
+    let table = (config.table.clone())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "the `table` solver needs a table"))?;
+
+    let inconsistent = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "output sequence not produced by PCG-XSH-RR",
+        )
+    };
+
+    let initial = [outputs[0], outputs[1], outputs[2], outputs[3]];
+
+    let mut predictor = Predictor::try_new_with_cancellation(
+        table,
+        initial,
+        &config.cancellation,
+        |done, total| {
+            if let Some(progress) = &config.progress {
+                progress(done, total);
+            }
+        },
+    )
+    .map_err(|_| match config.cancellation.is_cancelled() {
+        true => Error::new(ErrorKind::Interrupted, "search cancelled"),
+        false => inconsistent(),
+    })?;
+
+    for &output in &outputs[4..] {
+        predictor
+            .submit_next_output(output)
+            .map_err(|_| inconsistent())?;
+    }
+
+    let candidates = Candidates::Implicit(predictor);
+
+    Ok(Box::new(Tracker::with_threads(
+        candidates,
+        config.threshold,
+        config.threads,
+        (),
+    )))
+}
+
+fn known_inc_solver(config: &SolverConfig, outputs: &[u32]) -> Result<Box<dyn SolverBackend>> {
+    let inc = (config.inc).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "the `known-inc` solver needs an increment",
+        )
+    })?;
+
+    let state = recover_state_with_inc(W(inc), outputs).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "output sequence not produced with the given increment",
+        )
+    })?;
+
+    // explicit candidates are the states which produced the last output
+    let candidates = Candidates::Explicit(vec![state.jump(outputs.len() as u64 - 1)]);
+
+    Ok(Box::new(Tracker::with_threads(
+        candidates,
+        config.threshold,
+        config.threads,
+        (),
+    )))
+}
@@ -0,0 +1,308 @@
+//! Polling an HTTP endpoint for outputs.
+
+use super::url::split_url;
+use super::Json;
+use crate::{parse_observations, Observation, Position, INTERRUPTED};
+use std::collections::VecDeque;
+use std::io::{Error, Read, Result, Write};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Polls an HTTP endpoint returning outputs, such as a "guess the number"
+/// API, at most once per interval and backing off while it fails. The URL
+/// is a template in which `{n}` is replaced by the number of the request.
+pub struct HttpPoller {
+    pub url: String,
+    /// Where the outputs are in each response, if it is JSON.
+    extract: Option<String>,
+    interval: Duration,
+    /// How long to wait before the next request, longer after failures.
+    delay: Duration,
+    last_request: Option<Instant>,
+    requests: u64,
+    pub position: Position,
+    queue: VecDeque<Observation>,
+}
+
+/// Largest HTTP response accepted, headers included.
+const MAX_HTTP_RESPONSE: usize = 16 << 20;
+
+/// Parses an HTTP/1.x response read until the server closed the connection,
+/// returning the status, the `Retry-After` header if any, and the body, whose
+/// length is given by its chunks if chunked or by `Content-Length` if any.
+fn parse_http_response(response: &[u8]) -> Result<(u32, Option<u64>, String)> {
+    let invalid = || Error::other("invalid HTTP response");
+
+    let split = (response.windows(4))
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+
+    let head = String::from_utf8_lossy(&response[..split]);
+    let mut body = &response[split + 4..];
+    let mut lines = head.lines();
+
+    let status = lines
+        .next()
+        .filter(|line| line.starts_with("HTTP/1."))
+        .and_then(|line| line.split_whitespace().nth(1)?.parse().ok())
+        .ok_or_else(invalid)?;
+
+    let headers: Vec<(&str, &str)> = (lines.filter_map(|line| line.split_once(':')))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+
+    let header = |wanted: &str| {
+        (headers.iter())
+            .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+            .map(|&(_, value)| value)
+    };
+
+    let retry_after = header("retry-after").and_then(|value| value.parse().ok());
+
+    let chunked = header("transfer-encoding")
+        .is_some_and(|value| value.to_ascii_lowercase().ends_with("chunked"));
+
+    let body = if chunked {
+        let mut decoded = vec![];
+
+        // <size in hex>[;extensions] CRLF <data> CRLF, up to a chunk of size 0
+        loop {
+            let end = (body.windows(2))
+                .position(|window| window == b"\r\n")
+                .ok_or_else(invalid)?;
+
+            let line = String::from_utf8_lossy(&body[..end]);
+            let size = line.split(';').next().unwrap_or_default().trim();
+            let size = usize::from_str_radix(size, 16).map_err(|_| invalid())?;
+
+            body = &body[end + 2..];
+
+            if size == 0 {
+                break;
+            }
+
+            let chunk = body.get(..size).ok_or_else(invalid)?;
+
+            if body.get(size..size + 2) != Some(b"\r\n") {
+                return Err(invalid());
+            }
+
+            decoded.extend_from_slice(chunk);
+            body = &body[size + 2..];
+        }
+
+        decoded
+    } else if let Some(length) = header("content-length") {
+        let length: usize = length.parse().map_err(|_| invalid())?;
+        body.get(..length).ok_or_else(invalid)?.to_vec()
+    } else {
+        body.to_vec()
+    };
+
+    Ok((
+        status,
+        retry_after,
+        String::from_utf8_lossy(&body).into_owned(),
+    ))
+}
+
+/// The longest to wait between requests while backing off.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+impl HttpPoller {
+    pub fn new(url: &str, extract: Option<&str>, interval: Duration) -> Self {
+        Self {
+            url: url.to_owned(),
+            extract: extract.map(str::to_owned),
+            interval,
+            delay: interval,
+            last_request: None,
+            requests: 0,
+            position: Position::Unknown,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Makes a request, returning the status, the `Retry-After` header if
+    /// any, and the body of the response.
+    fn request(&self, url: &str) -> Result<(u32, Option<u64>, String)> {
+        let url = split_url(url, "http")?;
+        let (reader, mut writer) = url.connect(Some(Duration::from_secs(30)))?;
+
+        // the server closing the connection marks the end of the response
+        write!(
+            writer,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: pcg-breaker\r\nAccept: */*\r\n\
+             Connection: close\r\n\r\n",
+            url.path, url.host
+        )?;
+
+        writer.flush()?;
+
+        let mut response = vec![];
+        let limit = MAX_HTTP_RESPONSE as u64 + 1;
+        reader.take(limit).read_to_end(&mut response)?;
+
+        if response.len() > MAX_HTTP_RESPONSE {
+            return Err(Error::other(format!(
+                "HTTP response longer than the limit of {} bytes",
+                MAX_HTTP_RESPONSE
+            )));
+        }
+
+        parse_http_response(&response)
+    }
+
+    pub fn read_output(&mut self) -> Result<Observation> {
+        while self.queue.is_empty() {
+            if let Some(last_request) = self.last_request {
+                while last_request.elapsed() < self.delay {
+                    if INTERRUPTED.load(Ordering::SeqCst) {
+                        return Err(Error::other("interrupted"));
+                    }
+
+                    thread::sleep(
+                        (self.delay - last_request.elapsed()).min(Duration::from_millis(100)),
+                    );
+                }
+            }
+
+            self.requests += 1;
+            self.last_request = Some(Instant::now());
+
+            let url = self.url.replace("{n}", &self.requests.to_string());
+
+            let (failure, retry_after) = match self.request(&url) {
+                Ok((200..=299, _, body)) => {
+                    self.position = Position::Response(self.requests);
+
+                    match &self.extract {
+                        Some(path) => match Json::parse(&body)?.get(path) {
+                            Some(value) => value.outputs(&mut self.queue)?,
+                            None => return Err(Error::other(format!("no `{}` in response", path))),
+                        },
+                        None => parse_observations(&body, &mut self.queue)?,
+                    }
+
+                    self.delay = self.interval;
+                    continue;
+                }
+                // only rate limiting and server errors are worth retrying
+                Ok((status @ (429 | 500..), retry_after, _)) => {
+                    (format!("status {}", status), retry_after)
+                }
+                Ok((status, _, _)) => {
+                    return Err(Error::other(format!(
+                        "HTTP request failed with status {}",
+                        status
+                    )))
+                }
+                Err(err) if INTERRUPTED.load(Ordering::SeqCst) => return Err(err),
+                Err(err) => (err.to_string(), None),
+            };
+
+            // the server knows best how long to wait, if it says so
+            self.delay = match retry_after {
+                Some(seconds) => Duration::from_secs(seconds),
+                None => (self.delay * 2).clamp(Duration::from_secs(1), MAX_BACKOFF),
+            };
+
+            report!(
+                "[!] HTTP request failed ({}), retrying in {:.1} seconds.",
+                failure,
+                self.delay.as_secs_f64()
+            );
+        }
+
+        Ok(self.queue.pop_front().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_http(response: &str) -> Result<(u32, Option<u64>, String)> {
+        parse_http_response(response.as_bytes())
+    }
+
+    #[test]
+    fn http_responses_are_parsed() {
+        assert_eq!(
+            parse_http("HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n0x12 3456").unwrap(),
+            (200, None, "0x12 3456".to_owned())
+        );
+
+        // bytes past the announced length are ignored
+        assert_eq!(
+            parse_http("HTTP/1.1 200 OK\r\ncontent-length: 4\r\n\r\n1234\r\n").unwrap(),
+            (200, None, "1234".to_owned())
+        );
+
+        // without a length, the body runs until the connection was closed
+        assert_eq!(
+            parse_http("HTTP/1.0 200 OK\r\nServer: test\r\n\r\n{\"roll\": 42}\n").unwrap(),
+            (200, None, "{\"roll\": 42}\n".to_owned())
+        );
+
+        assert_eq!(
+            parse_http("HTTP/1.1 429 Too Many Requests\r\nRetry-After:  7 \r\n\r\n").unwrap(),
+            (429, Some(7), String::new())
+        );
+
+        assert_eq!(
+            parse_http("HTTP/1.1 503 Unavailable\r\nRetry-After: Wed, 21 Oct 2026\r\n\r\n")
+                .unwrap(),
+            (503, None, String::new())
+        );
+    }
+
+    #[test]
+    fn chunked_http_responses_are_decoded() {
+        assert_eq!(
+            parse_http(
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                 4\r\n0x12\r\n\
+                 A;name=value\r\n 3456 7890\r\n\
+                 0\r\nTrailer: yes\r\n\r\n"
+            )
+            .unwrap(),
+            (200, None, "0x12 3456 7890".to_owned())
+        );
+
+        // chunked encoding wins over any length
+        assert_eq!(
+            parse_http(
+                "HTTP/1.1 200 OK\r\nContent-Length: 1\r\nTransfer-Encoding: gzip, Chunked\r\n\
+                 \r\n2\r\n42\r\n0\r\n\r\n"
+            )
+            .unwrap(),
+            (200, None, "42".to_owned())
+        );
+
+        assert_eq!(
+            parse_http("HTTP/1.1 204 No Content\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n")
+                .unwrap(),
+            (204, None, String::new())
+        );
+    }
+
+    #[test]
+    fn invalid_http_responses_are_rejected() {
+        for response in [
+            "",
+            "HTTP/1.1 200 OK\r\n",
+            "HTTP/1.1 OK\r\n\r\n",
+            "SSH-2.0-OpenSSH\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n1234",
+            "HTTP/1.1 200 OK\r\nContent-Length: -1\r\n\r\n1234",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n12",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\n1234\r\n0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n12\r\n0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\n12\r\n",
+        ] {
+            assert!(parse_http(response).is_err(), "{:?}", response);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Parsing the JSON which outputs are extracted from, in messages and records.
+
+use crate::{parse_observations, Observation};
+use std::collections::VecDeque;
+use std::io::{Error, Result};
+
+/// A JSON value, parsed just far enough to pull outputs out of messages.
+pub enum Json {
+    /// `null`, `true` or `false`, none of which can hold outputs.
+    Literal,
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut chars = text.chars().peekable();
+        let value = Self::parse_value(&mut chars)?;
+
+        match chars.find(|c| !c.is_whitespace()) {
+            Some(_) => Err(Error::other("trailing characters after JSON value")),
+            None => Ok(value),
+        }
+    }
+
+    fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Self> {
+        let invalid = || Error::other("invalid JSON in message");
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let value = match chars.peek().ok_or_else(invalid)? {
+            '{' | '[' => {
+                let object = chars.next() == Some('{');
+                let mut items = vec![];
+
+                loop {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+                    if chars.next_if(|&c| c == '}' || c == ']').is_some() {
+                        break;
+                    }
+
+                    let key = match object {
+                        true => match Self::parse_value(chars)? {
+                            Self::String(key) => {
+                                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                                chars.next_if_eq(&':').ok_or_else(invalid)?;
+                                key
+                            }
+                            _ => return Err(invalid()),
+                        },
+                        false => String::new(),
+                    };
+
+                    items.push((key, Self::parse_value(chars)?));
+
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    chars.next_if_eq(&',');
+                }
+
+                match object {
+                    true => Self::Object(items),
+                    false => Self::Array(items.into_iter().map(|(_, value)| value).collect()),
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut string = String::new();
+
+                loop {
+                    match chars.next().ok_or_else(invalid)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(invalid)? {
+                            'u' => {
+                                let code: String = chars.by_ref().take(4).collect();
+                                let code = u32::from_str_radix(&code, 16).map_err(|_| invalid())?;
+                                string.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                            }
+                            'n' => string.push('\n'),
+                            't' => string.push('\t'),
+                            'r' => string.push('\r'),
+                            'b' => string.push('\u{8}'),
+                            'f' => string.push('\u{c}'),
+                            c => string.push(c),
+                        },
+                        c => string.push(c),
+                    }
+                }
+
+                Self::String(string)
+            }
+            _ => {
+                let mut word = String::new();
+
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "+-.".contains(*c)) {
+                    word.push(c);
+                }
+
+                match word.as_str() {
+                    "null" | "true" | "false" => Self::Literal,
+                    "" => return Err(invalid()),
+                    _ => Self::Number(word),
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Looks up a value by its path, made of object keys separated by dots
+    /// and array indices in brackets, e.g. `data.rolls[0]` (an optional `$.`
+    /// prefix as in JSONPath is ignored).
+    pub fn get(&self, path: &str) -> Option<&Self> {
+        let path = path.strip_prefix('$').unwrap_or(path);
+
+        path.split(['.', '['])
+            .filter(|step| !step.is_empty())
+            .try_fold(self, |value, step| match (value, step.strip_suffix(']')) {
+                (Self::Array(items), Some(index)) => items.get(index.parse::<usize>().ok()?),
+                (Self::Object(fields), None) => fields
+                    .iter()
+                    .find(|(key, _)| key == step)
+                    .map(|(_, value)| value),
+                _ => None,
+            })
+    }
+
+    /// Queues the outputs held by a value, which is either a number, a string
+    /// holding outputs, or an array of those.
+    pub fn outputs(&self, queue: &mut VecDeque<Observation>) -> Result<()> {
+        match self {
+            Self::Number(text) | Self::String(text) => parse_observations(text, queue),
+            Self::Array(items) => items.iter().try_for_each(|item| item.outputs(queue)),
+            _ => Err(Error::other("expected outputs in message")),
+        }
+    }
+}
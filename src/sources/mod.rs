@@ -0,0 +1,23 @@
+//! The clients receiving outputs over the network, each behind its feature,
+//! and the JSON parser pulling outputs out of what they receive (and out of
+//! recordings replayed with --replay-timing).
+
+#[cfg(feature = "http")]
+mod http;
+mod json;
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(any(feature = "websocket", feature = "http"))]
+mod tls;
+#[cfg(any(feature = "websocket", feature = "http"))]
+mod url;
+#[cfg(feature = "websocket")]
+mod websocket;
+
+#[cfg(feature = "http")]
+pub use http::HttpPoller;
+pub use json::Json;
+#[cfg(feature = "nats")]
+pub use nats::NatsSubscriber;
+#[cfg(feature = "websocket")]
+pub use websocket::{WebSocketClient, WEBSOCKET_STREAM};
@@ -0,0 +1,198 @@
+//! Subscribing to the outputs published on a NATS subject.
+
+use crate::{parse_observations, Interruptible, Observation, Position};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Result, Write};
+use std::net::TcpStream;
+
+/// Receives outputs published to a NATS subject, speaking just enough of the
+/// NATS protocol to subscribe to it. Each message may hold several outputs,
+/// written as on standard input.
+pub struct NatsSubscriber {
+    /// The server and subject, as `host:port/subject`.
+    pub address: String,
+    connection: Option<(BufReader<Interruptible<TcpStream>>, TcpStream)>,
+    messages: usize,
+    pub position: Position,
+    queue: VecDeque<Observation>,
+}
+
+impl NatsSubscriber {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_owned(),
+            connection: None,
+            messages: 0,
+            position: Position::Unknown,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Connects to the server and subscribes to the subject.
+    fn connect(&self) -> Result<(BufReader<Interruptible<TcpStream>>, TcpStream)> {
+        let (server, subject) = self.address.split_once('/').ok_or_else(|| {
+            Error::other(format!(
+                "expected `host:port/subject`, got `{}`",
+                self.address
+            ))
+        })?;
+
+        let mut writer = TcpStream::connect(server)?;
+        let reader = BufReader::new(Interruptible(writer.try_clone()?));
+
+        write!(
+            writer,
+            "CONNECT {{\"verbose\":false,\"pedantic\":false}}\r\nSUB {} 1\r\n",
+            subject
+        )?;
+
+        report!(
+            "[+] Subscribed to NATS subject `{}` on {}.",
+            subject,
+            server
+        );
+
+        Ok((reader, writer))
+    }
+
+    pub fn read_output(&mut self) -> Result<Observation> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect()?);
+        }
+
+        let (reader, writer) = self.connection.as_mut().unwrap();
+
+        while self.queue.is_empty() {
+            if let Some(payload) = read_nats_message(reader, writer)? {
+                self.messages += 1;
+                self.position = Position::Message(self.messages);
+
+                parse_observations(&String::from_utf8_lossy(&payload), &mut self.queue)?;
+            }
+        }
+
+        Ok(self.queue.pop_front().unwrap())
+    }
+}
+
+/// Largest message payload accepted from a NATS server, which is also the
+/// default limit of the server itself.
+const NATS_MAX_PAYLOAD: usize = 1 << 20;
+
+/// Reads the next line sent by a NATS server, answering pings, and returns
+/// the payload if it was a message (or nothing if it was anything else).
+fn read_nats_message(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> Result<Option<Vec<u8>>> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+
+    // MSG <subject> <sid> [reply-to] <size>, followed by the payload
+    if let Some(header) = line.strip_prefix("MSG ") {
+        let fields: Vec<&str> = header.split_whitespace().collect();
+
+        let size = match fields[..] {
+            [_, _, size] | [_, _, _, size] => size.parse::<usize>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| Error::other(format!("invalid NATS message header `{}`", line.trim())))?;
+
+        // the payload is followed by CRLF
+        let Some(length) = size.checked_add(2).filter(|_| size <= NATS_MAX_PAYLOAD) else {
+            return Err(Error::other(format!(
+                "NATS message of {} bytes is larger than the limit of {} bytes",
+                size, NATS_MAX_PAYLOAD
+            )));
+        };
+
+        let mut payload = vec![0; length];
+        reader.read_exact(&mut payload)?;
+
+        if !payload.ends_with(b"\r\n") {
+            return Err(Error::other("NATS message payload not followed by CRLF"));
+        }
+
+        payload.truncate(size);
+        Ok(Some(payload))
+    } else if line.starts_with("PING") {
+        writer.write_all(b"PONG\r\n")?;
+        Ok(None)
+    } else if let Some(message) = line.strip_prefix("-ERR ") {
+        Err(Error::other(format!("NATS error: {}", message.trim())))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the lines of a valid NATS session one at a time, returning the
+    /// payload of each message (if it was one) and everything written back.
+    fn read_nats_session(session: &[u8]) -> (Vec<Option<Vec<u8>>>, Vec<u8>) {
+        let mut reader = BufReader::new(session);
+        let mut written = vec![];
+        let mut payloads = vec![];
+
+        while !reader.fill_buf().unwrap().is_empty() {
+            payloads.push(read_nats_message(&mut reader, &mut written).unwrap());
+        }
+
+        (payloads, written)
+    }
+
+    #[test]
+    fn nats_messages_and_pings_are_parsed() {
+        let (payloads, written) = read_nats_session(
+            b"INFO {\"max_payload\":1048576}\r\n\
+              MSG outputs 1 11\r\n0x1234 5678\r\n\
+              PING\r\n\
+              MSG outputs 1 _INBOX.reply 0\r\n\r\n\
+              MSG outputs 1 4\r\nMSG \r\n",
+        );
+
+        assert_eq!(
+            payloads,
+            [
+                None,
+                Some(b"0x1234 5678".to_vec()),
+                None,
+                Some(vec![]),
+                Some(b"MSG ".to_vec()),
+            ]
+        );
+
+        assert_eq!(written, b"PONG\r\n");
+    }
+
+    #[test]
+    fn invalid_nats_messages_are_rejected() {
+        let oversized = format!("MSG outputs 1 {}\r\n", NATS_MAX_PAYLOAD + 1);
+        let overflowing = format!("MSG outputs 1 {}\r\n", usize::MAX);
+
+        for session in [
+            "MSG outputs 1\r\n",
+            "MSG outputs 1 a b c\r\n",
+            "MSG outputs 1 -4\r\n",
+            "MSG outputs 1 0x10\r\n",
+            &oversized,
+            &overflowing,
+            "MSG outputs 1 4\r\n1234XX",
+            "MSG outputs 1 8\r\n1234\r\n",
+            "-ERR 'Authorization Violation'\r\n",
+            "",
+        ] {
+            let mut reader = BufReader::new(session.as_bytes());
+            assert!(
+                read_nats_message(&mut reader, &mut vec![]).is_err(),
+                "{}",
+                session
+            );
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! TLS connections through OpenSSL, which is loaded at run time (as OpenCL
+//! is by the `gpu` feature) so that building with the `websocket` or `http`
+//! feature needs nothing but libc, and only connecting to `wss://` or
+//! `https://` URLs needs the library.
+
+use crate::INTERRUPTED;
+use std::ffi::{c_char, c_int, c_long, c_ulong, c_void, CStr, CString};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::net::{IpAddr, TcpStream};
+use std::os::unix::io::AsRawFd;
+use std::ptr::null;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, OnceLock};
+
+const SSL_VERIFY_PEER: c_int = 1;
+const SSL_CTRL_SET_TLSEXT_HOSTNAME: c_int = 55;
+const TLSEXT_NAMETYPE_HOST_NAME: c_long = 0;
+const SSL_OP_IGNORE_UNEXPECTED_EOF: u64 = 1 << 7;
+const SSL_ERROR_SSL: c_int = 1;
+const SSL_ERROR_WANT_READ: c_int = 2;
+const SSL_ERROR_WANT_WRITE: c_int = 3;
+const SSL_ERROR_SYSCALL: c_int = 5;
+const SSL_ERROR_ZERO_RETURN: c_int = 6;
+const X509_V_OK: c_long = 0;
+
+type Handle = *mut c_void;
+
+/// The OpenSSL entry points used, resolved from the library at run time.
+/// The library is never unloaded, as connections may be made until exiting.
+struct OpenSsl {
+    version_num: unsafe extern "C" fn() -> c_ulong,
+    client_method: unsafe extern "C" fn() -> *const c_void,
+    ctx_new: unsafe extern "C" fn(*const c_void) -> Handle,
+    ctx_free: unsafe extern "C" fn(Handle),
+    ctx_set_default_verify_paths: unsafe extern "C" fn(Handle) -> c_int,
+    ctx_set_verify: unsafe extern "C" fn(Handle, c_int, *const c_void),
+    ctx_set_options: unsafe extern "C" fn(Handle, u64) -> u64,
+    new: unsafe extern "C" fn(Handle) -> Handle,
+    free: unsafe extern "C" fn(Handle),
+    set_fd: unsafe extern "C" fn(Handle, c_int) -> c_int,
+    set1_host: unsafe extern "C" fn(Handle, *const c_char) -> c_int,
+    ctrl: unsafe extern "C" fn(Handle, c_int, c_long, *mut c_void) -> c_long,
+    connect: unsafe extern "C" fn(Handle) -> c_int,
+    read: unsafe extern "C" fn(Handle, *mut c_void, c_int) -> c_int,
+    write: unsafe extern "C" fn(Handle, *const c_void, c_int) -> c_int,
+    shutdown: unsafe extern "C" fn(Handle) -> c_int,
+    get_error: unsafe extern "C" fn(Handle, c_int) -> c_int,
+    get_verify_result: unsafe extern "C" fn(Handle) -> c_long,
+    verify_error_string: unsafe extern "C" fn(c_long) -> *const c_char,
+    err_get_error: unsafe extern "C" fn() -> c_ulong,
+    err_clear_error: unsafe extern "C" fn(),
+    err_error_string_n: unsafe extern "C" fn(c_ulong, *mut c_char, usize),
+}
+
+impl OpenSsl {
+    fn load() -> Result<Self> {
+        let library = ["libssl.so.3", "libssl.so"]
+            .iter()
+            .map(|name| {
+                let name = CString::new(*name).unwrap();
+                unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) }
+            })
+            .find(|library| !library.is_null())
+            .ok_or_else(|| Error::other("TLS connections need OpenSSL, which wasn't found"))?;
+
+        // the functions of libcrypto are found through libssl, which needs it
+        macro_rules! resolve {
+            ($name:literal) => {{
+                let name = CString::new($name).unwrap();
+                let symbol = unsafe { libc::dlsym(library, name.as_ptr()) };
+
+                if symbol.is_null() {
+                    return Err(Error::other(concat!("OpenSSL library lacks ", $name)));
+                }
+
+                #[allow(clippy::missing_transmute_annotations)]
+                unsafe {
+                    std::mem::transmute(symbol)
+                }
+            }};
+        }
+
+        let ssl = Self {
+            version_num: resolve!("OpenSSL_version_num"),
+            client_method: resolve!("TLS_client_method"),
+            ctx_new: resolve!("SSL_CTX_new"),
+            ctx_free: resolve!("SSL_CTX_free"),
+            ctx_set_default_verify_paths: resolve!("SSL_CTX_set_default_verify_paths"),
+            ctx_set_verify: resolve!("SSL_CTX_set_verify"),
+            ctx_set_options: resolve!("SSL_CTX_set_options"),
+            new: resolve!("SSL_new"),
+            free: resolve!("SSL_free"),
+            set_fd: resolve!("SSL_set_fd"),
+            set1_host: resolve!("SSL_set1_host"),
+            ctrl: resolve!("SSL_ctrl"),
+            connect: resolve!("SSL_connect"),
+            read: resolve!("SSL_read"),
+            write: resolve!("SSL_write"),
+            shutdown: resolve!("SSL_shutdown"),
+            get_error: resolve!("SSL_get_error"),
+            get_verify_result: resolve!("SSL_get_verify_result"),
+            verify_error_string: resolve!("X509_verify_cert_error_string"),
+            err_get_error: resolve!("ERR_get_error"),
+            err_clear_error: resolve!("ERR_clear_error"),
+            err_error_string_n: resolve!("ERR_error_string_n"),
+        };
+
+        // the signatures above (and the options) are those of OpenSSL 3
+        if unsafe { (ssl.version_num)() } < 0x3000_0000 {
+            return Err(Error::other("TLS connections need OpenSSL 3 or later"));
+        }
+
+        Ok(ssl)
+    }
+
+    /// Returns the library, loading it the first time it is needed.
+    fn get() -> Result<&'static Self> {
+        static OPENSSL: OnceLock<std::result::Result<OpenSsl, String>> = OnceLock::new();
+
+        OPENSSL
+            .get_or_init(|| Self::load().map_err(|err| err.to_string()))
+            .as_ref()
+            .map_err(|err| Error::other(err.clone()))
+    }
+
+    /// Describes the latest error of this thread.
+    fn last_error(&self) -> String {
+        match unsafe { (self.err_get_error)() } {
+            0 => "connection closed".to_owned(),
+            code => {
+                let mut text = [0 as c_char; 256];
+                unsafe { (self.err_error_string_n)(code, text.as_mut_ptr(), text.len()) };
+                unsafe { CStr::from_ptr(text.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        }
+    }
+}
+
+/// A TLS connection over a TCP stream, whose reads time out as those of the
+/// stream do.
+pub struct TlsStream {
+    ssl: &'static OpenSsl,
+    handle: Handle,
+    /// Kept open for as long as OpenSSL uses its file descriptor.
+    _stream: TcpStream,
+}
+
+// connections are only ever used by one thread at a time (see `split`)
+unsafe impl Send for TlsStream {}
+
+impl TlsStream {
+    /// Makes a TLS connection over the stream, verifying that the server's
+    /// certificate is valid for `name` against the system's trusted
+    /// certificates (or those named by the `SSL_CERT_FILE` and
+    /// `SSL_CERT_DIR` environment variables).
+    pub fn connect(stream: TcpStream, name: &str) -> Result<Self> {
+        let ssl = OpenSsl::get()?;
+        let host = CString::new(name).map_err(Error::other)?;
+
+        let handle = unsafe {
+            (ssl.err_clear_error)();
+
+            let context = (ssl.ctx_new)((ssl.client_method)());
+
+            if context.is_null() {
+                return Err(Error::other(format!(
+                    "can't set up TLS: {}",
+                    ssl.last_error()
+                )));
+            }
+
+            (ssl.ctx_set_default_verify_paths)(context);
+            (ssl.ctx_set_verify)(context, SSL_VERIFY_PEER, null());
+
+            // many servers close the connection without notice once done
+            (ssl.ctx_set_options)(context, SSL_OP_IGNORE_UNEXPECTED_EOF);
+
+            // the connection holds on to the context for as long as needed
+            let handle = (ssl.new)(context);
+            (ssl.ctx_free)(context);
+            handle
+        };
+
+        if handle.is_null() {
+            return Err(Error::other("can't set up a TLS connection"));
+        }
+
+        let tls = Self {
+            ssl,
+            handle,
+            _stream: stream,
+        };
+
+        unsafe {
+            if (ssl.set_fd)(handle, tls._stream.as_raw_fd()) != 1
+                || (ssl.set1_host)(handle, host.as_ptr()) != 1
+            {
+                return Err(Error::other(format!(
+                    "can't connect to `{}` over TLS",
+                    name
+                )));
+            }
+
+            // servers hosting several names pick a certificate by it, but
+            // addresses aren't names
+            if name.parse::<IpAddr>().is_err() {
+                let name = host.as_ptr() as *mut c_void;
+                (ssl.ctrl)(
+                    handle,
+                    SSL_CTRL_SET_TLSEXT_HOSTNAME,
+                    TLSEXT_NAMETYPE_HOST_NAME,
+                    name,
+                );
+            }
+        }
+
+        loop {
+            unsafe { (ssl.err_clear_error)() };
+
+            match tls.check(unsafe { (ssl.connect)(handle) }) {
+                Ok(0) => return Err(Error::other("TLS connection failed: connection closed")),
+                Ok(_) => return Ok(tls),
+                Err(err)
+                    if err.kind() == ErrorKind::Interrupted
+                        && !INTERRUPTED.load(Ordering::SeqCst) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Splits the connection into halves for reading and for writing, which
+    /// take turns using it.
+    pub fn split(self) -> (TlsHalf, TlsHalf) {
+        let shared = Arc::new(Mutex::new(self));
+        (TlsHalf(shared.clone()), TlsHalf(shared))
+    }
+
+    /// Turns what a call returned into a number of bytes transferred, none
+    /// once the server closed the connection, or the reason it failed. Reads
+    /// cut short by a signal fail with `ErrorKind::Interrupted`, and may be
+    /// retried.
+    fn check(&self, result: c_int) -> Result<usize> {
+        if result > 0 {
+            return Ok(result as usize);
+        }
+
+        // before anything else can change it
+        let os_error = Error::last_os_error();
+
+        match unsafe { (self.ssl.get_error)(self.handle, result) } {
+            SSL_ERROR_ZERO_RETURN => Ok(0),
+            // the socket's timeout expired, or a signal arrived
+            SSL_ERROR_WANT_READ | SSL_ERROR_WANT_WRITE => match os_error.kind() {
+                ErrorKind::Interrupted => Err(os_error),
+                _ => Err(ErrorKind::TimedOut.into()),
+            },
+            SSL_ERROR_SYSCALL if os_error.raw_os_error().unwrap_or_default() != 0 => Err(os_error),
+            SSL_ERROR_SSL | SSL_ERROR_SYSCALL => Err(Error::other(format!(
+                "TLS connection failed: {}",
+                self.describe_error()
+            ))),
+            code => Err(Error::other(format!(
+                "TLS connection failed with error {}",
+                code
+            ))),
+        }
+    }
+}
+
+impl TlsStream {
+    /// Describes why the connection failed, which is why the server's
+    /// certificate was rejected if it was.
+    fn describe_error(&self) -> String {
+        let verify_result = unsafe { (self.ssl.get_verify_result)(self.handle) };
+
+        match verify_result {
+            X509_V_OK => self.ssl.last_error(),
+            _ => {
+                let reason =
+                    unsafe { CStr::from_ptr((self.ssl.verify_error_string)(verify_result)) };
+                format!("certificate verify failed ({})", reason.to_string_lossy())
+            }
+        }
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let length = buf.len().min(c_int::MAX as usize) as c_int;
+
+        unsafe { (self.ssl.err_clear_error)() };
+        self.check(unsafe { (self.ssl.read)(self.handle, buf.as_mut_ptr().cast(), length) })
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let length = buf.len().min(c_int::MAX as usize) as c_int;
+
+        unsafe { (self.ssl.err_clear_error)() };
+        self.check(unsafe { (self.ssl.write)(self.handle, buf.as_ptr().cast(), length) })
+    }
+
+    /// Writes go straight to the socket, so there's nothing to flush.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for TlsStream {
+    fn drop(&mut self) {
+        unsafe {
+            (self.ssl.shutdown)(self.handle);
+            (self.ssl.free)(self.handle);
+            (self.ssl.err_clear_error)();
+        }
+    }
+}
+
+/// One half of a TLS connection, which locks the connection for each read
+/// or write. Both halves are used by the same thread, one after the other,
+/// so neither waits on the other.
+pub struct TlsHalf(Arc<Mutex<TlsStream>>);
+
+impl Read for TlsHalf {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.lock().unwrap().read(buf)
+    }
+}
+
+impl Write for TlsHalf {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
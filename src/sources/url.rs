@@ -0,0 +1,114 @@
+//! Connecting to servers given by URL, over plain TCP or over TLS.
+
+use super::tls::TlsStream;
+use crate::Interruptible;
+use std::io::{BufReader, Error, Read, Result, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// The reading and writing halves of a connection to a server.
+pub type Connection = (
+    BufReader<Interruptible<Box<dyn Read + Send>>>,
+    Box<dyn Write + Send>,
+);
+
+/// A `scheme://host[:port]/path` URL, or `schemes://...` over TLS.
+pub struct Url<'a> {
+    pub secure: bool,
+    /// The address to connect to, with the default port of the scheme if
+    /// none is given.
+    pub address: String,
+    pub host: &'a str,
+    pub path: &'a str,
+}
+
+/// Splits a URL with the given scheme (or its secure variant) into what is
+/// needed to connect to it.
+pub fn split_url<'a>(url: &'a str, scheme: &str) -> Result<Url<'a>> {
+    let (secure, rest) = match url.split_once("://") {
+        Some((name, rest)) if name == scheme => (false, rest),
+        Some((name, rest)) if name.strip_suffix('s') == Some(scheme) => (true, rest),
+        _ => {
+            return Err(Error::other(format!(
+                "expected a {}:// or {}s:// URL, got `{}`",
+                scheme, scheme, url
+            )))
+        }
+    };
+
+    let (host, path) = match rest.find('/') {
+        Some(slash) => rest.split_at(slash),
+        None => (rest, "/"),
+    };
+
+    let address = match (host.contains(':'), secure) {
+        (true, _) => host.to_owned(),
+        (false, false) => format!("{}:80", host),
+        (false, true) => format!("{}:443", host),
+    };
+
+    Ok(Url {
+        secure,
+        address,
+        host,
+        path,
+    })
+}
+
+impl Url<'_> {
+    /// Connects to the server, over TLS if the URL is secure, giving up on
+    /// reads after `timeout` if any.
+    pub fn connect(&self, timeout: Option<Duration>) -> Result<Connection> {
+        let stream = TcpStream::connect(&self.address)?;
+        stream.set_read_timeout(timeout)?;
+
+        if !self.secure {
+            let reader: Box<dyn Read + Send> = Box::new(stream.try_clone()?);
+            return Ok((BufReader::new(Interruptible(reader)), Box::new(stream)));
+        }
+
+        // the name the certificate must be valid for, without the port
+        let name = match self.host.rsplit_once(':') {
+            Some((name, _)) => name,
+            None => self.host,
+        };
+
+        let (reader, writer) = TlsStream::connect(stream, name)?.split();
+        let reader: Box<dyn Read + Send> = Box::new(reader);
+
+        Ok((BufReader::new(Interruptible(reader)), Box::new(writer)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_split_with_default_ports() {
+        let url = split_url("wss://example.com/socket?id=1", "ws").unwrap();
+        assert!(url.secure);
+        assert_eq!(
+            (url.address.as_str(), url.host, url.path),
+            ("example.com:443", "example.com", "/socket?id=1")
+        );
+
+        let url = split_url("ws://localhost:8080", "ws").unwrap();
+        assert!(!url.secure);
+        assert_eq!(
+            (url.address.as_str(), url.host, url.path),
+            ("localhost:8080", "localhost:8080", "/")
+        );
+
+        let url = split_url("http://10.0.0.1/rolls", "http").unwrap();
+        assert_eq!(url.address, "10.0.0.1:80");
+
+        for url in [
+            "https//example.com",
+            "ftp://example.com",
+            "wsss://example.com",
+        ] {
+            assert!(split_url(url, "ws").is_err(), "{}", url);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Receiving outputs from a WebSocket server.
+
+use super::url::{split_url, Connection};
+use super::Json;
+use crate::{parse_observations, Observation, Position};
+use pcg_breaker::Pcg32State;
+use std::collections::VecDeque;
+use std::io::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+/// Receives outputs sent by a WebSocket server, over a plain (`ws://`) or
+/// TLS (`wss://`) connection. Each text message may hold several outputs,
+/// written as on standard input, or be JSON from which the outputs are
+/// extracted.
+pub struct WebSocketClient {
+    pub url: String,
+    /// Where the outputs are in each message, if it is JSON.
+    field: Option<String>,
+    /// Draws the handshake keys and the masks of frames sent, from the seed
+    /// of the run.
+    rng: Pcg32State,
+    connection: Option<Connection>,
+    messages: usize,
+    pub position: Position,
+    queue: VecDeque<Observation>,
+}
+
+/// Stream of the generator drawing WebSocket keys and masks, apart from the
+/// one of the console (so that connecting doesn't change what it samples).
+pub const WEBSOCKET_STREAM: u64 = 1;
+
+/// Largest WebSocket message accepted, over all of its frames.
+const MAX_WEBSOCKET_MESSAGE: usize = 16 << 20;
+
+/// A WebSocket frame, unmasked.
+#[derive(Debug, PartialEq, Eq)]
+struct Frame {
+    /// Whether this is the last frame of its message.
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Reads a frame, refusing payloads longer than `max_length`.
+    fn read(reader: &mut impl Read, max_length: usize) -> Result<Self> {
+        let mut header = [0; 2];
+        reader.read_exact(&mut header)?;
+
+        let (fin, opcode) = (header[0] & 0x80 != 0, header[0] & 0x0f);
+
+        let length = match header[1] & 0x7f {
+            126 => {
+                let mut length = [0; 2];
+                reader.read_exact(&mut length)?;
+                u16::from_be_bytes(length) as u64
+            }
+            127 => {
+                let mut length = [0; 8];
+                reader.read_exact(&mut length)?;
+                u64::from_be_bytes(length)
+            }
+            length => length as u64,
+        };
+
+        // control frames are never fragmented, nor longer than 125 bytes
+        if opcode & 0x8 != 0 && (!fin || length > 125) {
+            return Err(Error::other("invalid WebSocket control frame"));
+        }
+
+        if length > max_length as u64 {
+            return Err(Error::other(format!(
+                "WebSocket message longer than the limit of {} bytes",
+                MAX_WEBSOCKET_MESSAGE
+            )));
+        }
+
+        // servers never mask their frames, but unmask them if they do
+        let mut mask = [0; 4];
+
+        if header[1] & 0x80 != 0 {
+            reader.read_exact(&mut mask)?;
+        }
+
+        let mut payload = vec![0; length as usize];
+        reader.read_exact(&mut payload)?;
+        apply_mask(&mut payload, mask);
+
+        Ok(Self {
+            fin,
+            opcode,
+            payload,
+        })
+    }
+
+    /// Writes the frame as a client, which must mask its payload.
+    fn write(&self, writer: &mut impl Write, mask: [u8; 4]) -> Result<()> {
+        let mut frame = vec![(self.fin as u8) << 7 | self.opcode];
+        let length = self.payload.len();
+
+        match length {
+            0..=125 => frame.push(0x80 | length as u8),
+            126..=0xffff => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(length as u16).to_be_bytes());
+            }
+            _ => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(length as u64).to_be_bytes());
+            }
+        }
+
+        frame.extend_from_slice(&mask);
+
+        let start = frame.len();
+        frame.extend_from_slice(&self.payload);
+        apply_mask(&mut frame[start..], mask);
+
+        writer.write_all(&frame)?;
+        writer.flush()
+    }
+}
+
+/// Masks or unmasks a WebSocket payload, XORing it with the repeated mask.
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+}
+
+impl WebSocketClient {
+    pub fn new(url: &str, field: Option<&str>, rng: Pcg32State) -> Self {
+        Self {
+            url: url.to_owned(),
+            field: field.map(str::to_owned),
+            rng,
+            connection: None,
+            messages: 0,
+            position: Position::Unknown,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Connects to the server and upgrades the connection to a WebSocket.
+    fn connect(&mut self) -> Result<Connection> {
+        let key: Vec<u8> = (0..4)
+            .flat_map(|_| self.rng.next_u32().to_le_bytes())
+            .collect();
+
+        let url = split_url(&self.url, "ws")?;
+        let (mut reader, mut writer) = url.connect(None)?;
+
+        write!(
+            writer,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            url.path,
+            url.host,
+            base64(&key)
+        )?;
+
+        writer.flush()?;
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        if line.split_whitespace().nth(1) != Some("101") {
+            return Err(Error::other(format!(
+                "WebSocket upgrade refused: {}",
+                line.trim()
+            )));
+        }
+
+        // skip the rest of the response headers
+        while line.trim() != "" {
+            line.clear();
+
+            if reader.read_line(&mut line)? == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+        }
+
+        report!("[+] Connected to WebSocket server at {}.", self.url);
+
+        Ok((reader, writer))
+    }
+
+    pub fn read_output(&mut self) -> Result<Observation> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect()?);
+        }
+
+        while self.queue.is_empty() {
+            let message = self.read_message()?;
+
+            self.messages += 1;
+            self.position = Position::Message(self.messages);
+
+            match &self.field {
+                Some(path) => {
+                    let value = Json::parse(&message)?;
+
+                    match value.get(path) {
+                        Some(value) => value.outputs(&mut self.queue)?,
+                        None => return Err(Error::other(format!("no `{}` in message", path))),
+                    }
+                }
+                None => parse_observations(&message, &mut self.queue)?,
+            }
+        }
+
+        Ok(self.queue.pop_front().unwrap())
+    }
+
+    /// Reads the next data message, answering pings along the way.
+    fn read_message(&mut self) -> Result<String> {
+        let (reader, writer) = self.connection.as_mut().unwrap();
+        let mut message = vec![];
+
+        loop {
+            let frame = Frame::read(reader, MAX_WEBSOCKET_MESSAGE - message.len())?;
+
+            match frame.opcode {
+                0x0..=0x2 => {
+                    message.extend_from_slice(&frame.payload);
+
+                    if frame.fin {
+                        return Ok(String::from_utf8_lossy(&message).into_owned());
+                    }
+                }
+                0x8 => return Err(ErrorKind::UnexpectedEof.into()),
+                0x9 => {
+                    let pong = Frame {
+                        fin: true,
+                        opcode: 0xa,
+                        payload: frame.payload,
+                    };
+
+                    pong.write(writer, self.rng.next_u32().to_le_bytes())?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Encodes bytes as base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    bytes
+        .chunks(3)
+        .flat_map(|chunk| {
+            let word = chunk.iter().enumerate().fold(0u32, |word, (index, &byte)| {
+                word | (byte as u32) << (16 - 8 * index)
+            });
+
+            (0..4).map(move |index| match index <= chunk.len() {
+                true => ALPHABET[(word >> (18 - 6 * index) & 0x3f) as usize] as char,
+                false => '=',
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interruptible;
+    use std::io::BufReader;
+
+    /// "Hello" in a single frame, unmasked and masked (from RFC 6455).
+    const HELLO: [u8; 7] = [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f];
+    const MASKED_HELLO: [u8; 11] = [
+        0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+    ];
+
+    fn frame(fin: bool, opcode: u8, payload: &[u8]) -> Frame {
+        Frame {
+            fin,
+            opcode,
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn websocket_frames_are_read_and_unmasked() {
+        let hello = frame(true, 0x1, b"Hello");
+
+        assert_eq!(Frame::read(&mut &HELLO[..], 1 << 20).unwrap(), hello);
+        assert_eq!(Frame::read(&mut &MASKED_HELLO[..], 1 << 20).unwrap(), hello);
+
+        // the first fragment of a message, and a pong
+        let first = [0x01, 0x03, 0x48, 0x65, 0x6c];
+        assert_eq!(
+            Frame::read(&mut &first[..], 1 << 20).unwrap(),
+            frame(false, 0x1, b"Hel")
+        );
+
+        let pong = [
+            0x8a, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        assert_eq!(
+            Frame::read(&mut &pong[..], 1 << 20).unwrap(),
+            frame(true, 0xa, b"Hello")
+        );
+    }
+
+    #[test]
+    fn websocket_frames_are_written_masked() {
+        let mut written = vec![];
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+
+        frame(true, 0x1, b"Hello")
+            .write(&mut written, mask)
+            .unwrap();
+        assert_eq!(written, MASKED_HELLO);
+
+        // every length encoding round-trips, with the payload masked
+        for length in [0, 1, 125, 126, 127, 0xffff, 0x10000, 0x12345] {
+            let payload: Vec<u8> = (0..length).map(|index| index as u8).collect();
+            let sent = frame(true, 0x2, &payload);
+
+            let mut written = vec![];
+            sent.write(&mut written, mask).unwrap();
+
+            let header = match length {
+                0..=125 => 2,
+                126..=0xffff => 4,
+                _ => 10,
+            };
+
+            assert_eq!(written.len(), header + 4 + length);
+            assert_eq!(written[1] & 0x80, 0x80);
+            assert_eq!(written[header..header + 4], mask);
+
+            if length > 0 {
+                assert_ne!(written[header + 4..], payload[..]);
+            }
+
+            assert_eq!(Frame::read(&mut &written[..], 1 << 20).unwrap(), sent);
+        }
+    }
+
+    #[test]
+    fn invalid_websocket_frames_are_rejected() {
+        let huge = [0x82, 0x7f, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+        let long = [0x82, 0x7e, 0x01, 0x00];
+        let long_ping = [0x89, 0x7e, 0x00, 0x7e];
+        let fragmented_ping = [0x09, 0x00];
+
+        for frame in [&huge[..], &long, &long_ping, &fragmented_ping, &HELLO[..5]] {
+            assert!(
+                Frame::read(&mut &frame[..], 0xff).is_err(),
+                "{:02x?}",
+                frame
+            );
+        }
+
+        assert!(Frame::read(&mut &HELLO[..], 4).is_err());
+        assert!(Frame::read(&mut &HELLO[..], 5).is_ok());
+    }
+
+    #[test]
+    fn websocket_messages_are_reassembled_around_pings() {
+        let mut session = vec![0x01, 0x03, 0x48, 0x65, 0x6c];
+        session.extend_from_slice(&[0x89, 0x02, 0x68, 0x69]);
+        session.extend_from_slice(&[0x80, 0x02, 0x6c, 0x6f]);
+        session.extend_from_slice(&HELLO);
+        session.extend_from_slice(&[0x88, 0x00]);
+
+        let reader: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(session));
+        let mut client = WebSocketClient::new("ws://localhost/", None, Pcg32State::new(0, 1));
+        client.connection = Some((
+            BufReader::new(Interruptible(reader)),
+            Box::new(std::io::sink()),
+        ));
+
+        assert_eq!(client.read_message().unwrap(), "Hello");
+        assert_eq!(client.read_message().unwrap(), "Hello");
+        assert!(client.read_message().is_err());
+    }
+
+    #[test]
+    fn websocket_messages_are_capped_over_all_frames() {
+        let mut session = vec![];
+
+        for _ in 0..2 {
+            let half = frame(false, 0x2, &vec![0x30; MAX_WEBSOCKET_MESSAGE / 2]);
+            half.write(&mut session, [0; 4]).unwrap();
+        }
+
+        frame(true, 0x0, b"0").write(&mut session, [0; 4]).unwrap();
+
+        let reader: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(session));
+        let mut client = WebSocketClient::new("ws://localhost/", None, Pcg32State::new(0, 1));
+        client.connection = Some((
+            BufReader::new(Interruptible(reader)),
+            Box::new(std::io::sink()),
+        ));
+
+        assert!(client.read_message().is_err());
+    }
+}